@@ -21,8 +21,27 @@ pub enum Commands {
     #[command(arg_required_else_help = true)]
     #[command(short_flag = 'G')]
     Generate(Generate),
+    /// Maps Hamiltonian to another encoding, or writes it in another format
     #[command(short_flag = 'C')]
     Convert(Convert),
+    /// Writes sparse matrix of Hamiltonian in MatrixMarket format
+    Matrix(Matrix),
+    /// Computes the lowest eigenvalues of Hamiltonian
+    Eigen(Eigen),
+    /// Prints norms and cost metrics of Hamiltonian
+    Inspect(Inspect),
+    /// Writes data for the PREPARE oracle of LCU block encoding
+    Prepare(Prepare),
+    /// Writes Trotter circuit of Hamiltonian evolution in OpenQASM format
+    Circuit(Circuit),
+    /// Writes measurement circuits for groups of qubit-wise commuting terms
+    Measure(Measure),
+    /// Estimates energy from measured counts
+    Estimate(Estimate),
+    /// Removes small terms of Hamiltonian
+    Prune(Prune),
+    /// Compares two Hamiltonians.  Exits with code 4, if they differ
+    Diff(Diff),
 }
 
 #[derive(Debug, Args)]
@@ -39,6 +58,9 @@ pub struct Generate {
     pub num_terms:         u64,
     #[arg(long, default_value = "63")]
     pub max_orbital_index: u32,
+    /// Order of terms in the output
+    #[arg(long, default_value = "code")]
+    pub sort:              SortOrder,
     /// STDOUT, if not specified
     #[arg(long, short)]
     pub output_file:       Option<String>,
@@ -63,8 +85,332 @@ pub struct Convert {
     /// Pretty print the output if possible
     #[arg(short, long, default_value = "false")]
     pub pretty_print:    bool,
+    /// Required, unless the input and output encodings are the same
     #[arg(short, long)]
-    pub mapping:         Mapping,
+    pub mapping:         Option<Mapping>,
+    /// Order of terms in the output
+    #[arg(long, default_value = "code")]
+    pub sort:            SortOrder,
+    /// Read and map the input term by term, keeping a bounded number of
+    /// terms in memory and the rest in temporary files (json input only)
+    #[arg(long, default_value = "false")]
+    pub stream:          bool,
+    /// Number of input terms mapped at once in streaming mode
+    #[arg(long, default_value = "65536", requires = "stream")]
+    pub chunk_size:      usize,
+    /// Maximal number of output terms kept in memory in streaming mode
+    #[arg(long, default_value = "16777216", requires = "stream")]
+    pub max_terms:       usize,
+    /// Directory for temporary files in streaming mode.  System default, if
+    /// not specified
+    #[arg(long, requires = "stream")]
+    pub temp_dir:        Option<String>,
+    /// Number of electrons in the header of FCIDUMP output [default: as in
+    /// FCIDUMP input, or 0]
+    #[arg(long)]
+    pub nelec:           Option<u32>,
+    /// Twice the total spin projection in the header of FCIDUMP output
+    /// [default: as in FCIDUMP input, or 0]
+    #[arg(long, allow_negative_numbers = true)]
+    pub ms2:             Option<i32>,
+}
+
+#[derive(Debug, Args)]
+pub struct Matrix {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    /// Number of qubits (orbitals).  Minimal size fitting the Hamiltonian,
+    /// if not specified
+    #[arg(long, short)]
+    pub num_qubits:     Option<u16>,
+}
+
+#[derive(Debug, Args)]
+pub struct Eigen {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:      Option<String>,
+    #[arg(long, default_value = "fermions")]
+    pub input_encoding:  Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:    Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:     Option<String>,
+    #[arg(long, default_value = "json")]
+    pub output_format:   Format,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:    bool,
+    /// Number of qubits (orbitals).  Minimal size fitting the Hamiltonian,
+    /// if not specified
+    #[arg(long, short)]
+    pub num_qubits:      Option<u16>,
+    /// Number of the lowest eigenvalues to compute
+    #[arg(long, short = 'k', default_value = "1")]
+    pub num_eigenvalues: usize,
+    /// Restrict to the sector with fixed number of electrons (fermions only)
+    #[arg(long)]
+    pub num_particles:   Option<u16>,
+    /// Restrict to the sector with fixed 2*Sz (fermions only)
+    #[arg(long, requires = "num_particles", allow_negative_numbers = true)]
+    pub ms2:             Option<i32>,
+    /// Output eigenvectors as well
+    #[arg(long, default_value = "false")]
+    pub eigenvectors:    bool,
+    /// Maximal dimension of the Krylov space
+    #[arg(long, default_value = "300")]
+    pub max_iter:        usize,
+    #[arg(long, default_value = "1e-10")]
+    pub tolerance:       f64,
+}
+
+#[derive(Debug, Args)]
+pub struct Inspect {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    /// Print metrics as JSON instead of a table
+    #[arg(long, default_value = "false")]
+    pub json:           bool,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:   bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Prune {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    #[arg(long, default_value = "json")]
+    pub output_format:  Format,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:   bool,
+    /// Remove terms with absolute values of coefficients less than threshold
+    #[arg(
+        long,
+        short,
+        conflicts_with = "budget",
+        required_unless_present = "budget"
+    )]
+    pub threshold:      Option<f64>,
+    /// Remove the smallest terms with the sum of absolute values of
+    /// coefficients not exceeding budget
+    #[arg(long, short)]
+    pub budget:         Option<f64>,
+    /// Write removed terms to this file
+    #[arg(long)]
+    pub dropped_file:   Option<String>,
+    /// Order of terms in the output
+    #[arg(long, default_value = "code")]
+    pub sort:           SortOrder,
+}
+
+#[derive(Debug, Args)]
+pub struct Diff {
+    pub left_file:      String,
+    pub right_file:     String,
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    /// Absolute tolerance of coefficients
+    #[arg(long, default_value = "1e-12")]
+    pub atol:           f64,
+    /// Relative tolerance of coefficients
+    #[arg(long, default_value = "0.0")]
+    pub rtol:           f64,
+    /// Print the differences as JSON instead of a table
+    #[arg(long, default_value = "false")]
+    pub json:           bool,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:   bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Prepare {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:   Option<String>,
+    #[arg(long, default_value = "json")]
+    pub input_format: Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:  Option<String>,
+    /// Number of bits of keep thresholds of the alias table
+    #[arg(long, short = 'b', default_value = "10")]
+    pub num_bits:     u32,
+    /// Write the binary format instead of JSON
+    #[arg(long, default_value = "false")]
+    pub binary:       bool,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Circuit {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    /// Fermions are mapped to qubits with the Jordan-Wigner mapping
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    /// Number of qubits.  Minimal size fitting the Hamiltonian, if not
+    /// specified
+    #[arg(long, short)]
+    pub num_qubits:     Option<u16>,
+    /// Evolution time
+    #[arg(long, short, default_value = "1.0", allow_negative_numbers = true)]
+    pub time:           f64,
+    /// Number of Trotter steps
+    #[arg(long, short, default_value = "1")]
+    pub steps:          usize,
+    /// Order of the product formula
+    #[arg(long, default_value = "1")]
+    pub order:          TrotterOrder,
+    #[arg(long, default_value = "3")]
+    pub qasm_version:   QasmVersion,
+}
+
+#[derive(Debug, Args)]
+pub struct Measure {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    /// Fermions are mapped to qubits with the Jordan-Wigner mapping
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// Writes <PREFIX>.json and a circuit <PREFIX>_<i>.qasm for each group
+    #[arg(long, short)]
+    pub output_prefix:  String,
+    /// Number of qubits.  Minimal size fitting the Hamiltonian, if not
+    /// specified
+    #[arg(long, short)]
+    pub num_qubits:     Option<u16>,
+    #[arg(long, default_value = "3")]
+    pub qasm_version:   QasmVersion,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:   bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Estimate {
+    /// STDIN, if not specified
+    #[arg(long, short)]
+    pub input_file:     Option<String>,
+    /// Fermions are mapped to qubits with the Jordan-Wigner mapping
+    #[arg(long, default_value = "qubits")]
+    pub input_encoding: Encoding,
+    #[arg(long, default_value = "json")]
+    pub input_format:   Format,
+    /// JSON files with counts: {"basis": "XZ..", "counts": {"01..": 123}}.
+    /// Each term is estimated from the first compatible basis
+    #[arg(long, short, required = true, num_args = 1..)]
+    pub counts:         Vec<String>,
+    /// Order of bits in bitstrings: big-endian means the last character is
+    /// the outcome of qubit 0
+    #[arg(long, default_value = "big-endian")]
+    pub bit_order:      BitOrder,
+    /// STDOUT, if not specified
+    #[arg(long, short)]
+    pub output_file:    Option<String>,
+    /// Pretty print the output if possible
+    #[arg(short, long, default_value = "false")]
+    pub pretty_print:   bool,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Increasing order of codes
+    Code,
+    /// Decreasing absolute values of coefficients
+    Magnitude,
+}
+
+impl From<SortOrder> for f2q::terms::SortOrder {
+    fn from(value: SortOrder) -> Self {
+        match value {
+            SortOrder::Code => Self::Code,
+            SortOrder::Magnitude => Self::Magnitude,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+impl From<BitOrder> for f2q::measure::BitOrder {
+    fn from(value: BitOrder) -> Self {
+        match value {
+            BitOrder::BigEndian => Self::BigEndian,
+            BitOrder::LittleEndian => Self::LittleEndian,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrotterOrder {
+    #[value(name = "1")]
+    First,
+    #[value(name = "2")]
+    Second,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QasmVersion {
+    #[value(name = "2")]
+    V2,
+    #[value(name = "3")]
+    V3,
+}
+
+impl From<QasmVersion> for f2q::circuit::QasmVersion {
+    fn from(value: QasmVersion) -> Self {
+        match value {
+            QasmVersion::V2 => Self::V2,
+            QasmVersion::V3 => Self::V3,
+        }
+    }
 }
 
 #[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
@@ -107,6 +453,14 @@ pub enum Format {
     Json,
     Yaml,
     Toml,
+    /// Molecular integrals in the FCIDUMP format (fermions only)
+    Fcidump,
+    /// String form of an OpenFermion `FermionOperator` or `QubitOperator`
+    #[value(name = "openfermion")]
+    OpenFermion,
+    /// Qiskit `SparsePauliOp` as JSON lists of big-endian Pauli labels and
+    /// coefficients (qubits only)
+    Qiskit,
 }
 
 impl std::fmt::Display for Format {
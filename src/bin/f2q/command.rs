@@ -1,31 +1,76 @@
 use std::{
     fs::File,
     io::{
+        BufRead,
+        BufReader,
         BufWriter,
+        Read,
         Write,
     },
 };
 
 use f2q::{
     code::Code,
-    terms::SumRepr,
+    fcidump::{
+        Fcidump,
+        Header,
+    },
+    openfermion::{
+        parse_fermion_operator,
+        parse_qubit_operator,
+        write_fermion_operator,
+        write_qubit_operator,
+    },
+    qiskit::SparsePauliOp,
+    terms::{
+        FermiSum,
+        PauliSum,
+        Sorted,
+        SumRepr,
+        Terms,
+    },
 };
 use num::Float;
-use serde::Serialize;
+use serde::{
+    de::{
+        DeserializeOwned,
+        DeserializeSeed,
+    },
+    Serialize,
+};
 
 use crate::{
     cli::{
+        Circuit,
         Convert,
+        Diff,
+        Eigen,
         Encoding,
+        Estimate,
         Format,
         Generate,
+        Inspect,
         Mapping,
+        Matrix,
+        Measure,
+        Prepare,
+        Prune,
+        SortOrder,
     },
     errors::Error,
 };
 
+mod circuit;
 mod convert;
+mod diff;
+mod eigen;
+mod estimate;
 mod generate;
+mod inspect;
+mod matrix;
+mod measure;
+mod prepare;
+mod prune;
 
 pub fn generate(args: &Generate) -> Result<(), Error> {
     match args.encoding {
@@ -38,64 +83,451 @@ pub fn generate(args: &Generate) -> Result<(), Error> {
 }
 
 pub fn convert(args: &Convert) -> Result<(), Error> {
+    if args.input_encoding == args.output_encoding {
+        return match args.input_encoding {
+            Encoding::Fermions => convert::fermions(args),
+            Encoding::Qubits => convert::qubits(args),
+        };
+    }
     match args.mapping {
-        Mapping::JordanWigner => convert::jordan_wigner(args)?,
+        Some(Mapping::JordanWigner) => convert::jordan_wigner(args)?,
+        None => {
+            return Err(Error::CmdArgs {
+                msg: "mapping must be specified to convert between encodings"
+                    .to_string(),
+            })
+        }
+    }
+    Ok(())
+}
+
+pub fn matrix(args: &Matrix) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => matrix::fermions(args),
+        Encoding::Qubits => matrix::qubits(args),
+    }
+}
+
+pub fn eigen(args: &Eigen) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => eigen::fermions(args),
+        Encoding::Qubits => eigen::qubits(args),
+    }
+}
+
+pub fn inspect(args: &Inspect) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => inspect::fermions(args),
+        Encoding::Qubits => inspect::qubits(args),
+    }
+}
+
+pub fn prune(args: &Prune) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => prune::fermions(args),
+        Encoding::Qubits => prune::qubits(args),
+    }
+}
+
+pub fn diff(args: &Diff) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => diff::fermions(args),
+        Encoding::Qubits => diff::qubits(args),
+    }
+}
+
+pub fn prepare(args: &Prepare) -> Result<(), Error> {
+    prepare::qubits(args)
+}
+
+pub fn circuit(args: &Circuit) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => circuit::fermions(args),
+        Encoding::Qubits => circuit::qubits(args),
+    }
+}
+
+pub fn measure(args: &Measure) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => measure::fermions(args),
+        Encoding::Qubits => measure::qubits(args),
+    }
+}
+
+pub fn estimate(args: &Estimate) -> Result<(), Error> {
+    match args.input_encoding {
+        Encoding::Fermions => estimate::fermions(args),
+        Encoding::Qubits => estimate::qubits(args),
+    }
+}
+
+/// Formats of sums of terms that are not handled by serde.
+fn is_text_format(format: Format) -> bool {
+    matches!(
+        format,
+        Format::Fcidump | Format::OpenFermion | Format::Qiskit
+    )
+}
+
+fn deserialize_sumrepr<T, K>(
+    input_path: Option<&str>,
+    format: Format,
+) -> Result<SumRepr<T, K>, Error>
+where
+    T: Float,
+    K: Code,
+    SumRepr<T, K>: DeserializeOwned + TextFormat,
+{
+    if let Some(path) = input_path {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        deserialize_sumrepr_from_reader(reader, format)
+    } else {
+        let stdin = std::io::stdin().lock();
+        let reader = BufReader::new(stdin);
+        deserialize_sumrepr_from_reader(reader, format)
+    }
+}
+
+/// Read fermionic Hamiltonian, keeping the header of FCIDUMP input.
+fn deserialize_fermisum(
+    input_path: Option<&str>,
+    format: Format,
+) -> Result<(FermiSum, Option<Header>), Error> {
+    if format != Format::Fcidump {
+        return Ok((deserialize_sumrepr(input_path, format)?, None));
+    }
+
+    let mut buf = String::new();
+    if let Some(path) = input_path {
+        File::open(path)?.read_to_string(&mut buf)?;
+    } else {
+        std::io::stdin().lock().read_to_string(&mut buf)?;
+    }
+    let fcidump: Fcidump = buf.parse()?;
+    let mut repr = FermiSum::new();
+    (&fcidump).add_to(&mut repr)?;
+    Ok((repr, Some(fcidump.header)))
+}
+
+/// Header of FCIDUMP output: the header of FCIDUMP input, if any, with the
+/// number of electrons and spin projection replaced, if specified.
+fn fcidump_header(
+    input_header: Option<Header>,
+    nelec: Option<u32>,
+    ms2: Option<i32>,
+) -> Header {
+    let mut header = input_header.unwrap_or_else(|| Header::new(0, 0));
+    if let Some(nelec) = nelec {
+        header.nelec = nelec;
+    }
+    if let Some(ms2) = ms2 {
+        header.ms2 = ms2;
+    }
+    header
+}
+
+fn deserialize_sumrepr_from_reader<T, K, R>(
+    reader: R,
+    format: Format,
+) -> Result<SumRepr<T, K>, Error>
+where
+    T: Float,
+    K: Code,
+    SumRepr<T, K>: DeserializeOwned + TextFormat,
+    R: BufRead,
+{
+    Ok(match format {
+        Format::Json => serde_json::from_reader(reader)?,
+        Format::Toml => {
+            let mut reader = reader;
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf)?;
+            toml::from_str(&buf)?
+        }
+        Format::Yaml => serde_yaml::from_reader(reader)?,
+        Format::Fcidump | Format::OpenFermion | Format::Qiskit => {
+            let mut reader = reader;
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf)?;
+            SumRepr::read_text(&buf, format)?
+        }
+    })
+}
+
+fn deserialize_seed<S>(
+    seed: S,
+    input_path: Option<&str>,
+    format: Format,
+) -> Result<(), Error>
+where
+    S: for<'de> DeserializeSeed<'de, Value = ()>,
+{
+    if let Some(path) = input_path {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        deserialize_seed_from_reader(seed, reader, format)
+    } else {
+        let stdin = std::io::stdin().lock();
+        let reader = BufReader::new(stdin);
+        deserialize_seed_from_reader(seed, reader, format)
     }
+}
+
+fn deserialize_seed_from_reader<S, R>(
+    seed: S,
+    reader: R,
+    format: Format,
+) -> Result<(), Error>
+where
+    S: for<'de> DeserializeSeed<'de, Value = ()>,
+    R: BufRead,
+{
+    // Other deserializers buffer the whole input.
+    if format != Format::Json {
+        return Err(Error::CmdArgs {
+            msg: format!(
+                "streaming conversion supports only json input, found: \
+                 {format}"
+            ),
+        });
+    }
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    seed.deserialize(&mut de)?;
+    de.end()?;
+
     Ok(())
 }
 
+fn min_num_orbitals(repr: &FermiSum) -> Result<u16, Error> {
+    let num_orbitals = repr.num_orbitals();
+    u16::try_from(num_orbitals).map_err(|_| Error::CmdArgs {
+        msg: format!("too many orbitals: {num_orbitals}"),
+    })
+}
+
+fn create_writer(
+    output_path: Option<&str>
+) -> Result<BufWriter<Box<dyn Write>>, Error> {
+    let writer: Box<dyn Write> = if let Some(path) = output_path {
+        Box::new(File::create(path)?)
+    } else {
+        Box::new(std::io::stdout().lock())
+    };
+    Ok(BufWriter::new(writer))
+}
+
+/// Absolute tolerance of spatial integrals written in the FCIDUMP format.
+const FCIDUMP_TOLERANCE: f64 = 1e-10;
+
+/// Sums of terms that can be read and written in text formats.
+trait TextFormat: Sized {
+    fn read_text(
+        input: &str,
+        format: Format,
+    ) -> Result<Self, Error>;
+
+    fn write_text<W: Write>(
+        &self,
+        writer: &mut W,
+        format: Format,
+        pretty_print: bool,
+        fcidump_header: Option<&Header>,
+    ) -> Result<(), Error>;
+}
+
+fn encoding_error(
+    format: Format,
+    encoding: &str,
+) -> Error {
+    Error::CmdArgs {
+        msg: format!("{format} format must be used with {encoding} encoding"),
+    }
+}
+
+impl TextFormat for FermiSum {
+    fn read_text(
+        input: &str,
+        format: Format,
+    ) -> Result<Self, Error> {
+        match format {
+            Format::Fcidump => {
+                let fcidump: Fcidump = input.parse()?;
+                let mut repr = FermiSum::new();
+                fcidump.add_to(&mut repr)?;
+                Ok(repr)
+            }
+            Format::OpenFermion => Ok(parse_fermion_operator(input)?),
+            Format::Qiskit => Err(encoding_error(format, "qubits")),
+            Format::Json | Format::Yaml | Format::Toml => {
+                unreachable!("serde format: {format}")
+            }
+        }
+    }
+
+    fn write_text<W: Write>(
+        &self,
+        writer: &mut W,
+        format: Format,
+        _pretty_print: bool,
+        fcidump_header: Option<&Header>,
+    ) -> Result<(), Error> {
+        match format {
+            Format::Fcidump => {
+                let header = fcidump_header
+                    .cloned()
+                    .unwrap_or_else(|| Header::new(0, 0));
+                Fcidump::from_terms(self, header, FCIDUMP_TOLERANCE)?
+                    .write(writer)?;
+            }
+            Format::OpenFermion => write_fermion_operator(self, writer)?,
+            Format::Qiskit => return Err(encoding_error(format, "qubits")),
+            Format::Json | Format::Yaml | Format::Toml => {
+                unreachable!("serde format: {format}")
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TextFormat for PauliSum {
+    fn read_text(
+        input: &str,
+        format: Format,
+    ) -> Result<Self, Error> {
+        match format {
+            Format::OpenFermion => Ok(parse_qubit_operator(input)?),
+            Format::Qiskit => {
+                let op: SparsePauliOp = serde_json::from_str(input)?;
+                let mut repr = PauliSum::new();
+                op.add_to(&mut repr)?;
+                Ok(repr)
+            }
+            Format::Fcidump => Err(encoding_error(format, "fermions")),
+            Format::Json | Format::Yaml | Format::Toml => {
+                unreachable!("serde format: {format}")
+            }
+        }
+    }
+
+    fn write_text<W: Write>(
+        &self,
+        writer: &mut W,
+        format: Format,
+        pretty_print: bool,
+        _fcidump_header: Option<&Header>,
+    ) -> Result<(), Error> {
+        match format {
+            Format::OpenFermion => write_qubit_operator(self, writer)?,
+            Format::Qiskit => {
+                let op = SparsePauliOp::from_terms(self, None)?;
+                if pretty_print {
+                    serde_json::to_writer_pretty(&mut *writer, &op)?;
+                } else {
+                    serde_json::to_writer(&mut *writer, &op)?;
+                }
+                writeln!(writer)?;
+            }
+            Format::Fcidump => return Err(encoding_error(format, "fermions")),
+            Format::Json | Format::Yaml | Format::Toml => {
+                unreachable!("serde format: {format}")
+            }
+        }
+        Ok(())
+    }
+}
+
 fn serialize_sumrepr<T, K>(
     repr: &SumRepr<T, K>,
     output_path: Option<&str>,
     format: Format,
     pretty_print: bool,
+    sort: SortOrder,
+    fcidump_header: Option<&Header>,
 ) -> Result<(), Error>
 where
     T: Float,
-    K: Code,
-    SumRepr<T, K>: Serialize,
+    K: Code + Ord,
+    for<'a> Sorted<'a, T, K>: Serialize,
+    SumRepr<T, K>: TextFormat,
+{
+    if is_text_format(format) {
+        if sort != SortOrder::Code {
+            return Err(Error::CmdArgs {
+                msg: format!(
+                    "{format} output supports only the order of codes"
+                ),
+            });
+        }
+        let mut writer = create_writer(output_path)?;
+        repr.write_text(&mut writer, format, pretty_print, fcidump_header)?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    serialize_value(
+        &repr.sorted(sort.into()),
+        output_path,
+        format,
+        pretty_print,
+    )
+}
+
+fn serialize_value<S>(
+    value: &S,
+    output_path: Option<&str>,
+    format: Format,
+    pretty_print: bool,
+) -> Result<(), Error>
+where
+    S: Serialize,
 {
     if let Some(path) = output_path {
         let file = File::create(path)?;
         let writer = BufWriter::new(file);
-        serialize_sumrepr_to_writer(repr, writer, format, pretty_print)
+        serialize_value_to_writer(value, writer, format, pretty_print)
     } else {
         let stdout = std::io::stdout().lock();
         let writer = BufWriter::new(stdout);
-        serialize_sumrepr_to_writer(repr, writer, format, pretty_print)
+        serialize_value_to_writer(value, writer, format, pretty_print)
     }
 }
 
-fn serialize_sumrepr_to_writer<T, K, W>(
-    repr: &SumRepr<T, K>,
+fn serialize_value_to_writer<S, W>(
+    value: &S,
     writer: BufWriter<W>,
     format: Format,
     pretty_print: bool,
 ) -> Result<(), Error>
 where
-    T: Float,
-    K: Code,
-    SumRepr<T, K>: Serialize,
+    S: Serialize,
     W: Write,
 {
     match format {
         Format::Json => {
             if pretty_print {
-                serde_json::to_writer_pretty(writer, repr)?;
+                serde_json::to_writer_pretty(writer, value)?;
             } else {
-                serde_json::to_writer(writer, repr)?;
+                serde_json::to_writer(writer, value)?;
             }
         }
-        Format::Yaml => serde_yaml::to_writer(writer, repr)?,
+        Format::Yaml => serde_yaml::to_writer(writer, value)?,
         Format::Toml => {
             let mut writer = writer;
             let buf = if pretty_print {
-                toml::to_string_pretty(&repr)?
+                toml::to_string_pretty(value)?
             } else {
-                toml::to_string(&repr)?
+                toml::to_string(value)?
             };
             write!(writer, "{buf}")?;
         }
+        Format::Fcidump | Format::OpenFermion | Format::Qiskit => {
+            return Err(Error::CmdArgs {
+                msg: format!(
+                    "{format} output is supported only for sums of terms"
+                ),
+            })
+        }
     };
 
     Ok(())
@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use f2q::{
+    circuit::{
+        self,
+        Trotter,
+    },
+    map::JordanWigner,
+    terms::{
+        FermiSum,
+        PauliSum,
+        Terms,
+    },
+};
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+};
+use crate::{
+    cli::{
+        Circuit,
+        TrotterOrder,
+    },
+    errors::Error,
+};
+
+pub fn fermions(args: &Circuit) -> Result<(), Error> {
+    let in_repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let mut repr = PauliSum::with_capacity(in_repr.len() * 4);
+    JordanWigner::new(&in_repr).add_to(&mut repr)?;
+    write_circuit(&repr, args)
+}
+
+pub fn qubits(args: &Circuit) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    write_circuit(&repr, args)
+}
+
+fn write_circuit(
+    repr: &PauliSum,
+    args: &Circuit,
+) -> Result<(), Error> {
+    let order = match args.order {
+        TrotterOrder::First => circuit::TrotterOrder::First,
+        TrotterOrder::Second => circuit::TrotterOrder::Second,
+    };
+    let circ = Trotter::new(args.time, args.steps)
+        .order(order)
+        .num_qubits(args.num_qubits)
+        .circuit(repr)?;
+
+    let mut writer = create_writer(args.output_file.as_deref())?;
+    circ.write_qasm(&mut writer, args.qasm_version.into())?;
+    writer.flush()?;
+    Ok(())
+}
@@ -1,20 +1,76 @@
+use std::path::PathBuf;
+
 use f2q::{
     map::JordanWigner,
+    stream::{
+        FermiTerms,
+        PauliTerms,
+        SpillSum,
+    },
     terms::{
+        FermiSum,
         PauliSum,
-        Terms,
     },
 };
 
-use super::serialize_sumrepr;
+use super::{
+    deserialize_fermisum,
+    deserialize_seed,
+    deserialize_sumrepr,
+    fcidump_header,
+    is_text_format,
+    serialize_sumrepr,
+    serialize_value,
+};
 use crate::{
     cli::{
         Convert,
         Encoding,
+        SortOrder,
     },
     errors::Error,
+    log_or_eprintln_if_verbose,
 };
 
+pub fn fermions(args: &Convert) -> Result<(), Error> {
+    check_no_stream(args)?;
+    let (repr, input_header) =
+        deserialize_fermisum(args.input_file.as_deref(), args.input_format)?;
+    let header = fcidump_header(input_header, args.nelec, args.ms2);
+    serialize_sumrepr(
+        &repr,
+        args.output_file.as_deref(),
+        args.output_format,
+        args.pretty_print,
+        args.sort,
+        Some(&header),
+    )
+}
+
+pub fn qubits(args: &Convert) -> Result<(), Error> {
+    check_no_stream(args)?;
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    serialize_sumrepr(
+        &repr,
+        args.output_file.as_deref(),
+        args.output_format,
+        args.pretty_print,
+        args.sort,
+        None,
+    )
+}
+
+fn check_no_stream(args: &Convert) -> Result<(), Error> {
+    if args.stream {
+        return Err(Error::CmdArgs {
+            msg: "streaming conversion requires a mapping between encodings"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
 pub fn jordan_wigner(args: &Convert) -> Result<(), Error> {
     if !(args.input_encoding == Encoding::Fermions
         && args.output_encoding == Encoding::Qubits)
@@ -26,62 +82,88 @@ pub fn jordan_wigner(args: &Convert) -> Result<(), Error> {
         });
     }
 
-    let in_repr = jordan_wigner::parse_input(args)?;
+    if args.stream {
+        return jordan_wigner_stream(args);
+    }
+
+    let in_repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
     let mut out_repr = PauliSum::with_capacity(in_repr.len() * 4);
-    JordanWigner::new(&in_repr).add_to(&mut out_repr)?;
+    JordanWigner::new(&in_repr).par_add_to(&mut out_repr)?;
     serialize_sumrepr(
         &out_repr,
         args.output_file.as_deref(),
         args.output_format,
         args.pretty_print,
+        args.sort,
+        None,
     )
 }
 
-mod jordan_wigner {
-
-    use std::{
-        fs::File,
-        io::{
-            BufRead,
-            BufReader,
-        },
-    };
-
-    use f2q::terms::FermiSum;
+fn jordan_wigner_stream(args: &Convert) -> Result<(), Error> {
+    if is_text_format(args.output_format) {
+        return Err(Error::CmdArgs {
+            msg: format!(
+                "streaming conversion supports only json, yaml and toml \
+                 output, found: {}",
+                args.output_format
+            ),
+        });
+    }
+    if args.sort != SortOrder::Code {
+        return Err(Error::CmdArgs {
+            msg: "streaming conversion supports only the order of codes"
+                .to_string(),
+        });
+    }
 
-    use crate::{
-        cli::{
-            Convert,
-            Format,
-        },
-        errors::Error,
-    };
+    let chunk_size = args.chunk_size.max(1);
+    let temp_dir = args
+        .temp_dir
+        .as_ref()
+        .map_or_else(std::env::temp_dir, PathBuf::from);
+    let mut spill = SpillSum::new(temp_dir, args.max_terms);
+    let mut chunk = FermiSum::with_capacity(chunk_size);
+    let mut failure = None;
 
-    pub fn parse_input(args: &Convert) -> Result<FermiSum, Error> {
-        if let Some(path) = &args.input_file {
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-            parse_input_reader(reader, args)
-        } else {
-            let stdin = std::io::stdin().lock();
-            let reader = BufReader::new(stdin);
-            parse_input_reader(reader, args)
+    let seed = FermiTerms::new(|coeff, code| {
+        chunk.add_term(code, coeff);
+        if chunk.len() < chunk_size {
+            return Ok(());
         }
-    }
-
-    fn parse_input_reader<R: BufRead>(
-        reader: R,
-        args: &Convert,
-    ) -> Result<FermiSum, Error> {
-        Ok(match args.input_format {
-            Format::Json => serde_json::from_reader(reader)?,
-            Format::Toml => {
-                let mut reader = reader;
-                let mut buf = String::new();
-                reader.read_to_string(&mut buf)?;
-                toml::from_str(&buf)?
-            }
-            Format::Yaml => serde_yaml::from_reader(reader)?,
+        flush_chunk(&mut chunk, &mut spill).map_err(|err| {
+            let msg = err.to_string();
+            failure = Some(err);
+            msg
         })
+    });
+    let result =
+        deserialize_seed(seed, args.input_file.as_deref(), args.input_format);
+    if let Some(err) = failure {
+        return Err(err);
     }
+    result?;
+    flush_chunk(&mut chunk, &mut spill)?;
+
+    log_or_eprintln_if_verbose(&format!(
+        "Spilled {} runs to disk",
+        spill.num_runs()
+    ));
+    serialize_value(
+        &PauliTerms::new(spill.finish()?),
+        args.output_file.as_deref(),
+        args.output_format,
+        args.pretty_print,
+    )
+}
+
+fn flush_chunk(
+    chunk: &mut FermiSum,
+    spill: &mut SpillSum,
+) -> Result<(), Error> {
+    let mut out_repr = PauliSum::with_capacity(chunk.len() * 4);
+    JordanWigner::new(chunk).par_add_to(&mut out_repr)?;
+    spill.try_extend(out_repr)?;
+    *chunk = FermiSum::with_capacity(chunk.capacity());
+    Ok(())
 }
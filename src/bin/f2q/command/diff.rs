@@ -0,0 +1,152 @@
+use std::{
+    fmt::Display,
+    io::Write,
+};
+
+use f2q::{
+    code::Code,
+    terms::{
+        Diff as TermsDiff,
+        FermiSum,
+        PauliSum,
+        SumRepr,
+    },
+};
+use serde::Serialize;
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+    serialize_value,
+};
+use crate::{
+    cli::{
+        Diff,
+        Format,
+    },
+    errors::Error,
+    log_or_eprintln_if_verbose,
+};
+
+#[derive(Serialize)]
+struct Term {
+    code:  String,
+    value: f64,
+}
+
+#[derive(Serialize)]
+struct Changed {
+    code:  String,
+    left:  f64,
+    right: f64,
+}
+
+#[derive(Serialize)]
+struct Report {
+    left_only:  Vec<Term>,
+    right_only: Vec<Term>,
+    changed:    Vec<Changed>,
+}
+
+pub fn fermions(args: &Diff) -> Result<(), Error> {
+    let left: FermiSum =
+        deserialize_sumrepr(Some(&args.left_file), args.input_format)?;
+    let right: FermiSum =
+        deserialize_sumrepr(Some(&args.right_file), args.input_format)?;
+    diff(&left, &right, args)
+}
+
+pub fn qubits(args: &Diff) -> Result<(), Error> {
+    let left: PauliSum =
+        deserialize_sumrepr(Some(&args.left_file), args.input_format)?;
+    let right: PauliSum =
+        deserialize_sumrepr(Some(&args.right_file), args.input_format)?;
+    diff(&left, &right, args)
+}
+
+fn diff<K>(
+    left: &SumRepr<f64, K>,
+    right: &SumRepr<f64, K>,
+    args: &Diff,
+) -> Result<(), Error>
+where
+    K: Code + Ord + Display,
+{
+    let diff = left.diff(right, args.atol, args.rtol);
+    log_or_eprintln_if_verbose(&format!(
+        "Compared {} and {} terms",
+        left.len(),
+        right.len()
+    ));
+
+    if args.json {
+        serialize_value(
+            &report(&diff),
+            args.output_file.as_deref(),
+            Format::Json,
+            args.pretty_print,
+        )?;
+    } else {
+        write_lines(&diff, args)?;
+    }
+
+    if diff.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Differ {
+            num_terms: diff.len(),
+        })
+    }
+}
+
+fn report<K>(diff: &TermsDiff<f64, K>) -> Report
+where
+    K: Display,
+{
+    let terms = |terms: &[(f64, K)]| {
+        terms
+            .iter()
+            .map(|(value, code)| Term {
+                code:  code.to_string(),
+                value: *value,
+            })
+            .collect()
+    };
+    Report {
+        left_only:  terms(&diff.left_only),
+        right_only: terms(&diff.right_only),
+        changed:    diff
+            .changed
+            .iter()
+            .map(|(left, right, code)| Changed {
+                code:  code.to_string(),
+                left:  *left,
+                right: *right,
+            })
+            .collect(),
+    }
+}
+
+/// Write a line for each term that differs, prefixed with `-` if the term is
+/// only in the left sum, `+` if only in the right one, and `~` if the
+/// coefficients differ.
+fn write_lines<K>(
+    diff: &TermsDiff<f64, K>,
+    args: &Diff,
+) -> Result<(), Error>
+where
+    K: Display,
+{
+    let mut writer = create_writer(args.output_file.as_deref())?;
+    for (value, code) in &diff.left_only {
+        writeln!(writer, "- {code}  {value}")?;
+    }
+    for (value, code) in &diff.right_only {
+        writeln!(writer, "+ {code}  {value}")?;
+    }
+    for (left, right, code) in &diff.changed {
+        writeln!(writer, "~ {code}  {left} -> {right}")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
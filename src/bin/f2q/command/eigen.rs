@@ -0,0 +1,102 @@
+use f2q::{
+    eigen::Lanczos,
+    matrix::{
+        Coo,
+        Csr,
+        Sector,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+    },
+};
+use num::Complex;
+use serde::Serialize;
+
+use super::{
+    deserialize_sumrepr,
+    min_num_orbitals,
+    serialize_value,
+};
+use crate::{
+    cli::Eigen,
+    errors::Error,
+};
+
+#[derive(Serialize)]
+struct EigenOutput {
+    eigenvalues:  Vec<f64>,
+    /// Basis states of the sector (as integers), if restricted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    basis:        Option<Vec<usize>>,
+    /// Eigenvectors as lists of pairs: [re, im]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eigenvectors: Option<Vec<Vec<[f64; 2]>>>,
+}
+
+pub fn fermions(args: &Eigen) -> Result<(), Error> {
+    let repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let num_orbitals = match args.num_qubits {
+        Some(n) => n,
+        None => min_num_orbitals(&repr)?,
+    };
+
+    if let Some(num_particles) = args.num_particles {
+        let sector = Sector::new(num_orbitals, num_particles, args.ms2)?;
+        if sector.dim() == 0 {
+            return Err(Error::CmdArgs {
+                msg: "sector is empty".to_string(),
+            });
+        }
+        let mtx = Csr::from(Coo::from_fermions_in_sector(&repr, &sector)?);
+        solve(&mtx, Some(sector.states().to_vec()), args)
+    } else {
+        let mtx = Csr::from(Coo::from_fermions(&repr, num_orbitals)?);
+        solve(&mtx, None, args)
+    }
+}
+
+pub fn qubits(args: &Eigen) -> Result<(), Error> {
+    if args.num_particles.is_some() {
+        return Err(Error::CmdArgs {
+            msg: "particle-number sectors are available only for fermions \
+                  encoding"
+                .to_string(),
+        });
+    }
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let num_qubits = args.num_qubits.unwrap_or(repr.num_qubits().into());
+    let mtx = Csr::from(Coo::from_paulis(&repr, num_qubits)?);
+    solve(&mtx, None, args)
+}
+
+fn solve(
+    mtx: &Csr<Complex<f64>>,
+    basis: Option<Vec<usize>>,
+    args: &Eigen,
+) -> Result<(), Error> {
+    let eigen = Lanczos::new(args.num_eigenvalues)
+        .max_iter(args.max_iter)
+        .tolerance(args.tolerance)
+        .eigenvectors(args.eigenvectors)
+        .solve(mtx)?;
+
+    let output = EigenOutput {
+        eigenvalues:  eigen.values,
+        basis:        basis.filter(|_| args.eigenvectors),
+        eigenvectors: eigen.vectors.map(|vectors| {
+            vectors
+                .iter()
+                .map(|v| v.iter().map(|x| [x.re, x.im]).collect())
+                .collect()
+        }),
+    };
+    serialize_value(
+        &output,
+        args.output_file.as_deref(),
+        args.output_format,
+        args.pretty_print,
+    )
+}
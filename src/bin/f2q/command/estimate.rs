@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+};
+
+use f2q::{
+    code::qubits::Paulis,
+    map::JordanWigner,
+    measure::{
+        self,
+        parse_outcome,
+        Counts,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        Terms,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    deserialize_sumrepr,
+    serialize_value,
+};
+use crate::{
+    cli::{
+        Estimate,
+        Format,
+    },
+    errors::Error,
+};
+
+#[derive(Deserialize)]
+struct CountsInput {
+    basis:  Paulis,
+    counts: HashMap<String, u64>,
+}
+
+#[derive(Serialize)]
+struct EstimateOutput {
+    energy:    f64,
+    variance:  f64,
+    std_error: f64,
+    terms:     Vec<TermOutput>,
+}
+
+#[derive(Serialize)]
+struct TermOutput {
+    code:        Paulis,
+    value:       f64,
+    expectation: f64,
+    variance:    f64,
+    /// Path to the counts file the term was estimated from
+    counts:      String,
+}
+
+pub fn fermions(args: &Estimate) -> Result<(), Error> {
+    let in_repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let mut repr = PauliSum::with_capacity(in_repr.len() * 4);
+    JordanWigner::new(&in_repr).add_to(&mut repr)?;
+    write_estimate(&repr, args)
+}
+
+pub fn qubits(args: &Estimate) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    write_estimate(&repr, args)
+}
+
+fn read_counts(
+    path: &str,
+    args: &Estimate,
+) -> Result<Counts, Error> {
+    let input: CountsInput =
+        serde_json::from_reader(BufReader::new(File::open(path)?))?;
+
+    let min_len = usize::from(input.basis.min_register_size());
+    let mut counts = Counts::new(input.basis);
+    let mut bit_len = None;
+    for (bits, count) in &input.counts {
+        // Bitstrings of different lengths are ambiguous in big-endian order
+        if bits.len() < min_len
+            || *bit_len.get_or_insert(bits.len()) != bits.len()
+        {
+            return Err(Error::CmdArgs {
+                msg: format!(
+                    "{path}: bitstrings must have the same length, at least \
+                     {min_len}: {bits:?}"
+                ),
+            });
+        }
+        counts.add(parse_outcome(bits, args.bit_order.into())?, *count);
+    }
+    Ok(counts)
+}
+
+fn write_estimate(
+    repr: &PauliSum,
+    args: &Estimate,
+) -> Result<(), Error> {
+    let counts = args
+        .counts
+        .iter()
+        .map(|path| read_counts(path, args))
+        .collect::<Result<Vec<_>, _>>()?;
+    let est = measure::estimate(repr, &counts)?;
+
+    let output = EstimateOutput {
+        energy:    est.energy,
+        variance:  est.variance,
+        std_error: est.variance.sqrt(),
+        terms:     est
+            .terms
+            .iter()
+            .map(|term| TermOutput {
+                code:        term.code,
+                value:       term.coeff,
+                expectation: term.expectation,
+                variance:    term.variance,
+                counts:      args.counts[term.counts].clone(),
+            })
+            .collect(),
+    };
+    serialize_value(
+        &output,
+        args.output_file.as_deref(),
+        Format::Json,
+        args.pretty_print,
+    )
+}
@@ -83,6 +83,8 @@ fn fermions_random(args: &Generate) -> Result<(), Error> {
         args.output_file.as_deref(),
         args.format,
         args.pretty_print,
+        args.sort,
+        None,
     )
 }
 
@@ -113,5 +115,7 @@ fn qubits_random(args: &Generate) -> Result<(), Error> {
         args.output_file.as_deref(),
         args.format,
         args.pretty_print,
+        args.sort,
+        None,
     )
 }
@@ -0,0 +1,133 @@
+use std::io::Write;
+
+use f2q::terms::{
+    FermiSum,
+    PauliSum,
+};
+use serde::Serialize;
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+    serialize_value,
+};
+use crate::{
+    cli::{
+        Format,
+        Inspect,
+    },
+    errors::Error,
+};
+
+#[derive(Serialize)]
+struct PauliMetrics {
+    num_terms:        usize,
+    num_qubits:       u8,
+    one_norm:         f64,
+    max_coeff:        Option<f64>,
+    identity_shift:   f64,
+    weight_histogram: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct FermiMetrics {
+    num_terms:        usize,
+    num_orbitals:     u32,
+    one_norm:         f64,
+    max_coeff:        Option<f64>,
+    offset:           f64,
+    num_offset:       usize,
+    num_one_electron: usize,
+    num_two_electron: usize,
+}
+
+pub fn qubits(args: &Inspect) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let metrics = PauliMetrics {
+        num_terms:        repr.len(),
+        num_qubits:       repr.num_qubits(),
+        one_norm:         repr.one_norm(),
+        max_coeff:        repr.max_coeff(),
+        identity_shift:   repr.identity_shift(),
+        weight_histogram: repr.weight_histogram(),
+    };
+    if args.json {
+        return serialize_value(
+            &metrics,
+            args.output_file.as_deref(),
+            Format::Json,
+            args.pretty_print,
+        );
+    }
+
+    let mut rows = vec![
+        ("num_terms".to_string(), metrics.num_terms.to_string()),
+        ("num_qubits".to_string(), metrics.num_qubits.to_string()),
+        ("one_norm".to_string(), metrics.one_norm.to_string()),
+        ("max_coeff".to_string(), fmt_option(metrics.max_coeff)),
+        (
+            "identity_shift".to_string(),
+            metrics.identity_shift.to_string(),
+        ),
+    ];
+    for (weight, count) in metrics.weight_histogram.iter().enumerate() {
+        rows.push((format!("weight[{weight}]"), count.to_string()));
+    }
+    write_table(&rows, args)
+}
+
+pub fn fermions(args: &Inspect) -> Result<(), Error> {
+    let repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let [num_offset, num_one_electron, num_two_electron] =
+        repr.order_histogram();
+    let metrics = FermiMetrics {
+        num_terms: repr.len(),
+        num_orbitals: repr.num_orbitals(),
+        one_norm: repr.one_norm(),
+        max_coeff: repr.max_coeff(),
+        offset: repr.offset(),
+        num_offset,
+        num_one_electron,
+        num_two_electron,
+    };
+    if args.json {
+        return serialize_value(
+            &metrics,
+            args.output_file.as_deref(),
+            Format::Json,
+            args.pretty_print,
+        );
+    }
+
+    let rows = [
+        ("num_terms", metrics.num_terms.to_string()),
+        ("num_orbitals", metrics.num_orbitals.to_string()),
+        ("one_norm", metrics.one_norm.to_string()),
+        ("max_coeff", fmt_option(metrics.max_coeff)),
+        ("offset", metrics.offset.to_string()),
+        ("num_offset", metrics.num_offset.to_string()),
+        ("num_one_electron", metrics.num_one_electron.to_string()),
+        ("num_two_electron", metrics.num_two_electron.to_string()),
+    ]
+    .map(|(key, value)| (key.to_string(), value));
+    write_table(&rows, args)
+}
+
+fn fmt_option(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |x| x.to_string())
+}
+
+fn write_table(
+    rows: &[(String, String)],
+    args: &Inspect,
+) -> Result<(), Error> {
+    let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let mut writer = create_writer(args.output_file.as_deref())?;
+    for (key, value) in rows {
+        writeln!(writer, "{key:width$}  {value}")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
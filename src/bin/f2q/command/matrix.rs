@@ -0,0 +1,51 @@
+use std::io::Write;
+
+use f2q::{
+    matrix::{
+        Coo,
+        Csr,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+    },
+};
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+    min_num_orbitals,
+};
+use crate::{
+    cli::Matrix,
+    errors::Error,
+};
+
+pub fn fermions(args: &Matrix) -> Result<(), Error> {
+    let repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let num_orbitals = match args.num_qubits {
+        Some(n) => n,
+        None => min_num_orbitals(&repr)?,
+    };
+    let mtx = Csr::from(Coo::from_fermions(&repr, num_orbitals)?);
+    write_matrix(&mtx, args)
+}
+
+pub fn qubits(args: &Matrix) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let num_qubits = args.num_qubits.unwrap_or(repr.num_qubits().into());
+    let mtx = Csr::from(Coo::from_paulis(&repr, num_qubits)?);
+    write_matrix(&mtx, args)
+}
+
+fn write_matrix(
+    mtx: &Csr<num::Complex<f64>>,
+    args: &Matrix,
+) -> Result<(), Error> {
+    let mut writer = create_writer(args.output_file.as_deref())?;
+    mtx.write_matrix_market(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
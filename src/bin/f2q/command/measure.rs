@@ -0,0 +1,116 @@
+use std::io::Write;
+
+use f2q::{
+    code::qubits::Paulis,
+    map::JordanWigner,
+    measure::{
+        support_mask,
+        Grouping,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        Terms,
+    },
+};
+use serde::Serialize;
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+    serialize_value,
+};
+use crate::{
+    cli::{
+        Format,
+        Measure,
+    },
+    errors::Error,
+};
+
+const BIT_ORDER: &str =
+    "little-endian: bit j of outcome is the measurement of qubit j into c[j]";
+
+#[derive(Serialize)]
+struct MeasurementPlan {
+    num_qubits: u16,
+    bit_order:  &'static str,
+    identity:   f64,
+    groups:     Vec<GroupOutput>,
+}
+
+#[derive(Serialize)]
+struct GroupOutput {
+    basis:   Paulis,
+    circuit: String,
+    terms:   Vec<TermOutput>,
+}
+
+/// Term value is `(-1)^popcount(outcome & mask)`
+#[derive(Serialize)]
+struct TermOutput {
+    code:  Paulis,
+    value: f64,
+    mask:  u64,
+}
+
+pub fn fermions(args: &Measure) -> Result<(), Error> {
+    let in_repr: FermiSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let mut repr = PauliSum::with_capacity(in_repr.len() * 4);
+    JordanWigner::new(&in_repr).add_to(&mut repr)?;
+    write_measurements(&repr, args)
+}
+
+pub fn qubits(args: &Measure) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    write_measurements(&repr, args)
+}
+
+fn write_measurements(
+    repr: &PauliSum,
+    args: &Measure,
+) -> Result<(), Error> {
+    let num_qubits = args.num_qubits.unwrap_or(repr.num_qubits().into());
+    let grouping = Grouping::qubit_wise(repr);
+    let circuits = grouping.circuits(num_qubits)?;
+
+    let prefix = &args.output_prefix;
+    let mut groups = Vec::with_capacity(circuits.len());
+    for (i, (group, circ)) in
+        grouping.groups().iter().zip(&circuits).enumerate()
+    {
+        let path = format!("{prefix}_{i}.qasm");
+        let mut writer = create_writer(Some(&path))?;
+        circ.write_qasm(&mut writer, args.qasm_version.into())?;
+        writer.flush()?;
+
+        groups.push(GroupOutput {
+            basis:   group.basis(),
+            circuit: path,
+            terms:   group
+                .terms()
+                .iter()
+                .map(|&(value, code)| TermOutput {
+                    code,
+                    value,
+                    mask: support_mask(code),
+                })
+                .collect(),
+        });
+    }
+
+    let plan = MeasurementPlan {
+        num_qubits,
+        bit_order: BIT_ORDER,
+        identity: grouping.identity(),
+        groups,
+    };
+    serialize_value(
+        &plan,
+        Some(&format!("{prefix}.json")),
+        Format::Json,
+        args.pretty_print,
+    )
+}
@@ -0,0 +1,39 @@
+use std::io::Write;
+
+use f2q::{
+    lcu,
+    terms::PauliSum,
+};
+
+use super::{
+    create_writer,
+    deserialize_sumrepr,
+    serialize_value,
+};
+use crate::{
+    cli::{
+        Format,
+        Prepare,
+    },
+    errors::Error,
+};
+
+pub fn qubits(args: &Prepare) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    let prep = lcu::Prepare::new(&repr, args.num_bits)?;
+
+    if args.binary {
+        let mut writer = create_writer(args.output_file.as_deref())?;
+        prep.write_binary(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    } else {
+        serialize_value(
+            &prep,
+            args.output_file.as_deref(),
+            Format::Json,
+            args.pretty_print,
+        )
+    }
+}
@@ -0,0 +1,75 @@
+use f2q::{
+    code::Code,
+    fcidump::Header,
+    terms::{
+        PauliSum,
+        Sorted,
+        SumRepr,
+    },
+};
+use serde::Serialize;
+
+use super::{
+    deserialize_fermisum,
+    deserialize_sumrepr,
+    serialize_sumrepr,
+    TextFormat,
+};
+use crate::{
+    cli::Prune,
+    errors::Error,
+    log_or_eprintln_if_verbose,
+};
+
+pub fn fermions(args: &Prune) -> Result<(), Error> {
+    let (repr, header) =
+        deserialize_fermisum(args.input_file.as_deref(), args.input_format)?;
+    prune(repr, header, args)
+}
+
+pub fn qubits(args: &Prune) -> Result<(), Error> {
+    let repr: PauliSum =
+        deserialize_sumrepr(args.input_file.as_deref(), args.input_format)?;
+    prune(repr, None, args)
+}
+
+fn prune<K>(
+    mut repr: SumRepr<f64, K>,
+    input_header: Option<Header>,
+    args: &Prune,
+) -> Result<(), Error>
+where
+    K: Code + Ord,
+    for<'a> Sorted<'a, f64, K>: Serialize,
+    SumRepr<f64, K>: TextFormat,
+{
+    let dropped = match (args.threshold, args.budget) {
+        (Some(threshold), _) => repr.prune(threshold),
+        (None, Some(budget)) => repr.prune_by_norm(budget),
+        (None, None) => unreachable!("clap requires one of the arguments"),
+    };
+    let dropped_norm: f64 = dropped.iter().map(|(coeff, _)| coeff.abs()).sum();
+    log_or_eprintln_if_verbose(&format!(
+        "Removed {} terms, 1-norm: {dropped_norm:e}",
+        dropped.len()
+    ));
+
+    if let Some(path) = &args.dropped_file {
+        serialize_sumrepr(
+            &dropped,
+            Some(path),
+            args.output_format,
+            args.pretty_print,
+            args.sort,
+            input_header.as_ref(),
+        )?;
+    }
+    serialize_sumrepr(
+        &repr,
+        args.output_file.as_deref(),
+        args.output_format,
+        args.pretty_print,
+        args.sort,
+        input_header.as_ref(),
+    )
+}
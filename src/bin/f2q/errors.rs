@@ -5,6 +5,7 @@ pub enum Error {
     CmdArgs { msg: String },
     File { msg: String },
     Serde { msg: String },
+    Differ { num_terms: usize },
     F2Q(f2q::Error),
 }
 
@@ -24,6 +25,9 @@ impl Display for Error {
             Error::Serde {
                 msg,
             } => write!(f, "[serde] {msg}"),
+            Error::Differ {
+                num_terms,
+            } => write!(f, "[diff] {num_terms} terms differ"),
         }
     }
 }
@@ -40,6 +44,9 @@ impl From<&Error> for u8 {
                 ..
             } => 2,
             Error::F2Q(_) => 3,
+            Error::Differ {
+                ..
+            } => 4,
             Error::Serde {
                 ..
             } => 11,
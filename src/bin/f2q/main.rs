@@ -52,5 +52,14 @@ fn main_exec(cli: &Cli) -> Result<(), Error> {
     match &cli.command {
         Commands::Generate(args) => command::generate(args),
         Commands::Convert(args) => command::convert(args),
+        Commands::Matrix(args) => command::matrix(args),
+        Commands::Eigen(args) => command::eigen(args),
+        Commands::Inspect(args) => command::inspect(args),
+        Commands::Prepare(args) => command::prepare(args),
+        Commands::Circuit(args) => command::circuit(args),
+        Commands::Measure(args) => command::measure(args),
+        Commands::Estimate(args) => command::estimate(args),
+        Commands::Prune(args) => command::prune(args),
+        Commands::Diff(args) => command::diff(args),
     }
 }
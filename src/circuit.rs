@@ -0,0 +1,459 @@
+//! Quantum circuits for Hamiltonian simulation.
+//!
+//! A rotation `exp(-i θ P)` about a Pauli string `P` is synthesized as a
+//! Pauli gadget: single-qubit gates rotating each nontrivial Pauli operator
+//! into `Z`, a ladder of CNOT gates computing the parity of the qubits onto
+//! the last of them, a single `RZ(2θ)` gate, and the inverse of the ladder
+//! and of the basis change.
+//!
+//! [`Trotter`] composes the gadgets into product formulas approximating the
+//! evolution `exp(-i H t)`.  Circuits are written in the OpenQASM format,
+//! version 2.0 or 3.0, see: [`Circuit::write_qasm()`].
+//!
+//! Qubit `j` of the circuit corresponds to the Pauli operator at index `j` of
+//! [`Paulis`].
+
+use std::io::Write;
+
+use crate::{
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    terms::SumRepr,
+    Error,
+};
+
+/// Quantum gate acting on qubits of a register.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gate {
+    /// Hadamard gate
+    H(u16),
+    /// Phase gate: `diag(1, i)`
+    S(u16),
+    /// Inverse of the phase gate: `diag(1, -i)`
+    Sdg(u16),
+    /// Rotation: `RZ(φ) = exp(-i φ Z / 2)`
+    Rz(f64, u16),
+    /// Controlled NOT gate: `(control, target)`
+    Cx(u16, u16),
+    /// Measurement in the computational basis into the classical bit of the
+    /// same index
+    Measure(u16),
+}
+
+impl Gate {
+    fn max_qubit(&self) -> u16 {
+        match *self {
+            Self::H(i)
+            | Self::S(i)
+            | Self::Sdg(i)
+            | Self::Rz(_, i)
+            | Self::Measure(i) => i,
+            Self::Cx(i, j) => i.max(j),
+        }
+    }
+}
+
+/// Version of the OpenQASM format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QasmVersion {
+    V2,
+    V3,
+}
+
+/// Sequence of gates acting on a register of qubits.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{circuit::{Circuit, Gate}, code::qubits::{Paulis, Sigma}};
+/// let mut circ = Circuit::new(2);
+/// circ.add_pauli_rotation(0.5, Paulis::with_ops([Sigma::X, Sigma::Z]));
+///
+/// assert_eq!(
+///     circ.gates(),
+///     &[
+///         Gate::H(0),
+///         Gate::Cx(0, 1),
+///         Gate::Rz(1.0, 1),
+///         Gate::Cx(0, 1),
+///         Gate::H(0),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Circuit {
+    num_qubits: u16,
+    gates:      Vec<Gate>,
+}
+
+impl Circuit {
+    /// Create empty circuit.
+    #[must_use]
+    pub fn new(num_qubits: u16) -> Self {
+        Self {
+            num_qubits,
+            gates: Vec::new(),
+        }
+    }
+
+    /// Number of qubits in the register.
+    #[must_use]
+    pub fn num_qubits(&self) -> u16 {
+        self.num_qubits
+    }
+
+    /// Gates in the order of application.
+    #[must_use]
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// Number of CNOT gates.
+    #[must_use]
+    pub fn num_cx(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Cx(..)))
+            .count()
+    }
+
+    fn has_measurements(&self) -> bool {
+        self.gates
+            .iter()
+            .any(|gate| matches!(gate, Gate::Measure(_)))
+    }
+
+    /// Append a gate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the gate acts on a qubit outside of the register.
+    pub fn push(
+        &mut self,
+        gate: Gate,
+    ) {
+        assert!(
+            gate.max_qubit() < self.num_qubits,
+            "qubit index out of bounds"
+        );
+        self.gates.push(gate);
+    }
+
+    /// Append the Pauli gadget implementing `exp(-i * angle * code)`.
+    ///
+    /// The identity contributes only a global phase and adds no gates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code acts on a qubit outside of the register.
+    pub fn add_pauli_rotation(
+        &mut self,
+        angle: f64,
+        code: Paulis,
+    ) {
+        let ops: Vec<_> = (0..64)
+            .zip(code)
+            .filter(|(_, pauli)| *pauli != Sigma::I)
+            .collect();
+        let Some(&(last, _)) = ops.last() else {
+            return;
+        };
+
+        for &(i, pauli) in &ops {
+            match pauli {
+                Sigma::X => self.push(Gate::H(i)),
+                Sigma::Y => {
+                    self.push(Gate::Sdg(i));
+                    self.push(Gate::H(i));
+                }
+                _ => (),
+            }
+        }
+        for w in ops.windows(2) {
+            self.push(Gate::Cx(w[0].0, w[1].0));
+        }
+        self.push(Gate::Rz(2.0 * angle, last));
+        for w in ops.windows(2).rev() {
+            self.push(Gate::Cx(w[0].0, w[1].0));
+        }
+        for &(i, pauli) in &ops {
+            match pauli {
+                Sigma::X => self.push(Gate::H(i)),
+                Sigma::Y => {
+                    self.push(Gate::H(i));
+                    self.push(Gate::S(i));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Write the circuit in the OpenQASM format.
+    ///
+    /// The register is called `q`.  Gates are taken from the standard
+    /// libraries: `qelib1.inc` (version 2.0) or `stdgates.inc` (3.0).  If the
+    /// circuit contains measurements, a classical register `c` of the same
+    /// size is declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error, if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::circuit::{Circuit, Gate, QasmVersion};
+    /// let mut circ = Circuit::new(2);
+    /// circ.push(Gate::Cx(0, 1));
+    /// circ.push(Gate::Rz(1e-20, 1));
+    ///
+    /// let mut buf = Vec::new();
+    /// circ.write_qasm(&mut buf, QasmVersion::V3).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "OPENQASM 3.0;\ninclude \"stdgates.inc\";\nqubit[2] q;\ncx q[0], \
+    ///      q[1];\nrz(1.0e-20) q[1];\n"
+    /// );
+    /// ```
+    pub fn write_qasm<W: Write>(
+        &self,
+        writer: &mut W,
+        version: QasmVersion,
+    ) -> std::io::Result<()> {
+        match version {
+            QasmVersion::V2 => {
+                writeln!(writer, "OPENQASM 2.0;")?;
+                writeln!(writer, "include \"qelib1.inc\";")?;
+                writeln!(writer, "qreg q[{}];", self.num_qubits)?;
+                if self.has_measurements() {
+                    writeln!(writer, "creg c[{}];", self.num_qubits)?;
+                }
+            }
+            QasmVersion::V3 => {
+                writeln!(writer, "OPENQASM 3.0;")?;
+                writeln!(writer, "include \"stdgates.inc\";")?;
+                writeln!(writer, "qubit[{}] q;", self.num_qubits)?;
+                if self.has_measurements() {
+                    writeln!(writer, "bit[{}] c;", self.num_qubits)?;
+                }
+            }
+        }
+        for gate in &self.gates {
+            match *gate {
+                Gate::H(i) => writeln!(writer, "h q[{i}];")?,
+                Gate::S(i) => writeln!(writer, "s q[{i}];")?,
+                Gate::Sdg(i) => writeln!(writer, "sdg q[{i}];")?,
+                Gate::Rz(phi, i) => {
+                    writeln!(writer, "rz({}) q[{i}];", fmt_real(phi))?;
+                }
+                Gate::Cx(i, j) => writeln!(writer, "cx q[{i}], q[{j}];")?,
+                Gate::Measure(i) => match version {
+                    QasmVersion::V2 => {
+                        writeln!(writer, "measure q[{i}] -> c[{i}];")?;
+                    }
+                    QasmVersion::V3 => {
+                        writeln!(writer, "c[{i}] = measure q[{i}];")?;
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format a real number, so that it always contains the decimal point, as
+/// required by the OpenQASM 2.0 grammar.
+fn fmt_real(x: f64) -> String {
+    let s = format!("{x:?}");
+    if s.contains('.') {
+        s
+    } else {
+        s.replacen('e', ".0e", 1)
+    }
+}
+
+/// Order of the product formula.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrotterOrder {
+    /// `Π_j exp(-i h_j P_j Δt)`
+    #[default]
+    First,
+    /// Symmetric (Strang) splitting: the first-order formula for `Δt/2`,
+    /// followed by the same product in reverse order.
+    Second,
+}
+
+/// Trotter circuits for a sum of Pauli strings.
+///
+/// The evolution `exp(-i H t)` is split into `num_steps` steps of length
+/// `Δt = t / num_steps`, each approximated by a product formula of Pauli
+/// rotations.  Terms are applied in the order of their codes.  Adjacent
+/// rotations about the same Pauli string, e.g. in the middle of each
+/// second-order step, are merged.  The identity term only contributes a global
+/// phase and is skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{circuit::{Trotter, TrotterOrder}, code::qubits::{Paulis, Sigma}, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (1.0, Paulis::identity()),
+///     (0.5, Paulis::with_ops([Sigma::X])),
+///     (0.25, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+/// ]);
+///
+/// let trotter = Trotter::new(1.0, 2).order(TrotterOrder::Second);
+/// let rots = trotter.rotations(&repr).unwrap();
+///
+/// let x = Paulis::with_ops([Sigma::X]);
+/// let zz = Paulis::with_ops([Sigma::Z, Sigma::Z]);
+/// assert_eq!(
+///     rots,
+///     &[(0.125, x), (0.125, zz), (0.25, x), (0.125, zz), (0.125, x)]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trotter {
+    time:       f64,
+    num_steps:  usize,
+    order:      TrotterOrder,
+    num_qubits: Option<u16>,
+}
+
+impl Trotter {
+    /// Evolution for `time` in `num_steps` first-order steps.
+    #[must_use]
+    pub fn new(
+        time: f64,
+        num_steps: usize,
+    ) -> Self {
+        Self {
+            time,
+            num_steps,
+            order: TrotterOrder::First,
+            num_qubits: None,
+        }
+    }
+
+    /// Order of the product formula.
+    #[must_use]
+    pub fn order(
+        self,
+        order: TrotterOrder,
+    ) -> Self {
+        Self {
+            order,
+            ..self
+        }
+    }
+
+    /// Size of the register.  Minimal size fitting the Hamiltonian, if not
+    /// specified.
+    #[must_use]
+    pub fn num_qubits(
+        self,
+        num_qubits: Option<u16>,
+    ) -> Self {
+        Self {
+            num_qubits,
+            ..self
+        }
+    }
+
+    /// Sequence of Pauli rotations.
+    ///
+    /// Each element is a pair: `(angle, code)` representing the unitary
+    /// `exp(-i * angle * code)`, in the order of application.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the number of steps is zero, or time or any of
+    /// coefficients is not finite.
+    pub fn rotations(
+        &self,
+        repr: &SumRepr<f64, Paulis>,
+    ) -> Result<Vec<(f64, Paulis)>, Error> {
+        if self.num_steps == 0 {
+            return Err(Error::Circuit {
+                msg: "number of steps must be positive".to_string(),
+            });
+        }
+        if !self.time.is_finite() {
+            return Err(Error::Circuit {
+                msg: "time must be finite".to_string(),
+            });
+        }
+        let mut terms: Vec<_> = repr
+            .iter()
+            .filter(|(coeff, code)| {
+                **code != Paulis::identity() && **coeff != 0.0
+            })
+            .map(|(&coeff, &code)| (coeff, code))
+            .collect();
+        if terms.iter().any(|(coeff, _)| !coeff.is_finite()) {
+            return Err(Error::Circuit {
+                msg: "coefficients must be finite".to_string(),
+            });
+        }
+        terms.sort_by_key(|&(_, code)| code);
+
+        #[allow(clippy::cast_precision_loss)]
+        let dt = self.time / self.num_steps as f64;
+        let step: Vec<_> = match self.order {
+            TrotterOrder::First => terms
+                .iter()
+                .map(|&(coeff, code)| (coeff * dt, code))
+                .collect(),
+            TrotterOrder::Second => terms
+                .iter()
+                .chain(terms.iter().rev())
+                .map(|&(coeff, code)| (coeff * dt / 2.0, code))
+                .collect(),
+        };
+
+        let mut rots: Vec<(f64, Paulis)> = Vec::new();
+        for &(angle, code) in
+            step.iter().cycle().take(step.len() * self.num_steps)
+        {
+            match rots.last_mut() {
+                Some((last_angle, last_code)) if *last_code == code => {
+                    *last_angle += angle;
+                }
+                _ => rots.push((angle, code)),
+            }
+        }
+        Ok(rots)
+    }
+
+    /// Synthesize the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the number of steps is zero, time or any of
+    /// coefficients is not finite, or the Hamiltonian does not fit in the
+    /// register.
+    pub fn circuit(
+        &self,
+        repr: &SumRepr<f64, Paulis>,
+    ) -> Result<Circuit, Error> {
+        let min_num_qubits = u16::from(repr.num_qubits());
+        let num_qubits = self.num_qubits.unwrap_or(min_num_qubits);
+        if num_qubits < min_num_qubits {
+            return Err(Error::Circuit {
+                msg: format!(
+                    "Hamiltonian acts on {min_num_qubits} qubits, register \
+                     has {num_qubits}"
+                ),
+            });
+        }
+
+        let mut circ = Circuit::new(num_qubits);
+        for (angle, code) in self.rotations(repr)? {
+            circ.add_pauli_rotation(angle, code);
+        }
+        Ok(circ)
+    }
+}
@@ -10,7 +10,7 @@ use std::{
 use crate::Error;
 
 /// Spin one-half
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Spin {
     #[default]
     Down,
@@ -102,7 +102,7 @@ impl_spin_int!(u8 u16 u32 u64 u128 usize);
 impl_spin_int!(i8 i16 i32 i64 i128 isize);
 
 /// Electronic orbital consisting of a principal quantum number and a spin 1/2.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Orbital {
     pub n: u32,
     pub s: Spin,
@@ -207,13 +207,13 @@ impl Orbital {
 /// Creation operator
 ///
 /// A newtype struct representing a creation operator.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Cr(pub Orbital);
 
 /// Annihilation operator
 ///
 /// A newtype struct representing an annihilation operator.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct An(pub Orbital);
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -237,7 +237,11 @@ pub enum Majorana {
 ///   ```
 ///
 ///   then `p < q`, `r > s` and `p <= s`.
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+///
+/// Codes are ordered by the number of operators first (offset, one-electron,
+/// two-electron), and then lexicographically by orbital indices of creation and
+/// annihilation operators.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Fermions {
     #[default]
     Offset,
@@ -350,6 +354,41 @@ impl Fermions {
             an,
         })
     }
+
+    /// Largest orbital index in the code, or `None` for `Fermions::Offset`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::code::fermions::Fermions;
+    ///
+    /// assert_eq!(Fermions::Offset.max_index(), None);
+    /// assert_eq!(Fermions::try_from((1, 3)).unwrap().max_index(), Some(3));
+    /// assert_eq!(
+    ///     Fermions::try_from((0, 5, 2, 1)).unwrap().max_index(),
+    ///     Some(5)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn max_index(&self) -> Option<u32> {
+        match self {
+            Self::Offset => None,
+            Self::One {
+                cr,
+                an,
+            } => Some(cr.0.index().max(an.0.index())),
+            Self::Two {
+                cr,
+                an,
+            } => Some(
+                cr.0 .0
+                    .index()
+                    .max(cr.1 .0.index())
+                    .max(an.0 .0.index())
+                    .max(an.1 .0.index()),
+            ),
+        }
+    }
 }
 
 impl From<()> for Fermions {
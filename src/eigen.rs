@@ -0,0 +1,357 @@
+//! Exact diagonalization of Hamiltonians.
+//!
+//! The Lanczos algorithm with full reorthogonalization finds the lowest
+//! eigenvalues of a Hermitian matrix.  Combined with
+//! [`Coo::from_fermions_in_sector()`](crate::matrix::Coo::from_fermions_in_sector)
+//! it gives reference (FCI) energies for Hamiltonians of small active spaces.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use f2q::{code::fermions::Fermions, eigen::Lanczos, matrix::{Coo, Csr, Sector}, terms::SumRepr};
+//! // Two orbitals with hopping: 0.5 * (a+_0 a_1 + a+_1 a_0)
+//! let repr = SumRepr::from([(0.5, Fermions::try_from((0, 1)).unwrap())]);
+//!
+//! let sector = Sector::new(2, 1, None).unwrap();
+//! let mtx = Csr::from(Coo::from_fermions_in_sector(&repr, &sector).unwrap());
+//! let eigen = Lanczos::new(1).solve(&mtx).unwrap();
+//!
+//! assert!((eigen.values[0] + 0.5).abs() < 1e-12);
+//! ```
+
+use num::{
+    Complex,
+    Zero,
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+use crate::{
+    matrix::Csr,
+    Error,
+};
+
+const CHECK_INTERVAL: usize = 5;
+
+/// Lowest eigenvalues and, optionally, corresponding eigenvectors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Eigen {
+    /// Eigenvalues in increasing order.
+    pub values:  Vec<f64>,
+    /// Normalized eigenvectors, in the same order as eigenvalues.
+    pub vectors: Option<Vec<Vec<Complex<f64>>>>,
+}
+
+/// Lanczos eigensolver for Hermitian matrices.
+///
+/// The Krylov space is grown from a pseudo-random starting vector until the
+/// requested number of the lowest Ritz values converge, or the Krylov space
+/// becomes invariant.  The basis is kept orthogonal by full
+/// reorthogonalization, hence memory scales like `dim * max_iter`.
+///
+/// Since the Krylov space is generated by a single vector, only one
+/// eigenvector in each degenerate eigenspace can be found.  Degenerate
+/// eigenvalues are therefore reported only once.
+#[derive(Clone, Debug)]
+pub struct Lanczos {
+    num_eigenvalues: usize,
+    max_iter:        usize,
+    tolerance:       f64,
+    eigenvectors:    bool,
+    seed:            u64,
+}
+
+impl Lanczos {
+    /// Create new solver for `num_eigenvalues` lowest eigenvalues.
+    ///
+    /// Default values are: `max_iter = 300`, `tolerance = 1e-10`, no
+    /// eigenvectors, `seed = 0`.
+    #[must_use]
+    pub fn new(num_eigenvalues: usize) -> Self {
+        Self {
+            num_eigenvalues,
+            max_iter: 300,
+            tolerance: 1e-10,
+            eigenvectors: false,
+            seed: 0,
+        }
+    }
+
+    /// Maximal dimension of the Krylov space.
+    #[must_use]
+    pub fn max_iter(
+        self,
+        max_iter: usize,
+    ) -> Self {
+        Self {
+            max_iter,
+            ..self
+        }
+    }
+
+    /// Convergence threshold for the residual norm of Ritz pairs, relative to
+    /// the magnitude of the eigenvalue (or absolute, if it is less than one).
+    #[must_use]
+    pub fn tolerance(
+        self,
+        tolerance: f64,
+    ) -> Self {
+        Self {
+            tolerance,
+            ..self
+        }
+    }
+
+    /// Compute eigenvectors as well.
+    #[must_use]
+    pub fn eigenvectors(
+        self,
+        eigenvectors: bool,
+    ) -> Self {
+        Self {
+            eigenvectors,
+            ..self
+        }
+    }
+
+    /// Seed of the random number generator for the starting vector.
+    #[must_use]
+    pub fn seed(
+        self,
+        seed: u64,
+    ) -> Self {
+        Self {
+            seed,
+            ..self
+        }
+    }
+
+    /// Find the lowest eigenvalues of a Hermitian matrix.
+    ///
+    /// If the Krylov space becomes invariant before `num_eigenvalues`
+    /// are found, fewer eigenvalues are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the matrix is not square or empty, or if the
+    /// eigenvalues do not converge within `max_iter` iterations.
+    pub fn solve(
+        &self,
+        mtx: &Csr<Complex<f64>>,
+    ) -> Result<Eigen, Error> {
+        let (dim, num_cols) = mtx.shape();
+        if dim != num_cols || dim == 0 {
+            return Err(Error::Eigen {
+                msg: format!(
+                    "matrix must be square and nonempty: {dim}x{num_cols}"
+                ),
+            });
+        }
+        let max_iter = self.max_iter.min(dim);
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut v: Vec<_> = (0..dim)
+            .map(|_| Complex::new(rng.gen_range(-1.0..1.0), 0.0))
+            .collect();
+        normalize(&mut v);
+
+        let mut basis = vec![v];
+        let mut alpha = Vec::new();
+        let mut beta: Vec<f64> = Vec::new();
+        let mut w = vec![Complex::zero(); dim];
+
+        let ritz = loop {
+            let j = alpha.len();
+            mtx.mul_vec(&basis[j], &mut w);
+            let a = dot(&basis[j], &w).re;
+            axpy(-a, &basis[j], &mut w);
+            if j > 0 {
+                axpy(-beta[j - 1], &basis[j - 1], &mut w);
+            }
+            // Full reorthogonalization, applied twice for stability
+            for _ in 0..2 {
+                for v in &basis {
+                    let proj = dot(v, &w);
+                    for (w_i, v_i) in w.iter_mut().zip(v) {
+                        *w_i -= proj * v_i;
+                    }
+                }
+            }
+            let b = norm(&w);
+            alpha.push(a);
+
+            let invariant = b <= f64::EPSILON * a.abs().max(1.0);
+            let exhausted = alpha.len() >= max_iter;
+            // Check for convergence periodically, as it costs O(n^3)
+            if invariant
+                || exhausted
+                || alpha.len() >= self.num_eigenvalues
+                    && alpha.len() % CHECK_INTERVAL == 0
+            {
+                let ritz = tridiagonal_eigen(&alpha, &beta)?;
+                let num_wanted = self.num_eigenvalues.min(alpha.len());
+                let converged = ritz.0[..num_wanted].iter().enumerate().all(
+                    |(k, theta)| {
+                        let residual = (b * ritz.1[k][alpha.len() - 1]).abs();
+                        residual <= self.tolerance * theta.abs().max(1.0)
+                    },
+                );
+                if invariant || converged {
+                    break ritz;
+                }
+                if exhausted {
+                    return Err(Error::Eigen {
+                        msg: format!(
+                            "no convergence after {max_iter} iterations"
+                        ),
+                    });
+                }
+            }
+
+            beta.push(b);
+            let mut v = w.clone();
+            v.iter_mut().for_each(|x| *x /= b);
+            basis.push(v);
+        };
+
+        let num_found = self.num_eigenvalues.min(alpha.len());
+        let values = ritz.0[..num_found].to_vec();
+        let vectors = self.eigenvectors.then(|| {
+            ritz.1[..num_found]
+                .iter()
+                .map(|y| {
+                    let mut x = vec![Complex::zero(); dim];
+                    for (y_j, v) in y.iter().zip(&basis) {
+                        axpy(*y_j, v, &mut x);
+                    }
+                    normalize(&mut x);
+                    x
+                })
+                .collect()
+        });
+
+        Ok(Eigen {
+            values,
+            vectors,
+        })
+    }
+}
+
+fn dot(
+    x: &[Complex<f64>],
+    y: &[Complex<f64>],
+) -> Complex<f64> {
+    x.iter().zip(y).map(|(a, b)| a.conj() * b).sum()
+}
+
+fn norm(x: &[Complex<f64>]) -> f64 {
+    x.iter().map(Complex::norm_sqr).sum::<f64>().sqrt()
+}
+
+fn normalize(x: &mut [Complex<f64>]) {
+    let n = norm(x);
+    x.iter_mut().for_each(|x_i| *x_i /= n);
+}
+
+/// `y += a * x`
+fn axpy(
+    a: f64,
+    x: &[Complex<f64>],
+    y: &mut [Complex<f64>],
+) {
+    for (y_i, x_i) in y.iter_mut().zip(x) {
+        *y_i += x_i * a;
+    }
+}
+
+/// Eigenvalues and eigenvectors of a real symmetric tridiagonal matrix with
+/// diagonal `diag` and off-diagonal `off_diag`.
+///
+/// Returns eigenvalues in increasing order together with corresponding
+/// normalized eigenvectors.  Implicit QL algorithm with Wilkinson shifts.
+pub(crate) fn tridiagonal_eigen(
+    diag: &[f64],
+    off_diag: &[f64],
+) -> Result<(Vec<f64>, Vec<Vec<f64>>), Error> {
+    let n = diag.len();
+    let mut d = diag.to_vec();
+    let mut e = off_diag.to_vec();
+    e.resize(n, 0.0);
+    // Row-major matrix of eigenvectors stored as columns
+    let mut z: Vec<Vec<f64>> = (0..n)
+        .map(|k| (0..n).map(|i| if i == k { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for l in 0..n {
+        let mut iter = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= f64::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            iter += 1;
+            if iter > 60 {
+                return Err(Error::Eigen {
+                    msg: "tridiagonal QL algorithm did not converge"
+                        .to_string(),
+                });
+            }
+
+            let mut g = (d[l + 1] - d[l]) / (2.0 * e[l]);
+            let mut r = g.hypot(1.0);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let (mut s, mut c, mut p) = (1.0, 1.0, 0.0);
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == 0.0 {
+                    d[i + 1] -= p;
+                    e[m] = 0.0;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + 2.0 * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                for z_k in &mut z {
+                    let f = z_k[i + 1];
+                    z_k[i + 1] = s * z_k[i] + c * f;
+                    z_k[i] = c * z_k[i] - s * f;
+                }
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.0;
+        }
+    }
+
+    let mut order: Vec<_> = (0..n).collect();
+    order.sort_by(|&i, &j| d[i].total_cmp(&d[j]));
+    let values = order.iter().map(|&i| d[i]).collect();
+    let vectors = order
+        .iter()
+        .map(|&i| z.iter().map(|z_k| z_k[i]).collect())
+        .collect();
+
+    Ok((values, vectors))
+}
@@ -0,0 +1,706 @@
+//! FCIDUMP format of molecular integrals.
+//!
+//! FCIDUMP files, as written by Molpro, PySCF and most quantum chemistry
+//! codes, consist of a namelist header:
+//!
+//! ```text
+//!  &FCI NORB=2,NELEC=2,MS2=0,
+//!   ORBSYM=1,1,
+//!   ISYM=1,
+//!  &END
+//! ```
+//!
+//! followed by lines `value i j k l` of integrals over spatial orbitals,
+//! indexed from 1:
+//!
+//! - `i j k l` all nonzero: two-electron integral `(ij|kl)` in the chemists'
+//!   notation,
+//! - `i j 0 0`: one-electron integral `h_ij`,
+//! - `0 0 0 0`: nuclear repulsion energy (or any constant core energy),
+//! - `i 0 0 0`: orbital energy, which is ignored.
+//!
+//! Orbitals are assumed to be real, so that integrals have the 8-fold
+//! permutational symmetry: `(ij|kl) = (ji|kl) = (ij|lk) = (kl|ij) = ...`,
+//! and each of them needs to be listed only once.
+//!
+//! The Hamiltonian:
+//!
+//! ```text
+//! H = E_core + sum_{pq,σ} h_pq a+_pσ a_qσ
+//!     + 1/2 sum_{pqrs,στ} (pq|rs) a+_pσ a+_rτ a_sτ a_qσ
+//! ```
+//!
+//! is expanded to spin orbitals with the indexing of [`Orbital::index()`],
+//! and written as a sum of canonical [`Fermions`] codes, where a term with
+//! coefficient `c` and code `T` stands for `c * (T + T^†)`, as in
+//! [`JordanWigner`](crate::map::JordanWigner).
+
+use std::{
+    collections::HashMap,
+    io::{
+        self,
+        Write,
+    },
+    str::FromStr,
+};
+
+use num::Float;
+
+use crate::{
+    code::fermions::{
+        Fermions,
+        Orbital,
+        Spin,
+    },
+    integrals::{
+        add_one_body,
+        add_two_body,
+        spin_orbital,
+    },
+    terms::{
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+/// Header of FCIDUMP file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Number of spatial orbitals.
+    pub norb:   u32,
+    /// Number of electrons.
+    pub nelec:  u32,
+    /// Twice the total spin projection.
+    pub ms2:    i32,
+    /// Irreducible representations of orbitals.  Empty, if not specified.
+    pub orbsym: Vec<u32>,
+    /// Irreducible representation of the wave function.
+    pub isym:   u32,
+}
+
+impl Header {
+    /// Create header for `norb` orbitals and `nelec` electrons, with no
+    /// symmetry information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::fcidump::Header;
+    /// let header = Header::new(4, 2);
+    ///
+    /// assert_eq!(header.ms2, 0);
+    /// assert_eq!(header.isym, 1);
+    /// ```
+    #[must_use]
+    pub fn new(
+        norb: u32,
+        nelec: u32,
+    ) -> Self {
+        Self {
+            norb,
+            nelec,
+            ms2: 0,
+            orbsym: Vec::new(),
+            isym: 1,
+        }
+    }
+}
+
+/// Molecular integrals read from FCIDUMP file.
+///
+/// Orbital indices are counted from 0.  Integrals related by permutational
+/// symmetry are stored once, with indices `i <= j` for one-electron
+/// integrals, and `i <= j`, `k <= l`, `(i, j) <= (k, l)` for two-electron
+/// integrals.  If the file lists several integrals related by symmetry, the
+/// last one is kept.  Integrals are sorted by indices.
+///
+/// Implements [`Terms`] for the Hamiltonian expanded to spin orbitals.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::fermions::Fermions, fcidump::Fcidump, terms::{FermiSum, Terms}};
+/// # fn main() -> Result<(), f2q::Error> {
+/// let input = "&FCI NORB=1,NELEC=2,MS2=0,ORBSYM=1,ISYM=1,&END
+///   0.5  1 1 1 1
+///  -1.0  1 1 0 0
+///   0.25 0 0 0 0
+/// ";
+/// let fcidump: Fcidump = input.parse()?;
+/// assert_eq!(fcidump.header.nelec, 2);
+///
+/// let mut repr = FermiSum::new();
+/// fcidump.add_to(&mut repr)?;
+///
+/// // Doubly occupied orbital: E = 0.25 - 2.0 + 0.5
+/// assert_eq!(repr.coeff(Fermions::Offset), Some(&0.25));
+/// assert_eq!(repr.coeff(Fermions::try_from((0, 0)).unwrap()), Some(&-0.5));
+/// assert_eq!(repr.coeff(Fermions::try_from((1, 1)).unwrap()), Some(&-0.5));
+/// assert_eq!(
+///     repr.coeff(Fermions::try_from((0, 1, 1, 0)).unwrap()),
+///     Some(&0.25)
+/// );
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fcidump {
+    pub header:       Header,
+    /// Constant core energy, e.g. the nuclear repulsion.
+    pub core_energy:  f64,
+    /// One-electron integrals `h_ij`.
+    pub one_electron: Vec<(f64, (u32, u32))>,
+    /// Two-electron integrals `(ij|kl)`.
+    pub two_electron: Vec<(f64, (u32, u32, u32, u32))>,
+}
+
+impl Fcidump {
+    /// Spatial integrals of a spin-restricted Hamiltonian.
+    ///
+    /// This is the inverse of the expansion to spin orbitals.  The fields of
+    /// `header`, like the number of electrons and the symmetry of orbitals,
+    /// are kept, except that the number of orbitals is increased, if
+    /// needed, to the smallest fitting the Hamiltonian.  Integrals equal to
+    /// zero are omitted.  A two-electron term can be given by either of the
+    /// codes valid for it and its adjoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the terms are not consistent with spatial integrals,
+    /// i.e. if expanding the integrals back to spin orbitals does not
+    /// reproduce the Hamiltonian up to the absolute `tolerance`.  This is
+    /// the case e.g. for terms that depend on spin.  Returns error also if
+    /// the header specifies the symmetry of a different number of orbitals.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{
+    /// #     code::fermions::Fermions,
+    /// #     fcidump::{Fcidump, Header},
+    /// #     terms::SumRepr,
+    /// # };
+    /// # fn main() -> Result<(), f2q::Error> {
+    /// // Hopping between two spatial orbitals
+    /// let repr = SumRepr::from([
+    ///     (-1.0, Fermions::try_from((0, 2)).unwrap()),
+    ///     (-1.0, Fermions::try_from((1, 3)).unwrap()),
+    /// ]);
+    /// let fcidump = Fcidump::from_terms(&repr, Header::new(0, 2), 1e-12)?;
+    ///
+    /// assert_eq!(fcidump.header.norb, 2);
+    /// assert_eq!(fcidump.header.nelec, 2);
+    /// assert_eq!(fcidump.one_electron, &[(-1.0, (0, 1))]);
+    ///
+    /// // Spin down only
+    /// let repr = SumRepr::from([(-1.0, Fermions::try_from((0, 2)).unwrap())]);
+    /// assert!(Fcidump::from_terms(&repr, Header::new(1, 1), 1e-12).is_err());
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn from_terms<T>(
+        repr: &SumRepr<T, Fermions>,
+        header: Header,
+        tolerance: T,
+    ) -> Result<Self, Error>
+    where
+        T: Float,
+    {
+        let to_f64 = |value: T| {
+            value
+                .to_f64()
+                .ok_or_else(|| parse_error("cannot convert coefficient"))
+        };
+        let norb = repr
+            .iter()
+            .filter_map(|(_, code)| code.max_index())
+            .max()
+            .map_or(0, |index| Orbital::with_index(index).n + 1)
+            .max(header.norb);
+        if !header.orbsym.is_empty() && header.orbsym.len() != norb as usize {
+            return Err(parse_error(format!(
+                "ORBSYM should have {norb} entries, found: {}",
+                header.orbsym.len()
+            )));
+        }
+
+        // Two-electron codes a+_p a+_q a_r a_p and a+_p a+_r a_q a_p are both
+        // valid and stand for the same term.  Fold them into the orientation
+        // of the expansion of spatial integrals.
+        let mut normalised = SumRepr::new();
+        for (&coeff, &code) in repr.iter() {
+            let two = coeff + coeff;
+            match code {
+                Fermions::Offset => normalised.add_term(code, coeff),
+                Fermions::One {
+                    cr,
+                    an,
+                } => add_one_body(
+                    two,
+                    cr.0.index(),
+                    an.0.index(),
+                    &mut normalised,
+                ),
+                Fermions::Two {
+                    cr,
+                    an,
+                } => add_two_body(
+                    two,
+                    cr.0 .0.index(),
+                    cr.1 .0.index(),
+                    an.0 .0.index(),
+                    an.1 .0.index(),
+                    &mut normalised,
+                ),
+            }
+        }
+        let repr = &normalised;
+
+        let mut core_energy = 0.0;
+        let mut one_electron = HashMap::new();
+        let mut two_electron = HashMap::new();
+        for (&coeff, &code) in repr.iter() {
+            match code {
+                Fermions::Offset => core_energy = to_f64(coeff)?,
+                // Spin down terms determine the spatial integrals.  The other
+                // ones are only checked for consistency.
+                Fermions::One {
+                    cr,
+                    an,
+                } if cr.0.s == Spin::Down && an.0.s == Spin::Down => {
+                    let indices = (cr.0.n, an.0.n);
+                    let mut unit = SumRepr::new();
+                    add_one_electron(T::one(), indices, &mut unit);
+                    if let Some(&scale) = unit.coeff(code) {
+                        one_electron.insert(indices, to_f64(coeff / scale)?);
+                    }
+                }
+                // Terms with electrons of opposite spins determine the
+                // spatial integrals: (pq|rs) from a+_p↓ a+_r↑ a_s↑ a_q↓.
+                Fermions::Two {
+                    cr,
+                    an,
+                } if cr.0 .0.s != cr.1 .0.s && an.0 .0.s != an.1 .0.s => {
+                    let (p, r) = if cr.0 .0.s == Spin::Down {
+                        (cr.0 .0.n, cr.1 .0.n)
+                    } else {
+                        (cr.1 .0.n, cr.0 .0.n)
+                    };
+                    let (s, q) = if an.0 .0.s == Spin::Up {
+                        (an.0 .0.n, an.1 .0.n)
+                    } else {
+                        (an.1 .0.n, an.0 .0.n)
+                    };
+                    let indices = two_electron_key(p, q, r, s);
+                    let mut unit = SumRepr::new();
+                    add_two_electron(T::one(), indices, &mut unit);
+                    if let Some(&scale) = unit.coeff(code) {
+                        two_electron
+                            .entry(indices)
+                            .or_insert(to_f64(coeff / scale)?);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut fcidump = Self {
+            header: Header {
+                norb,
+                ..header
+            },
+            core_energy,
+            one_electron: one_electron
+                .into_iter()
+                .filter(|&(_, value)| value != 0.0)
+                .map(|(key, value)| (value, key))
+                .collect(),
+            two_electron: two_electron
+                .into_iter()
+                .filter(|&(_, value)| value != 0.0)
+                .map(|(key, value)| (value, key))
+                .collect(),
+        };
+        fcidump.one_electron.sort_unstable_by_key(|&(_, key)| key);
+        fcidump.two_electron.sort_unstable_by_key(|&(_, key)| key);
+
+        let mut expanded = SumRepr::new();
+        fcidump.add_to(&mut expanded)?;
+        let diff = repr.diff(&expanded, tolerance, T::zero());
+        let code = diff
+            .left_only
+            .iter()
+            .map(|&(_, code)| code)
+            .chain(diff.right_only.iter().map(|&(_, code)| code))
+            .chain(diff.changed.iter().map(|&(_, _, code)| code))
+            .min();
+        if let Some(code) = code {
+            return Err(parse_error(format!(
+                "term {code} is not consistent with spatial integrals"
+            )));
+        }
+
+        Ok(fcidump)
+    }
+
+    /// Write integrals in the FCIDUMP format.
+    ///
+    /// Two-electron integrals are written first, followed by one-electron
+    /// integrals and the core energy, as is customary.  If the header does
+    /// not specify the symmetry of orbitals, all orbitals are written as
+    /// belonging to the first irreducible representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error, if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::fcidump::{Fcidump, Header};
+    /// let fcidump = Fcidump {
+    ///     header:       Header::new(2, 2),
+    ///     core_energy:  0.5,
+    ///     one_electron: vec![(-1.0, (0, 1))],
+    ///     two_electron: vec![(0.25, (0, 0, 1, 1))],
+    /// };
+    ///
+    /// let mut buf = Vec::new();
+    /// fcidump.write(&mut buf).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     " &FCI NORB=2,NELEC=2,MS2=0,
+    ///   ORBSYM=1,1,
+    ///   ISYM=1,
+    ///  &END
+    ///    2.5000000000000000E-1    1    1    2    2
+    ///    -1.0000000000000000E0    1    2    0    0
+    ///    5.0000000000000000E-1    0    0    0    0
+    /// "
+    /// );
+    /// ```
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let header = &self.header;
+        writeln!(
+            writer,
+            " &FCI NORB={},NELEC={},MS2={},",
+            header.norb, header.nelec, header.ms2
+        )?;
+        write!(writer, "  ORBSYM=")?;
+        if header.orbsym.is_empty() {
+            for _ in 0..header.norb {
+                write!(writer, "1,")?;
+            }
+        } else {
+            for irrep in &header.orbsym {
+                write!(writer, "{irrep},")?;
+            }
+        }
+        writeln!(writer)?;
+        writeln!(writer, "  ISYM={},", header.isym)?;
+        writeln!(writer, " &END")?;
+
+        for &(value, (i, j, k, l)) in &self.two_electron {
+            write_integral(writer, value, [i + 1, j + 1, k + 1, l + 1])?;
+        }
+        for &(value, (i, j)) in &self.one_electron {
+            write_integral(writer, value, [i + 1, j + 1, 0, 0])?;
+        }
+        write_integral(writer, self.core_energy, [0; 4])
+    }
+}
+
+fn write_integral<W: Write>(
+    writer: &mut W,
+    value: f64,
+    [i, j, k, l]: [u32; 4],
+) -> io::Result<()> {
+    let value = format!("{value:.16E}");
+    writeln!(writer, "{value:>24}{i:5}{j:5}{k:5}{l:5}")
+}
+
+fn parse_error(msg: impl Into<String>) -> Error {
+    Error::Fcidump {
+        msg: msg.into()
+    }
+}
+
+/// Parse number, allowing for Fortran exponents: `1.0D-03`.
+fn parse_value<V: FromStr>(token: &str) -> Result<V, Error> {
+    token
+        .replace(['D', 'd'], "E")
+        .parse()
+        .map_err(|_| parse_error(format!("invalid number: {token}")))
+}
+
+fn parse_single<V: FromStr>(
+    key: &str,
+    values: &[&str],
+) -> Result<V, Error> {
+    match values {
+        [value] => parse_value(value),
+        _ => Err(parse_error(format!("{key} should have a single value"))),
+    }
+}
+
+fn parse_header(text: &str) -> Result<Header, Error> {
+    let text = text.replace(',', " ").replace('=', " = ");
+    let tokens: Vec<_> = text.split_whitespace().collect();
+
+    let mut fields: Vec<(String, Vec<&str>)> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens.get(i + 1) == Some(&"=") {
+            fields.push((tokens[i].to_uppercase(), Vec::new()));
+            i += 2;
+        } else {
+            let (_, values) = fields.last_mut().ok_or_else(|| {
+                parse_error(format!(
+                    "unexpected token in header: {}",
+                    tokens[i]
+                ))
+            })?;
+            values.push(tokens[i]);
+            i += 1;
+        }
+    }
+
+    let mut norb = None;
+    let mut header = Header::new(0, 0);
+    for (key, values) in fields {
+        match key.as_str() {
+            "NORB" => norb = Some(parse_single(&key, &values)?),
+            "NELEC" => header.nelec = parse_single(&key, &values)?,
+            "MS2" => header.ms2 = parse_single(&key, &values)?,
+            "ISYM" => header.isym = parse_single(&key, &values)?,
+            "ORBSYM" => {
+                header.orbsym = values
+                    .iter()
+                    .map(|value| parse_value(value))
+                    .collect::<Result<_, _>>()?;
+            }
+            "UHF" | "IUHF" => {
+                let value = values.first().map(|v| v.to_uppercase());
+                if matches!(value.as_deref(), Some(".TRUE." | "T" | "1")) {
+                    return Err(parse_error(
+                        "unrestricted integrals are not supported",
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+    header.norb = norb.ok_or_else(|| parse_error("missing NORB"))?;
+
+    if !header.orbsym.is_empty() && header.orbsym.len() != header.norb as usize
+    {
+        return Err(parse_error("ORBSYM should have NORB values"));
+    }
+
+    Ok(header)
+}
+
+/// Representative of indices `(i, j)` under the symmetry `i <-> j`.
+fn one_electron_key(
+    i: u32,
+    j: u32,
+) -> (u32, u32) {
+    (i.min(j), i.max(j))
+}
+
+/// Representative of indices `(i, j, k, l)` under the 8-fold symmetry.
+fn two_electron_key(
+    i: u32,
+    j: u32,
+    k: u32,
+    l: u32,
+) -> (u32, u32, u32, u32) {
+    let (i, j) = one_electron_key(i, j);
+    let (k, l) = one_electron_key(k, l);
+    if (i, j) <= (k, l) {
+        (i, j, k, l)
+    } else {
+        (k, l, i, j)
+    }
+}
+
+impl FromStr for Fcidump {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let mut header_text = String::new();
+        loop {
+            let line =
+                lines.next().ok_or_else(|| parse_error("missing &END"))?;
+            let end =
+                line.find("&END").or_else(|| line.find("&end")).or_else(|| {
+                    line.trim_end().ends_with('/').then_some(line.len())
+                });
+            let line = &line[..end.unwrap_or(line.len())];
+            header_text.push_str(line.trim_end_matches('/'));
+            header_text.push(' ');
+            if end.is_some() {
+                break;
+            }
+        }
+        let header_text = header_text.trim_start();
+        let header_text = ["&FCI", "&fci"]
+            .iter()
+            .find_map(|prefix| header_text.strip_prefix(prefix))
+            .ok_or_else(|| parse_error("header should start with &FCI"))?;
+        let header = parse_header(header_text)?;
+
+        let mut core_energy = 0.0;
+        let mut one_electron = HashMap::new();
+        let mut two_electron = HashMap::new();
+        for line in lines {
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            let Some((value, indices)) = tokens.split_first() else {
+                continue;
+            };
+            let value: f64 = parse_value(value)?;
+            let indices = indices
+                .iter()
+                .map(|index| parse_value::<u32>(index))
+                .collect::<Result<Vec<_>, _>>()?;
+            if indices.iter().any(|&index| index > header.norb) {
+                return Err(parse_error(format!(
+                    "orbital index out of range: {line}"
+                )));
+            }
+            match indices[..] {
+                [0, 0, 0, 0] => core_energy = value,
+                [_, 0, 0, 0] => (),
+                [i, j, 0, 0] if i != 0 && j != 0 => {
+                    one_electron.insert(one_electron_key(i - 1, j - 1), value);
+                }
+                [i, j, k, l] if i != 0 && j != 0 && k != 0 && l != 0 => {
+                    two_electron.insert(
+                        two_electron_key(i - 1, j - 1, k - 1, l - 1),
+                        value,
+                    );
+                }
+                _ => {
+                    return Err(parse_error(format!(
+                        "invalid integral line: {line}"
+                    )))
+                }
+            }
+        }
+
+        let mut one_electron: Vec<_> = one_electron
+            .into_iter()
+            .map(|(key, value)| (value, key))
+            .collect();
+        one_electron.sort_unstable_by_key(|&(_, key)| key);
+        let mut two_electron: Vec<_> = two_electron
+            .into_iter()
+            .map(|(key, value)| (value, key))
+            .collect();
+        two_electron.sort_unstable_by_key(|&(_, key)| key);
+
+        Ok(Self {
+            header,
+            core_energy,
+            one_electron,
+            two_electron,
+        })
+    }
+}
+
+/// Distinct permutations of indices under the 8-fold symmetry.
+fn two_electron_perms(
+    (i, j, k, l): (u32, u32, u32, u32)
+) -> Vec<(u32, u32, u32, u32)> {
+    let mut perms = vec![
+        (i, j, k, l),
+        (j, i, k, l),
+        (i, j, l, k),
+        (j, i, l, k),
+        (k, l, i, j),
+        (l, k, i, j),
+        (k, l, j, i),
+        (l, k, j, i),
+    ];
+    perms.sort_unstable();
+    perms.dedup();
+    perms
+}
+
+/// Add spin-orbital terms of the one-electron integral `h_ij`.
+fn add_one_electron<T>(
+    value: T,
+    (i, j): (u32, u32),
+    repr: &mut impl Extend<(T, Fermions)>,
+) where
+    T: Float,
+{
+    let perms: &[_] = if i == j { &[(i, j)] } else { &[(i, j), (j, i)] };
+    for &(p, q) in perms {
+        for s in Spin::both() {
+            add_one_body(value, spin_orbital(p, s), spin_orbital(q, s), repr);
+        }
+    }
+}
+
+/// Add spin-orbital terms of the two-electron integral `(ij|kl)`.
+fn add_two_electron<T>(
+    value: T,
+    indices: (u32, u32, u32, u32),
+    repr: &mut impl Extend<(T, Fermions)>,
+) where
+    T: Float,
+{
+    let value = value / (T::one() + T::one());
+    for (p, q, r, s) in two_electron_perms(indices) {
+        for sigma in Spin::both() {
+            for tau in Spin::both() {
+                add_two_body(
+                    value,
+                    spin_orbital(p, sigma),
+                    spin_orbital(r, tau),
+                    spin_orbital(s, tau),
+                    spin_orbital(q, sigma),
+                    repr,
+                );
+            }
+        }
+    }
+}
+
+impl<T> Terms<(T, Fermions)> for &Fcidump
+where
+    T: Float,
+{
+    type Error = Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, Fermions)>,
+    ) -> Result<(), Self::Error> {
+        let coeff = |value: f64| {
+            T::from(value).ok_or_else(|| {
+                parse_error(format!("cannot convert coefficient: {value}"))
+            })
+        };
+        if self.header.norb > u32::MAX / 2 {
+            return Err(parse_error("too many orbitals"));
+        }
+
+        repr.extend(Some((coeff(self.core_energy)?, Fermions::Offset)));
+        for &(value, indices) in &self.one_electron {
+            add_one_electron(coeff(value)?, indices, repr);
+        }
+        for &(value, indices) in &self.two_electron {
+            add_two_electron(coeff(value)?, indices, repr);
+        }
+
+        Ok(())
+    }
+}
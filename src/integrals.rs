@@ -0,0 +1,499 @@
+//! Hamiltonians from dense tensors of molecular integrals.
+//!
+//! The electronic Hamiltonian in a basis of `n` spatial orbitals reads, in
+//! the chemists' notation:
+//!
+//! ```text
+//! H = c + sum_{pq,σ} h_pq a+_pσ a_qσ
+//!       + 1/2 sum_{pqrs,στ} (pq|rs) a+_pσ a+_rτ a_sτ a_qσ,
+//! ```
+//!
+//! or equivalently, in the physicists' notation, with `<pq|rs> = (pr|qs)`:
+//!
+//! ```text
+//! H = c + sum_{pq,σ} h_pq a+_pσ a_qσ
+//!       + 1/2 sum_{pqrs,στ} <pq|rs> a+_pσ a+_qτ a_sτ a_rσ.
+//! ```
+//!
+//! Spin orbitals are indexed as in [`Orbital::index()`].
+//!
+//! # Unrestricted integrals
+//!
+//! Integrals computed with spin-unrestricted orbitals, e.g. from UHF or
+//! ROHF references, depend on spin:
+//!
+//! ```text
+//! H = c + sum_{pq,σ} h^σ_pq a+_pσ a_qσ
+//!       + 1/2 sum_{pqrs,στ} (p^σ q^σ|r^τ s^τ) a+_pσ a+_rτ a_sτ a_qσ.
+//! ```
+//!
+//! There are two blocks of one-electron integrals, `h^α` and `h^β`, and
+//! three blocks of two-electron integrals: `(αα|αα)`, `(ββ|ββ)` and
+//! `(αα|ββ)`.  The remaining block, `(ββ|αα)`, follows from the
+//! permutational symmetry: `(p^β q^β|r^α s^α) = (r^α s^α|p^β q^β)`.  Spin
+//! `α` corresponds to [`Spin::Up`], and spin `β` to [`Spin::Down`].
+//!
+//! # Canonical codes
+//!
+//! A term of `SumRepr<T, Fermions>` with coefficient `c` and code `T`, other
+//! than [`Fermions::Offset`], stands for `c * (T + T^†)`, and codes are kept
+//! in the canonical order enforced by [`Fermions::one_electron()`] and
+//! [`Fermions::two_electron()`].  Each string of operators in the expansion
+//! above is therefore:
+//!
+//! 1. brought to the form `a+_p a+_q a_r a_s` with `p < q` and `r > s`, by
+//!    anticommuting the operators and flipping the sign (strings with repeated
+//!    creation or annihilation operators vanish),
+//! 2. replaced by its adjoint `a+_s a+_r a_q a_p`, if `p > s` (or if `p == s`
+//!    and `q > r`), so that the code is canonical,
+//! 3. added with *half* of its coefficient.
+//!
+//! Since the Hamiltonian is Hermitian, every string comes together with its
+//! adjoint, which is mapped to the same code.  The two halves then add up
+//! to the coefficient of `T + T^†`.  Self-adjoint codes, like `a+_p a_p`,
+//! receive half of the coefficient of `T`, as `T + T^† = 2T`.  If the
+//! integrals do not describe a Hermitian operator, the result is its
+//! Hermitian part.
+
+use num::Float;
+
+use crate::{
+    code::fermions::{
+        Fermions,
+        Orbital,
+        Spin,
+    },
+    terms::{
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+/// Index order of two-electron integrals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Convention {
+    /// Chemists' notation: `(pq|rs)`, as in PySCF and FCIDUMP.
+    #[default]
+    Chemist,
+    /// Physicists' notation: `<pq|rs> = (pr|qs)`.
+    Physicist,
+}
+
+/// Dense tensors of molecular integrals.
+///
+/// One-electron integrals `h_pq` are stored as a flat slice of length `n^2`
+/// in the row-major order: `h[p * n + q]`, and two-electron integrals as a
+/// flat slice of length `n^4`: `h[((p * n + q) * n + r) * n + s]`, where `n`
+/// is the number of spatial orbitals.  Integrals that are not given are
+/// zero.
+///
+/// Spin-restricted integrals are set with [`Integrals::one_electron()`] and
+/// [`Integrals::two_electron()`].  Blocks of unrestricted integrals can be
+/// set, or overwritten, separately for each spin with
+/// [`Integrals::one_electron_spin()`] and
+/// [`Integrals::two_electron_spin()`].
+///
+/// Implements [`Terms`] for the Hamiltonian expanded to spin orbitals, see
+/// the [module documentation](self).  Integrals equal to zero are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{
+/// #     code::fermions::Fermions,
+/// #     integrals::{Convention, Integrals},
+/// #     terms::SumRepr,
+/// # };
+/// # fn main() -> Result<(), f2q::Error> {
+/// // One orbital: E(doubly occupied) = 0.25 + 2 * (-1.0) + 0.5
+/// let h1 = [-1.0];
+/// let h2 = [0.5];
+/// let integrals = Integrals::new(1, Convention::Chemist)
+///     .constant(0.25)
+///     .one_electron(&h1)
+///     .two_electron(&h2);
+///
+/// let repr = SumRepr::try_from(integrals)?;
+///
+/// assert_eq!(repr.coeff(Fermions::Offset), Some(&0.25));
+/// assert_eq!(repr.coeff(Fermions::try_from((0, 0)).unwrap()), Some(&-0.5));
+/// assert_eq!(repr.coeff(Fermions::try_from((1, 1)).unwrap()), Some(&-0.5));
+/// assert_eq!(
+///     repr.coeff(Fermions::try_from((0, 1, 1, 0)).unwrap()),
+///     Some(&0.25)
+/// );
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Integrals<'a> {
+    num_orbitals:       u32,
+    convention:         Convention,
+    constant:           f64,
+    // Indexed by spin
+    one_electron:       [Option<&'a [f64]>; 2],
+    // Same-spin blocks, indexed by spin
+    two_electron:       [Option<&'a [f64]>; 2],
+    // Opposite-spin block, with the spin of the first pair of indices
+    two_electron_mixed: Option<(Spin, &'a [f64])>,
+}
+
+impl<'a> Integrals<'a> {
+    /// Create integrals over `num_orbitals` spatial orbitals, all equal to
+    /// zero.
+    #[must_use]
+    pub fn new(
+        num_orbitals: u32,
+        convention: Convention,
+    ) -> Self {
+        Self {
+            num_orbitals,
+            convention,
+            constant: 0.0,
+            one_electron: [None; 2],
+            two_electron: [None; 2],
+            two_electron_mixed: None,
+        }
+    }
+
+    /// Set the constant term, e.g. the nuclear repulsion energy.
+    #[must_use]
+    pub fn constant(
+        self,
+        constant: f64,
+    ) -> Self {
+        Self {
+            constant,
+            ..self
+        }
+    }
+
+    /// Set one-electron integrals for both spins: a slice of length `n^2`.
+    #[must_use]
+    pub fn one_electron(
+        self,
+        h: &'a [f64],
+    ) -> Self {
+        Self {
+            one_electron: [Some(h); 2],
+            ..self
+        }
+    }
+
+    /// Set two-electron integrals for all spins: a slice of length `n^4`.
+    #[must_use]
+    pub fn two_electron(
+        self,
+        h: &'a [f64],
+    ) -> Self {
+        Self {
+            two_electron: [Some(h); 2],
+            two_electron_mixed: Some((Spin::Down, h)),
+            ..self
+        }
+    }
+
+    /// Set one-electron integrals `h^σ`: a slice of length `n^2`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{
+    /// #     code::fermions::{Fermions, Orbital, Spin},
+    /// #     integrals::{Convention, Integrals},
+    /// #     terms::SumRepr,
+    /// # };
+    /// let h_alpha = [-1.0];
+    /// let integrals = Integrals::new(1, Convention::Chemist)
+    ///     .one_electron_spin(Spin::Up, &h_alpha);
+    ///
+    /// let repr = SumRepr::<f64, _>::try_from(integrals).unwrap();
+    /// let up = Orbital::new(0, Spin::Up).index();
+    ///
+    /// assert_eq!(repr.len(), 2);
+    /// assert_eq!(
+    ///     repr.coeff(Fermions::try_from((up, up)).unwrap()),
+    ///     Some(&-0.5)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn one_electron_spin(
+        self,
+        sigma: Spin,
+        h: &'a [f64],
+    ) -> Self {
+        let mut one_electron = self.one_electron;
+        one_electron[usize::from(sigma)] = Some(h);
+        Self {
+            one_electron,
+            ..self
+        }
+    }
+
+    /// Set the block of two-electron integrals `(p^σ q^σ|r^τ s^τ)` in the
+    /// chemists' notation, or `<p^σ q^τ|r^σ s^τ>` in the physicists'
+    /// notation: a slice of length `n^4`.
+    ///
+    /// The opposite-spin block for `σ != τ` determines also the block with
+    /// spins exchanged, so setting it for `(σ, τ)` overwrites the block set
+    /// for `(τ, σ)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{
+    /// #     code::fermions::{Fermions, Orbital, Spin},
+    /// #     integrals::{Convention, Integrals},
+    /// #     terms::SumRepr,
+    /// # };
+    /// // (αα|ββ) block, as e.g. in PySCF
+    /// let h_ab = [0.5];
+    /// let integrals = Integrals::new(1, Convention::Chemist).two_electron_spin(
+    ///     Spin::Up,
+    ///     Spin::Down,
+    ///     &h_ab,
+    /// );
+    ///
+    /// let repr = SumRepr::<f64, _>::try_from(integrals).unwrap();
+    /// let down = Orbital::new(0, Spin::Down).index();
+    /// let up = Orbital::new(0, Spin::Up).index();
+    ///
+    /// // 0.5 * n_up * n_down
+    /// assert_eq!(
+    ///     repr.coeff(Fermions::try_from((down, up, up, down)).unwrap()),
+    ///     Some(&0.25)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn two_electron_spin(
+        self,
+        sigma: Spin,
+        tau: Spin,
+        h: &'a [f64],
+    ) -> Self {
+        if sigma == tau {
+            let mut two_electron = self.two_electron;
+            two_electron[usize::from(sigma)] = Some(h);
+            Self {
+                two_electron,
+                ..self
+            }
+        } else {
+            Self {
+                two_electron_mixed: Some((sigma, h)),
+                ..self
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn num_orbitals(&self) -> u32 {
+        self.num_orbitals
+    }
+
+    #[must_use]
+    pub fn convention(&self) -> Convention {
+        self.convention
+    }
+}
+
+fn integrals_error(msg: impl Into<String>) -> Error {
+    Error::Integrals {
+        msg: msg.into()
+    }
+}
+
+/// Check the length of a flat tensor with `dim` indices of size `n`.
+fn check_len(
+    h: &[f64],
+    n: u32,
+    dim: u32,
+) -> Result<(), Error> {
+    let len = usize::try_from(n)
+        .ok()
+        .and_then(|n| n.checked_pow(dim))
+        .ok_or_else(|| integrals_error("too many orbitals"))?;
+    if h.len() == len {
+        Ok(())
+    } else {
+        Err(integrals_error(format!(
+            "tensor of {dim} indices should have length {len}, found: {}",
+            h.len()
+        )))
+    }
+}
+
+/// Indices `(p, q)` of a flat tensor, in the row-major order.
+pub(crate) fn pairs(n: u32) -> impl Iterator<Item = (u32, u32)> {
+    (0..n).flat_map(move |p| (0..n).map(move |q| (p, q)))
+}
+
+/// Indices `(p, q, r, s)` of a flat tensor, in the row-major order.
+pub(crate) fn quads(n: u32) -> impl Iterator<Item = (u32, u32, u32, u32)> {
+    pairs(n).flat_map(move |(p, q)| pairs(n).map(move |(r, s)| (p, q, r, s)))
+}
+
+/// Index of a spin orbital.
+pub(crate) fn spin_orbital(
+    n: u32,
+    s: Spin,
+) -> u32 {
+    Orbital::new(n, s).index()
+}
+
+/// Add `coeff * a+_p a_q`, and implicitly its adjoint, to the sum.
+///
+/// The string and its adjoint share a canonical code, so each of them
+/// contributes half of its coefficient.
+pub(crate) fn add_one_body<T>(
+    coeff: T,
+    p: u32,
+    q: u32,
+    repr: &mut impl Extend<(T, Fermions)>,
+) where
+    T: Float,
+{
+    let half = coeff / (T::one() + T::one());
+    repr.extend(Some((
+        half,
+        Fermions::try_from((p.min(q), p.max(q)))
+            .expect("indices in canonical order"),
+    )));
+}
+
+/// Add `coeff * a+_p a+_q a_r a_s`, and implicitly its adjoint, to the sum.
+///
+/// See [`add_one_body()`].
+pub(crate) fn add_two_body<T>(
+    coeff: T,
+    p: u32,
+    q: u32,
+    r: u32,
+    s: u32,
+    repr: &mut impl Extend<(T, Fermions)>,
+) where
+    T: Float,
+{
+    if p == q || r == s {
+        return;
+    }
+    let mut half = coeff / (T::one() + T::one());
+    let (p, q) = if p < q {
+        (p, q)
+    } else {
+        half = -half;
+        (q, p)
+    };
+    let (r, s) = if r > s {
+        (r, s)
+    } else {
+        half = -half;
+        (s, r)
+    };
+    // The adjoint is a+_s a+_r a_q a_p.
+    let code = if p < s || (p == s && q <= r) {
+        (p, q, r, s)
+    } else {
+        (s, r, q, p)
+    };
+    repr.extend(Some((
+        half,
+        Fermions::try_from(code).expect("indices in canonical order"),
+    )));
+}
+
+impl<'a, T> Terms<(T, Fermions)> for Integrals<'a>
+where
+    T: Float,
+{
+    type Error = Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, Fermions)>,
+    ) -> Result<(), Self::Error> {
+        let n = self.num_orbitals;
+        if n > u32::MAX / 2 {
+            return Err(integrals_error("too many orbitals"));
+        }
+        for h in self.one_electron.into_iter().flatten() {
+            check_len(h, n, 2)?;
+        }
+        for h in self
+            .two_electron
+            .into_iter()
+            .chain([self.two_electron_mixed.map(|(_, h)| h)])
+            .flatten()
+        {
+            check_len(h, n, 4)?;
+        }
+        let coeff = |value: f64| {
+            T::from(value).ok_or_else(|| {
+                integrals_error(format!("cannot convert coefficient: {value}"))
+            })
+        };
+
+        repr.extend(Some((coeff(self.constant)?, Fermions::Offset)));
+
+        for sigma in Spin::both() {
+            let Some(h) = self.one_electron[usize::from(sigma)] else {
+                continue;
+            };
+            for ((p, q), value) in pairs(n).zip(h).filter(|(_, &v)| v != 0.0) {
+                add_one_body(
+                    coeff(*value)?,
+                    spin_orbital(p, sigma),
+                    spin_orbital(q, sigma),
+                    repr,
+                );
+            }
+        }
+
+        // Same-spin blocks enter with the factor 1/2, and the opposite-spin
+        // block stands for both (σ, τ) and (τ, σ).
+        let blocks = Spin::both()
+            .filter_map(|sigma| {
+                self.two_electron[usize::from(sigma)]
+                    .map(|h| (0.5, sigma, sigma, h))
+            })
+            .chain(
+                self.two_electron_mixed
+                    .map(|(sigma, h)| (1.0, sigma, sigma.flip(), h)),
+            );
+        for (factor, sigma, tau, h) in blocks {
+            for ((p, q, r, s), value) in
+                quads(n).zip(h).filter(|(_, &v)| v != 0.0)
+            {
+                // Operators a+_i a+_j a_k a_l, with spins σ, τ, τ, σ.
+                let (i, j, k, l) = match self.convention {
+                    Convention::Chemist => (p, r, s, q),
+                    Convention::Physicist => (p, q, s, r),
+                };
+                add_two_body(
+                    coeff(factor * value)?,
+                    spin_orbital(i, sigma),
+                    spin_orbital(j, tau),
+                    spin_orbital(k, tau),
+                    spin_orbital(l, sigma),
+                    repr,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, T> TryFrom<Integrals<'a>> for SumRepr<T, Fermions>
+where
+    T: Float,
+{
+    type Error = Error;
+
+    fn try_from(value: Integrals<'a>) -> Result<Self, Self::Error> {
+        let mut repr = Self::new();
+        value.add_to(&mut repr)?;
+        Ok(repr)
+    }
+}
@@ -0,0 +1,308 @@
+//! Data for PREPARE oracles of block encodings.
+//!
+//! A Hamiltonian `H = h_0 I + Σ_j h_j P_j` is block-encoded as a linear
+//! combination of unitaries (LCU) with the normalization
+//! `λ = Σ_{j≠0} |h_j|`.  The PREPARE oracle loads the amplitudes
+//! `sqrt(|h_j| / λ)`, which is commonly implemented by coherent alias
+//! sampling: a register uniform over `0..L` is compared against a `μ`-bit
+//! uniform register using a table of "keep" thresholds, and swapped with an
+//! "alternate" index if the threshold is exceeded.
+//!
+//! [`Prepare`] holds the classical data such circuits are generated from.
+//! The probabilities `|h_j| / λ` are first rounded to integer weights summing
+//! to `L * 2^μ`, and the alias table is then computed exactly, so that the
+//! distribution sampled by the circuit deviates from the ideal one by at most
+//! `1 / (L * 2^μ)` for each term.
+//!
+//! # JSON format
+//!
+//! The struct serializes to an object with the following keys.  The arrays
+//! are indexed by term `j = 0..num_terms`, in the order of decreasing
+//! absolute values of coefficients:
+//!
+//! - `"type"`: always `"lcu_prepare"`,
+//! - `"num_terms"`: number of terms `L` (excluding the identity),
+//! - `"num_bits"`: number of bits `μ` of keep thresholds,
+//! - `"lambda"`: normalization `λ`,
+//! - `"identity"`: coefficient of the identity, `h_0`,
+//! - `"codes"`: Pauli strings as in the `"qubits"` encoding,
+//! - `"coeffs"`: absolute values of coefficients, `|h_j|`,
+//! - `"signs"`: signs of coefficients, `1` or `-1`,
+//! - `"keep"`: keep thresholds, integers within `0..2^μ`,
+//! - `"alt"`: alternate indices, integers within `0..L`.
+//!
+//! Sampling index `j` uniformly and `σ` uniformly from `0..2^μ`, the outcome
+//! is `j` if `σ < keep[j]`, or `alt[j]` otherwise.
+//!
+//! # Binary format
+//!
+//! All numbers are little-endian.  The file consists of:
+//!
+//! ```text
+//! magic       8 bytes     b"F2QLCU\0\0"
+//! version     u32         1
+//! num_bits    u32
+//! num_terms   u64         L
+//! lambda      f64
+//! identity    f64
+//! codes       L x u128    Pauli strings, see: Paulis::index()
+//! coeffs      L x f64
+//! signs       L x u8      0 for positive, 1 for negative coefficients
+//! keep        L x u64
+//! alt         L x u64
+//! ```
+
+use std::io::Write;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    code::qubits::Paulis,
+    terms::SumRepr,
+    Error,
+};
+
+/// Magic bytes at the start of the binary format.
+pub const MAGIC: &[u8; 8] = b"F2QLCU\0\0";
+
+/// Version of the binary format.
+pub const VERSION: u32 = 1;
+
+/// Maximal number of bits of keep thresholds.
+pub const MAX_NUM_BITS: u32 = 32;
+
+/// Classical data for the PREPARE oracle.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, lcu::Prepare, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (1.0, Paulis::identity()),
+///     (-0.5, Paulis::with_ops([Sigma::X])),
+///     (0.25, Paulis::with_ops([Sigma::Z])),
+///     (0.25, Paulis::with_ops([Sigma::Y])),
+/// ]);
+/// let prep = Prepare::new(&repr, 4).unwrap();
+///
+/// assert_eq!(prep.lambda, 1.0);
+/// assert_eq!(prep.identity, 1.0);
+/// assert_eq!(prep.coeffs, &[0.5, 0.25, 0.25]);
+/// assert_eq!(prep.signs, &[-1, 1, 1]);
+///
+/// // Sampled distribution: (2^4 * 3) * [0.5, 0.25, 0.25] = [24, 12, 12]
+/// assert_eq!(prep.weights(), &[24, 12, 12]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Prepare {
+    r#type:        String,
+    pub num_terms: u64,
+    pub num_bits:  u32,
+    pub lambda:    f64,
+    pub identity:  f64,
+    pub codes:     Vec<Paulis>,
+    pub coeffs:    Vec<f64>,
+    pub signs:     Vec<i8>,
+    pub keep:      Vec<u64>,
+    pub alt:       Vec<u64>,
+}
+
+impl Prepare {
+    /// Compute the data from a sum of Pauli strings.
+    ///
+    /// Terms are sorted by decreasing absolute values of coefficients, with
+    /// ties resolved by the order of codes.  Terms with zero coefficients are
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if there are no terms other than the identity,
+    /// coefficients are not finite, `num_bits` is not within
+    /// `1..=MAX_NUM_BITS`, or `L * 2^num_bits` does not fit in `u64`.
+    pub fn new(
+        repr: &SumRepr<f64, Paulis>,
+        num_bits: u32,
+    ) -> Result<Self, Error> {
+        if !(1..=MAX_NUM_BITS).contains(&num_bits) {
+            return Err(Error::Lcu {
+                msg: format!(
+                    "number of bits must be within 1..={MAX_NUM_BITS}"
+                ),
+            });
+        }
+
+        let mut terms: Vec<_> = repr
+            .iter()
+            .filter(|(coeff, code)| {
+                **code != Paulis::identity() && **coeff != 0.0
+            })
+            .map(|(&coeff, &code)| (coeff, code))
+            .collect();
+        if terms.iter().any(|(coeff, _)| !coeff.is_finite()) {
+            return Err(Error::Lcu {
+                msg: "coefficients must be finite".to_string(),
+            });
+        }
+        terms.sort_by(|(x, p), (y, q)| {
+            y.abs().total_cmp(&x.abs()).then_with(|| p.cmp(q))
+        });
+        if terms.is_empty() {
+            return Err(Error::Lcu {
+                msg: "no terms other than identity".to_string(),
+            });
+        }
+
+        let num_terms = terms.len() as u64;
+        let capacity = 1_u64 << num_bits;
+        let total = num_terms.checked_mul(capacity).ok_or(Error::Lcu {
+            msg: "too many terms for given number of bits".to_string(),
+        })?;
+
+        let lambda: f64 = terms.iter().map(|(coeff, _)| coeff.abs()).sum();
+        let weights = discretize(&terms, lambda, total);
+        let (keep, alt) = alias_table(&weights, capacity);
+
+        Ok(Self {
+            r#type: "lcu_prepare".to_string(),
+            num_terms,
+            num_bits,
+            lambda,
+            identity: repr.coeff(Paulis::identity()).copied().unwrap_or(0.0),
+            codes: terms.iter().map(|(_, code)| *code).collect(),
+            coeffs: terms.iter().map(|(coeff, _)| coeff.abs()).collect(),
+            signs: terms
+                .iter()
+                .map(|(coeff, _)| if *coeff < 0.0 { -1 } else { 1 })
+                .collect(),
+            keep,
+            alt,
+        })
+    }
+
+    /// Integer weights of terms sampled by the alias table.
+    ///
+    /// The weights sum up to `num_terms * 2^num_bits`.
+    #[must_use]
+    pub fn weights(&self) -> Vec<u64> {
+        let capacity = 1_u64 << self.num_bits;
+        let mut weights = self.keep.clone();
+        for (j, &alt) in self.alt.iter().enumerate() {
+            let alt = usize::try_from(alt).expect("index fits in usize");
+            weights[alt] += capacity - self.keep[j];
+        }
+        weights
+    }
+
+    /// Write the data in the binary format.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error, if writing fails.
+    pub fn write_binary<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.num_bits.to_le_bytes())?;
+        writer.write_all(&self.num_terms.to_le_bytes())?;
+        writer.write_all(&self.lambda.to_le_bytes())?;
+        writer.write_all(&self.identity.to_le_bytes())?;
+        for code in &self.codes {
+            writer.write_all(&code.index().to_le_bytes())?;
+        }
+        for coeff in &self.coeffs {
+            writer.write_all(&coeff.to_le_bytes())?;
+        }
+        for sign in &self.signs {
+            writer.write_all(&[u8::from(*sign < 0)])?;
+        }
+        for keep in &self.keep {
+            writer.write_all(&keep.to_le_bytes())?;
+        }
+        for alt in &self.alt {
+            writer.write_all(&alt.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Round probabilities `|h_j| / λ` to integer weights summing to `total`.
+///
+/// The remainder is distributed to terms with largest rounding errors.
+fn discretize(
+    terms: &[(f64, Paulis)],
+    lambda: f64,
+    total: u64,
+) -> Vec<u64> {
+    #[allow(clippy::cast_precision_loss)]
+    let scale = total as f64 / lambda;
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let (mut weights, mut errors): (Vec<_>, Vec<_>) = terms
+        .iter()
+        .enumerate()
+        .map(|(j, (coeff, _))| {
+            let exact = coeff.abs() * scale;
+            let weight = exact.floor() as u64;
+            (weight, (exact - weight as f64, j))
+        })
+        .unzip();
+
+    let assigned: u64 = weights.iter().sum();
+    if assigned > total {
+        // Rounding errors in floating point arithmetic: take away from
+        // the largest weights
+        let mut excess = assigned - total;
+        for w in &mut weights {
+            let d = excess.min(*w);
+            *w -= d;
+            excess -= d;
+        }
+        return weights;
+    }
+
+    errors.sort_by(|(x, i), (y, j)| y.total_cmp(x).then(i.cmp(j)));
+    let remainder = usize::try_from(total - assigned).unwrap_or(usize::MAX);
+    for (_, j) in errors.iter().cycle().take(remainder) {
+        weights[*j] += 1;
+    }
+    weights
+}
+
+/// Alias table for integer weights, each column of capacity `capacity`.
+///
+/// The weights must sum up to `weights.len() * capacity`.
+fn alias_table(
+    weights: &[u64],
+    capacity: u64,
+) -> (Vec<u64>, Vec<u64>) {
+    let mut keep = weights.to_vec();
+    let mut alt: Vec<_> = (0..weights.len() as u64).collect();
+
+    let (mut small, mut large): (Vec<_>, Vec<_>) =
+        (0..weights.len()).partition(|&j| keep[j] < capacity);
+    while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+        small.pop();
+        alt[s] = l as u64;
+        keep[l] -= capacity - keep[s];
+        if keep[l] < capacity {
+            large.pop();
+            small.push(l);
+        }
+    }
+    // Full columns always return themselves
+    for j in large {
+        keep[j] = 0;
+        alt[j] = j as u64;
+    }
+    debug_assert!(small.is_empty(), "weights sum up to L * capacity");
+
+    (keep, alt)
+}
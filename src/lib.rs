@@ -8,8 +8,20 @@
 
 use std::fmt::Display;
 
+pub mod circuit;
 pub mod code;
+pub mod eigen;
+pub mod fcidump;
+pub mod integrals;
+pub mod lcu;
 pub mod map;
+pub mod matrix;
+pub mod measure;
+pub mod openfermion;
+pub mod qiskit;
+pub mod rotation;
+pub mod sample;
+pub mod stream;
 pub mod terms;
 
 pub(crate) mod math;
@@ -20,6 +32,24 @@ mod serialize;
 pub enum Error {
     /// Invalid qubit index in a Pauli string
     QubitIndex { msg: String },
+    /// Eigensolver failure
+    Eigen { msg: String },
+    /// Invalid input for a linear combination of unitaries
+    Lcu { msg: String },
+    /// Circuit synthesis failure
+    Circuit { msg: String },
+    /// Invalid measurement data
+    Measure { msg: String },
+    /// Invalid FCIDUMP data
+    Fcidump { msg: String },
+    /// Invalid tensors of molecular integrals
+    Integrals { msg: String },
+    /// Invalid string form of an OpenFermion operator
+    OpenFermion { msg: String },
+    /// Invalid Qiskit operator
+    Qiskit { msg: String },
+    /// Invalid orbital rotation
+    Rotation { msg: String },
 }
 
 impl Display for Error {
@@ -31,6 +61,33 @@ impl Display for Error {
             Self::QubitIndex {
                 msg,
             } => write!(f, "PauliIndex: {msg}"),
+            Self::Eigen {
+                msg,
+            } => write!(f, "Eigen: {msg}"),
+            Self::Lcu {
+                msg,
+            } => write!(f, "Lcu: {msg}"),
+            Self::Circuit {
+                msg,
+            } => write!(f, "Circuit: {msg}"),
+            Self::Measure {
+                msg,
+            } => write!(f, "Measure: {msg}"),
+            Self::Fcidump {
+                msg,
+            } => write!(f, "Fcidump: {msg}"),
+            Self::Integrals {
+                msg,
+            } => write!(f, "Integrals: {msg}"),
+            Self::OpenFermion {
+                msg,
+            } => write!(f, "OpenFermion: {msg}"),
+            Self::Qiskit {
+                msg,
+            } => write!(f, "Qiskit: {msg}"),
+            Self::Rotation {
+                msg,
+            } => write!(f, "Rotation: {msg}"),
         }
     }
 }
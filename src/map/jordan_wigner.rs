@@ -1,4 +1,24 @@
+//! Jordan-Wigner mapping.
+//!
+//! Under the Jordan-Wigner transformation, the creation and annihilation
+//! operators of orbital `j` are mapped to:
+//!
+//! ```text
+//! a_j^† = Z_0 ... Z_{j-1} |1><0|_j,    a_j = Z_0 ... Z_{j-1} |0><1|_j.
+//! ```
+//!
+//! A product of such operators is brought to the form: a sign, times a string
+//! of `Z` operators (the parity string), times a matrix unit `|a><b|` on each
+//! of the (at most four) orbitals involved.  The image is then written
+//! directly as a sum of Pauli strings, using:
+//!
+//! ```text
+//! |0><0| = (I + Z)/2,    |1><1| = (I - Z)/2,
+//! |0><1| = (X + iY)/2,   |1><0| = (X - iY)/2.
+//! ```
+
 use num::Float;
+use rayon::prelude::*;
 
 use crate::{
     code::{
@@ -6,14 +26,9 @@ use crate::{
             An,
             Cr,
             Fermions,
-            Orbital,
-        },
-        qubits::{
-            Paulis,
-            Sigma,
         },
+        qubits::Paulis,
     },
-    math::ReIm,
     terms::{
         SumRepr,
         Terms,
@@ -21,124 +36,183 @@ use crate::{
     Error,
 };
 
-enum Map {
-    An(Orbital),
-    Cr(Orbital),
+/// Matrix unit `|row><col|` acting on a single qubit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Unit {
+    row: bool,
+    col: bool,
 }
 
-macro_rules! impl_tryfrom_map {
-    ($($Typ:tt)* ) => {
-        $(
-            impl TryFrom<$Typ> for Map {
-                type Error = Error;
-
-                fn try_from(value: $Typ) -> Result<Self, Self::Error> {
-                    (value.0.index() < 64)
-                        .then_some(Self::$Typ(value.0))
-                        .ok_or_else(|| Error::QubitIndex {
-                            msg: "orbital index must be within 0..=63".to_string(),
-                        })
-                }
-            }
-        )*
+impl Unit {
+    const AN: Self = Self {
+        row: false,
+        col: true,
+    };
+    const CR: Self = Self {
+        row: true,
+        col: false,
     };
-}
 
-impl_tryfrom_map!(An Cr);
+    /// Product of matrix units, or `None` if it vanishes.
+    fn mul(
+        self,
+        rhs: Self,
+    ) -> Option<Self> {
+        (self.col == rhs.row).then_some(Self {
+            row: self.row,
+            col: rhs.col,
+        })
+    }
+}
 
-fn pauli_codes_from_index(index: u16) -> (Paulis, Paulis) {
-    let code = Paulis::parity_op(index);
+fn site(index: u32) -> Result<u16, Error> {
+    u16::try_from(index)
+        .ok()
+        .filter(|&j| j < 64)
+        .ok_or_else(|| Error::QubitIndex {
+            msg: "orbital index must be within 0..=63".to_string(),
+        })
+}
 
-    let x = {
-        let mut code = code;
-        code.set(index, Sigma::X);
-        code
-    };
-    let y = {
-        let mut code = code;
-        code.set(index, Sigma::Y);
-        code
-    };
+/// String of `Z` operators on qubits marked by the bits of `mask`.
+fn z_string(mut mask: u64) -> u128 {
+    let mut code = 0;
+    while mask != 0 {
+        code |= 0b11 << (2 * mask.trailing_zeros());
+        mask &= mask - 1;
+    }
+    code
+}
 
-    (x, y)
+/// Jordan-Wigner image of a product of creation and annihilation operators.
+#[derive(Clone, Copy, Debug)]
+struct Image {
+    negative: bool,
+    parity:   u64,
+    sites:    [(u16, Unit); 4],
+    len:      usize,
 }
 
-impl Map {
-    fn index(&self) -> u16 {
-        u16::try_from(match self {
-            Self::An(an) => an.index(),
-            Self::Cr(cr) => cr.index(),
+impl Image {
+    /// Image of the product of operators, in the order given.
+    ///
+    /// Returns `None` if the product vanishes.
+    fn new(ops: &[(u16, Unit)]) -> Option<Self> {
+        assert!(ops.len() <= 4, "at most four operators");
+
+        // Move parity strings to the left.  The matrix unit on qubit `i`
+        // anticommutes with the parity string of each later operator acting
+        // on a qubit `j > i`.
+        let mut negative = false;
+        let mut parity = 0;
+        for (k, &(j, _)) in ops.iter().enumerate() {
+            parity ^= (1_u64 << j) - 1;
+            negative ^=
+                ops[..k].iter().filter(|&&(i, _)| i < j).count() % 2 == 1;
+        }
+
+        let mut sites = [(0, Unit::CR); 4];
+        let mut len = 0;
+        for &(j, unit) in ops {
+            if let Some(site) = sites[..len].iter_mut().find(|(i, _)| *i == j) {
+                site.1 = site.1.mul(unit)?;
+            } else {
+                sites[len] = (j, unit);
+                len += 1;
+            }
+        }
+
+        // Absorb the parity string on the qubits acted upon:
+        // Z |a><b| = (-1)^a |a><b|.
+        for &(j, unit) in &sites[..len] {
+            if parity >> j & 1 == 1 {
+                negative ^= unit.row;
+                parity ^= 1 << j;
+            }
+        }
+
+        Some(Self {
+            negative,
+            parity,
+            sites,
+            len,
         })
-        .expect("index within 0..=63")
     }
 
-    fn mul_iter<'a, T, I>(
-        &'a self,
-        rhs: I,
-    ) -> impl Iterator<Item = (ReIm<T>, Paulis)> + 'a
-    where
-        T: Float + 'a,
-        I: IntoIterator<Item = (ReIm<T>, Paulis)> + 'a,
-    {
+    /// Pauli strings of `coeff * (P + P^†)`, where `P` is the product of
+    /// operators.
+    fn hermitian<T: Float>(
+        self,
+        coeff: T,
+    ) -> impl Iterator<Item = (T, Paulis)> {
         let one_half =
             T::from(0.5_f64).expect("floating point conversion from 0.5");
-        let (x, y) = pauli_codes_from_index(self.index());
-        let term_x = ReIm::Re(one_half);
-        let term_y = match self {
-            Self::An(_) => ReIm::Im(one_half),
-            Self::Cr(_) => ReIm::Im(-one_half),
-        };
-
-        rhs.into_iter().flat_map(move |(rhs_coeff, rhs_pauli)| {
-            [(term_x, x), (term_y, y)].into_iter().map(
-                move |(lhs_coeff, lhs_pauli)| {
-                    let (root, prod) = lhs_pauli * rhs_pauli;
+        let scale = (0..self.len).fold(coeff + coeff, |acc, _| acc * one_half);
+        let parity = z_string(self.parity);
+
+        (0..1_u8 << self.len).filter_map(move |choice| {
+            let mut code = parity;
+            let mut negative = self.negative;
+            let mut num_y = 0;
+            for (i, &(j, unit)) in self.sites[..self.len].iter().enumerate() {
+                let pauli: u128 = match (unit.row == unit.col, choice >> i & 1)
+                {
+                    (true, 0) => 0b00,
+                    (true, _) => {
+                        negative ^= unit.row;
+                        0b11
+                    }
+                    (false, 0) => 0b01,
+                    (false, _) => {
+                        negative ^= unit.row;
+                        num_y += 1;
+                        0b10
+                    }
+                };
+                code |= pauli << (2 * j);
+            }
 
-                    (lhs_coeff * rhs_coeff * ReIm::from(root), prod)
-                },
-            )
+            // Keep the real part: i^num_y must be real.
+            (num_y % 2 == 0).then(|| {
+                negative ^= num_y % 4 == 2;
+                (if negative { -scale } else { scale }, Paulis::from(code))
+            })
         })
     }
 }
 
-fn iter_hermitian<'a, T, I>(iter: I) -> impl Iterator<Item = (T, Paulis)> + 'a
+fn add_term_to<T>(
+    coeff: T,
+    code: Fermions,
+    repr: &mut impl Extend<(T, Paulis)>,
+) -> Result<(), Error>
 where
-    T: Float + 'a,
-    I: IntoIterator<Item = (ReIm<T>, Paulis)> + 'a,
+    T: Float,
 {
-    let two = T::from(2.0_f64).expect("floating point conversion from 2.0");
-    iter.into_iter().filter_map(move |(x, p)| {
-        if let ReIm::Re(xre) = x {
-            Some((xre * two, p))
-        } else {
-            None
+    let cr = |op: Cr| site(op.0.index()).map(|j| (j, Unit::CR));
+    let an = |op: An| site(op.0.index()).map(|j| (j, Unit::AN));
+
+    let image = match code {
+        Fermions::Offset => {
+            repr.extend(Some((coeff, Paulis::identity())));
+            return Ok(());
         }
-    })
-}
+        Fermions::One {
+            cr: p,
+            an: q,
+        } => Image::new(&[cr(p)?, an(q)?]),
+        Fermions::Two {
+            cr: (p, q),
+            an: (r, s),
+        } => Image::new(&[cr(p)?, cr(q)?, an(r)?, an(s)?]),
+    };
+    if let Some(image) = image {
+        repr.extend(image.hermitian(coeff));
+    }
 
-#[inline]
-fn jw_map_two<'a, T: Float + 'a>(
-    op1: &'a Map,
-    op2: &'a Map,
-    coeff: T,
-) -> impl Iterator<Item = (T, Paulis)> + 'a {
-    iter_hermitian(
-        op1.mul_iter(op2.mul_iter([(ReIm::Re(coeff), Paulis::identity())])),
-    )
+    Ok(())
 }
 
-#[inline]
-fn jw_map_four<'a, T: Float + 'a>(
-    op1: &'a Map,
-    op2: &'a Map,
-    op3: &'a Map,
-    op4: &'a Map,
-    coeff: T,
-) -> impl Iterator<Item = (T, Paulis)> + 'a {
-    iter_hermitian(op1.mul_iter(op2.mul_iter(
-        op3.mul_iter(op4.mul_iter([(ReIm::Re(coeff), Paulis::identity())])),
-    )))
-}
 /// Jordan-Wigner mapping.
 ///
 /// This mapping is initialized with [`SumRepr<T,Fermions>`],
@@ -208,6 +282,66 @@ impl<'a, T> JordanWigner<'a, T> {
     }
 }
 
+impl<'a, T> JordanWigner<'a, T>
+where
+    T: Float + Send + Sync,
+{
+    /// Map terms in parallel and add them to the supplied representation.
+    ///
+    /// Chunks of the fermionic sum are mapped on worker threads of the
+    /// global `rayon` thread pool into local sums of Pauli strings, which are
+    /// then merged.  The result is the same as that of [`Terms::add_to()`],
+    /// up to the order of floating point summation.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if an orbital index is outside of `0..=63`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{
+    /// #     code::{fermions::Fermions, qubits::{Paulis, Sigma}},
+    /// #     map::JordanWigner,
+    /// #     terms::{PauliSum, SumRepr},
+    /// # };
+    /// # fn main() -> Result<(), f2q::Error> {
+    /// let fermi_repr = SumRepr::from([
+    ///     (0.5, Fermions::Offset),
+    ///     (1.0, Fermions::try_from((0, 0)).unwrap()),
+    /// ]);
+    ///
+    /// let mut pauli_repr = PauliSum::new();
+    /// JordanWigner::new(&fermi_repr).par_add_to(&mut pauli_repr)?;
+    ///
+    /// assert_eq!(pauli_repr.coeff(Paulis::identity()), Some(&1.5));
+    /// assert_eq!(pauli_repr.coeff(Paulis::with_ops([Sigma::Z])), Some(&-1.0));
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn par_add_to(
+        self,
+        repr: &mut impl Extend<(T, Paulis)>,
+    ) -> Result<(), Error> {
+        let sum = self
+            .repr
+            .par_iter()
+            .try_fold(SumRepr::new, |mut acc, (&coeff, &code)| {
+                add_term_to(coeff, code, &mut acc).map(|()| acc)
+            })
+            .try_reduce(SumRepr::new, |lhs, rhs| {
+                Ok(if lhs.len() < rhs.len() {
+                    rhs + lhs
+                } else {
+                    lhs + rhs
+                })
+            })?;
+        repr.extend(sum);
+
+        Ok(())
+    }
+}
+
 impl<'a, T> Terms<(T, Paulis)> for JordanWigner<'a, T>
 where
     T: Float,
@@ -217,246 +351,11 @@ where
     fn add_to(
         self,
         repr: &mut impl Extend<(T, Paulis)>,
-    ) -> Result<(), Error> {
+    ) -> Result<(), Self::Error> {
         for (&coeff, &code) in self.repr.iter() {
-            match code {
-                Fermions::Offset => {
-                    repr.extend(Some((coeff, Paulis::identity())));
-                }
-                Fermions::One {
-                    cr,
-                    an,
-                } => {
-                    let jw_cr = Map::try_from(cr)?;
-                    let jw_an = Map::try_from(an)?;
-                    repr.extend(jw_map_two(&jw_cr, &jw_an, coeff));
-                }
-                Fermions::Two {
-                    cr,
-                    an,
-                } => {
-                    let jw_cr = (Map::try_from(cr.0)?, Map::try_from(cr.1)?);
-                    let jw_an = (Map::try_from(an.0)?, Map::try_from(an.1)?);
-                    repr.extend(jw_map_four(
-                        &jw_cr.0, &jw_cr.1, &jw_an.0, &jw_an.1, coeff,
-                    ));
-                }
-            }
+            add_term_to(coeff, code, repr)?;
         }
 
         Ok(())
     }
 }
-
-#[cfg(test)]
-mod tests {
-
-    use ReIm::*;
-    use Sigma::*;
-
-    use super::*;
-
-    #[test]
-    fn mul_iter_01() {
-        let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> =
-            jw_an.mul_iter([(Re(2.0), Paulis::identity())]).collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([X])),
-                (Im(1.0), Paulis::with_ops([Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_02() {
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> =
-            jw_cr.mul_iter([(Re(2.0), Paulis::identity())]).collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([X])),
-                (Im(-1.0), Paulis::with_ops([Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_03() {
-        let jw_an = Map::try_from(An(Orbital::with_index(3))).unwrap();
-
-        let result: Vec<_> =
-            jw_an.mul_iter([(Re(2.0), Paulis::identity())]).collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([Z, Z, Z, X])),
-                (Im(1.0), Paulis::with_ops([Z, Z, Z, Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_04() {
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(3))).unwrap();
-
-        let result: Vec<_> =
-            jw_cr.mul_iter([(Re(2.0), Paulis::identity())]).collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([Z, Z, Z, X])),
-                (Im(-1.0), Paulis::with_ops([Z, Z, Z, Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_05() {
-        let jw_an_1 = Map::try_from(An(Orbital::with_index(0))).unwrap();
-        let jw_an_2 = Map::try_from(An(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> = jw_an_1
-            .mul_iter(jw_an_2.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([I])),
-                (Re(1.0), Paulis::with_ops([Z])),
-                (Re(-1.0), Paulis::with_ops([Z])),
-                (Re(-1.0), Paulis::with_ops([I])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_06() {
-        let jw_cr_1 = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
-        let jw_cr_2 = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> = jw_cr_1
-            .mul_iter(jw_cr_2.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([I])),
-                (Re(-1.0), Paulis::with_ops([Z])),
-                (Re(1.0), Paulis::with_ops([Z])),
-                (Re(-1.0), Paulis::with_ops([I])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_07() {
-        let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> = jw_cr
-            .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([I])),
-                (Re(-1.0), Paulis::with_ops([Z])),
-                (Re(-1.0), Paulis::with_ops([Z])),
-                (Re(1.0), Paulis::with_ops([I])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_08() {
-        let jw_an = Map::try_from(An(Orbital::with_index(2))).unwrap();
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(2))).unwrap();
-
-        let result: Vec<_> = jw_cr
-            .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Re(1.0), Paulis::with_ops([I])),
-                (Re(-1.0), Paulis::with_ops([I, I, Z])),
-                (Re(-1.0), Paulis::with_ops([I, I, Z])),
-                (Re(1.0), Paulis::with_ops([I])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_09() {
-        let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(1))).unwrap();
-
-        let result: Vec<_> = jw_cr
-            .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Im(1.0), Paulis::with_ops([Y, X])),
-                (Re(1.0), Paulis::with_ops([Y, Y])),
-                (Re(1.0), Paulis::with_ops([X, X])),
-                (Im(-1.0), Paulis::with_ops([X, Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_10() {
-        let jw_an = Map::try_from(An(Orbital::with_index(1))).unwrap();
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
-
-        let result: Vec<_> = jw_cr
-            .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Im(-1.0), Paulis::with_ops([Y, X])),
-                (Re(1.0), Paulis::with_ops([X, X])),
-                (Re(1.0), Paulis::with_ops([Y, Y])),
-                (Im(1.0), Paulis::with_ops([X, Y])),
-            ]
-        );
-    }
-
-    #[test]
-    fn mul_iter_11() {
-        let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
-        let jw_cr = Map::try_from(Cr(Orbital::with_index(2))).unwrap();
-
-        let result: Vec<_> = jw_cr
-            .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
-            .collect();
-
-        assert_eq!(
-            result,
-            &[
-                (Im(1.0), Paulis::with_ops([Y, Z, X])),
-                (Re(1.0), Paulis::with_ops([Y, Z, Y])),
-                (Re(1.0), Paulis::with_ops([X, Z, X])),
-                (Im(-1.0), Paulis::with_ops([X, Z, Y])),
-            ]
-        );
-    }
-}
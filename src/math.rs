@@ -5,12 +5,11 @@ use std::ops::{
     Neg,
 };
 
-use num::One;
-
 /// Group structure.
 pub trait Group: Mul<Output = Self> + Sized {
     fn identity() -> Self;
     #[must_use]
+    #[allow(dead_code)]
     fn inverse(self) -> Self;
 }
 
@@ -122,57 +121,3 @@ impl Group for Root4 {
         }
     }
 }
-
-/// A complex number that can only be either real or imaginary.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum ReIm<T> {
-    Zero,
-    Re(T),
-    Im(T),
-}
-
-impl<T> Mul for ReIm<T>
-where
-    T: Mul<Output = T> + Neg<Output = T>,
-{
-    type Output = Self;
-
-    fn mul(
-        self,
-        rhs: Self,
-    ) -> Self::Output {
-        match self {
-            Self::Zero => Self::Zero,
-            Self::Re(x) => match rhs {
-                Self::Zero => Self::Zero,
-                Self::Re(y) => Self::Re(x * y),
-                Self::Im(y) => Self::Im(x * y),
-            },
-            Self::Im(x) => match rhs {
-                Self::Zero => Self::Zero,
-                Self::Re(y) => Self::Im(x * y),
-                Self::Im(y) => Self::Re(-x * y),
-            },
-        }
-    }
-}
-
-impl<T> From<Root4> for ReIm<T>
-where
-    T: Neg<Output = T> + One,
-{
-    fn from(value: Root4) -> Self {
-        use Root4::{
-            R0,
-            R1,
-            R2,
-            R3,
-        };
-        match value {
-            R0 => ReIm::Re(T::one()),
-            R1 => ReIm::Re(-T::one()),
-            R2 => ReIm::Im(T::one()),
-            R3 => ReIm::Im(-T::one()),
-        }
-    }
-}
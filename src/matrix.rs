@@ -0,0 +1,740 @@
+//! Sparse matrix representation of Hamiltonians.
+//!
+//! Matrices are built in the computational basis of a register of `n`
+//! qubits (or `n` fermionic modes).  Basis states are enumerated by integers
+//! `0..2^n`, where the bit `j` of the index is the state of qubit `j`.
+//! For fermions, this is the occupation number of the orbital with index `j`,
+//! which is consistent with the Jordan-Wigner mapping, see:
+//! [`JordanWigner`](crate::map::JordanWigner).
+
+use std::{
+    fmt::Display,
+    io::Write,
+    ops::{
+        Add,
+        Mul,
+    },
+};
+
+use num::{
+    Complex,
+    Float,
+    Zero,
+};
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    terms::SumRepr,
+    Error,
+};
+
+/// Sparse matrix in the coordinate (COO) format.
+///
+/// Entries are stored as triplets `(row, col, value)` in no particular order.
+/// Duplicate entries are allowed and are understood to be summed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coo<T> {
+    num_rows: usize,
+    num_cols: usize,
+    entries:  Vec<(usize, usize, T)>,
+}
+
+impl<T> Coo<T> {
+    /// Create new, empty matrix of given shape.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::Coo;
+    /// let mtx = Coo::<f64>::new(2, 3);
+    ///
+    /// assert_eq!(mtx.shape(), (2, 3));
+    /// assert!(mtx.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new(
+        num_rows: usize,
+        num_cols: usize,
+    ) -> Self {
+        Self {
+            num_rows,
+            num_cols,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Shape of the matrix: `(num_rows, num_cols)`.
+    #[must_use]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.num_rows, self.num_cols)
+    }
+
+    /// Number of stored entries, including duplicates.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append an entry to the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::Coo;
+    /// let mut mtx = Coo::new(2, 2);
+    /// mtx.push(0, 1, 1.0);
+    /// mtx.push(0, 1, 2.0);
+    ///
+    /// assert_eq!(mtx.len(), 2);
+    /// ```
+    pub fn push(
+        &mut self,
+        row: usize,
+        col: usize,
+        value: T,
+    ) {
+        assert!(
+            row < self.num_rows && col < self.num_cols,
+            "matrix index out of bounds"
+        );
+        self.entries.push((row, col, value));
+    }
+
+    /// Iterate over stored entries: `(row, col, &value)`.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.entries.iter().map(|(i, j, x)| (*i, *j, x))
+    }
+}
+
+/// Sparse matrix in the compressed sparse row (CSR) format.
+///
+/// Column indices within each row are sorted and unique.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Csr<T> {
+    num_rows: usize,
+    num_cols: usize,
+    row_ptr:  Vec<usize>,
+    col_idx:  Vec<usize>,
+    values:   Vec<T>,
+}
+
+impl<T> Csr<T> {
+    /// Shape of the matrix: `(num_rows, num_cols)`.
+    #[must_use]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.num_rows, self.num_cols)
+    }
+
+    /// Number of stored entries.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Row pointers.  Entries of row `i` are stored at positions
+    /// `row_ptr[i]..row_ptr[i+1]`.
+    #[must_use]
+    pub fn row_ptr(&self) -> &[usize] {
+        &self.row_ptr
+    }
+
+    /// Column indices of stored entries.
+    #[must_use]
+    pub fn col_idx(&self) -> &[usize] {
+        &self.col_idx
+    }
+
+    /// Values of stored entries.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Iterate over stored entries: `(row, col, &value)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.row_ptr.windows(2).enumerate().flat_map(move |(i, w)| {
+            (w[0]..w[1]).map(move |k| (i, self.col_idx[k], &self.values[k]))
+        })
+    }
+}
+
+impl<T> Csr<T>
+where
+    T: Copy + Zero,
+{
+    /// Value of the entry at `(row, col)`.
+    ///
+    /// Returns zero, if the entry is not stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::{Coo, Csr};
+    /// let mut mtx = Coo::new(2, 2);
+    /// mtx.push(0, 1, 1.0);
+    /// mtx.push(0, 1, 2.0);
+    /// let mtx = Csr::from(mtx);
+    ///
+    /// assert_eq!(mtx.get(0, 1), 3.0);
+    /// assert_eq!(mtx.get(1, 1), 0.0);
+    /// ```
+    #[must_use]
+    pub fn get(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> T {
+        if row >= self.num_rows {
+            return T::zero();
+        }
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        self.col_idx[range.clone()]
+            .binary_search(&col)
+            .map_or(T::zero(), |k| self.values[range.start + k])
+    }
+}
+
+impl<T> Csr<T>
+where
+    T: Copy + Zero + Mul<Output = T>,
+{
+    /// Matrix-vector product: `y = A x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths of `x` and `y` do not match the shape of the
+    /// matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::{Coo, Csr};
+    /// let mut mtx = Coo::new(2, 2);
+    /// mtx.push(0, 1, 2.0);
+    /// mtx.push(1, 1, 3.0);
+    /// let mtx = Csr::from(mtx);
+    ///
+    /// let mut y = [0.0; 2];
+    /// mtx.mul_vec(&[1.0, 1.0], &mut y);
+    ///
+    /// assert_eq!(y, [2.0, 3.0]);
+    /// ```
+    pub fn mul_vec(
+        &self,
+        x: &[T],
+        y: &mut [T],
+    ) {
+        assert_eq!(x.len(), self.num_cols, "vector length mismatch");
+        assert_eq!(y.len(), self.num_rows, "vector length mismatch");
+
+        for (i, y_i) in y.iter_mut().enumerate() {
+            *y_i = (self.row_ptr[i]..self.row_ptr[i + 1])
+                .fold(T::zero(), |acc, k| {
+                    acc + self.values[k] * x[self.col_idx[k]]
+                });
+        }
+    }
+}
+
+impl<T> From<Coo<T>> for Csr<T>
+where
+    T: Add<Output = T> + Copy + Zero,
+{
+    /// Sum duplicate entries and drop the ones that are exactly zero.
+    fn from(value: Coo<T>) -> Self {
+        let mut entries = value.entries;
+        entries.sort_unstable_by_key(|&(i, j, _)| (i, j));
+
+        let mut merged: Vec<(usize, usize, T)> =
+            Vec::with_capacity(entries.len());
+        for (i, j, x) in entries {
+            match merged.last_mut() {
+                Some(last) if (last.0, last.1) == (i, j) => last.2 = last.2 + x,
+                _ => merged.push((i, j, x)),
+            }
+        }
+        merged.retain(|(_, _, x)| !x.is_zero());
+
+        let mut row_ptr = vec![0; value.num_rows + 1];
+        let mut col_idx = Vec::with_capacity(merged.len());
+        let mut values = Vec::with_capacity(merged.len());
+        for (i, j, x) in merged {
+            row_ptr[i + 1] += 1;
+            col_idx.push(j);
+            values.push(x);
+        }
+        for i in 0..value.num_rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+
+        Self {
+            num_rows: value.num_rows,
+            num_cols: value.num_cols,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+}
+
+fn register_dim(num_qubits: u16) -> Result<usize, Error> {
+    (u32::from(num_qubits) < usize::BITS)
+        .then(|| 1 << num_qubits)
+        .ok_or_else(|| Error::QubitIndex {
+            msg: format!("register of {num_qubits} qubits is too large"),
+        })
+}
+
+impl<T> Coo<Complex<T>>
+where
+    T: Float,
+{
+    /// Matrix of a sum of Pauli strings acting on `num_qubits` qubits.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if any of the Pauli strings acts nontrivially on a qubit
+    /// outside the register, or if the matrix dimension does not fit in
+    /// `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, matrix::{Coo, Csr}, terms::SumRepr};
+    /// # use num::Complex;
+    /// let repr = SumRepr::from([(0.5, Paulis::with_ops([Sigma::Y]))]);
+    /// let mtx = Csr::from(Coo::from_paulis(&repr, 1).unwrap());
+    ///
+    /// assert_eq!(mtx.get(0, 1), Complex::new(0.0, -0.5));
+    /// assert_eq!(mtx.get(1, 0), Complex::new(0.0, 0.5));
+    /// ```
+    pub fn from_paulis(
+        repr: &SumRepr<T, Paulis>,
+        num_qubits: u16,
+    ) -> Result<Self, Error> {
+        let dim = register_dim(num_qubits)?;
+        let mut mtx = Self::new(dim, dim);
+        mtx.entries.reserve(repr.len() * dim);
+
+        for (&coeff, code) in repr.iter() {
+            if u16::from(code.min_register_size()) > num_qubits {
+                return Err(Error::QubitIndex {
+                    msg: format!(
+                        "Pauli string {code} does not fit in the register of \
+                         {num_qubits} qubits"
+                    ),
+                });
+            }
+
+            // Masks of qubits with operators: X or Y (flip), Y or Z (sign)
+            let (mut flip, mut sign, mut num_y) = (0, 0, 0);
+            for (j, pauli) in
+                code.into_iter().take(num_qubits.into()).enumerate()
+            {
+                match pauli {
+                    Sigma::I => (),
+                    Sigma::X => flip |= 1 << j,
+                    Sigma::Y => {
+                        flip |= 1 << j;
+                        sign |= 1 << j;
+                        num_y += 1;
+                    }
+                    Sigma::Z => sign |= 1 << j,
+                }
+            }
+            // Y |b> = i (-1)^b |1-b>
+            let phase = match num_y % 4 {
+                0 => Complex::new(coeff, T::zero()),
+                1 => Complex::new(T::zero(), coeff),
+                2 => Complex::new(-coeff, T::zero()),
+                _ => Complex::new(T::zero(), -coeff),
+            };
+
+            for col in 0..dim {
+                let value = if (col & sign).count_ones() % 2 == 0 {
+                    phase
+                } else {
+                    -phase
+                };
+                mtx.entries.push((col ^ flip, col, value));
+            }
+        }
+
+        Ok(mtx)
+    }
+
+    /// Matrix of a fermionic Hamiltonian in the Fock basis of `num_orbitals`
+    /// orbitals.
+    ///
+    /// Each term with coefficient `c` and code `T` other than
+    /// `Fermions::Offset` contributes `c * (T + T^†)`, which is the same
+    /// convention as used by the Jordan-Wigner mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if any orbital index is outside of `0..num_orbitals`,
+    /// or if the matrix dimension does not fit in `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, matrix::{Coo, Csr}, terms::SumRepr};
+    /// # use num::Complex;
+    /// let repr = SumRepr::from([(0.5, Fermions::try_from((0, 0)).unwrap())]);
+    /// let mtx = Csr::from(Coo::from_fermions(&repr, 1).unwrap());
+    ///
+    /// // 0.5 * (n_0 + n_0)
+    /// assert_eq!(mtx.get(0, 0), Complex::new(0.0, 0.0));
+    /// assert_eq!(mtx.get(1, 1), Complex::new(1.0, 0.0));
+    /// ```
+    pub fn from_fermions(
+        repr: &SumRepr<T, Fermions>,
+        num_orbitals: u16,
+    ) -> Result<Self, Error> {
+        let dim = register_dim(num_orbitals)?;
+        Self::from_fermions_with_basis(repr, num_orbitals, dim, |col| col, Some)
+    }
+
+    /// Matrix of a fermionic Hamiltonian restricted to a sector of the Fock
+    /// space.
+    ///
+    /// Rows and columns are indexed by the positions of basis states in
+    /// [`Sector::states()`].  Matrix elements connecting the sector with
+    /// states outside of it are discarded, i.e. the returned matrix
+    /// represents the projection of the Hamiltonian onto the sector.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if any orbital index is outside of the sector's
+    /// register.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, matrix::{Coo, Csr, Sector}, terms::SumRepr};
+    /// # use num::Complex;
+    /// let repr = SumRepr::from([(0.5, Fermions::try_from((0, 2)).unwrap())]);
+    ///
+    /// // One spin-down electron in two spatial orbitals
+    /// let sector = Sector::new(4, 1, Some(-1)).unwrap();
+    /// let mtx = Csr::from(Coo::from_fermions_in_sector(&repr, &sector).unwrap());
+    ///
+    /// assert_eq!(sector.states(), &[0b0001, 0b0100]);
+    /// assert_eq!(mtx.get(0, 1), Complex::new(0.5, 0.0));
+    /// ```
+    pub fn from_fermions_in_sector(
+        repr: &SumRepr<T, Fermions>,
+        sector: &Sector,
+    ) -> Result<Self, Error> {
+        Self::from_fermions_with_basis(
+            repr,
+            sector.num_orbitals,
+            sector.dim(),
+            |col| sector.states[col],
+            |state| sector.position(state),
+        )
+    }
+
+    fn from_fermions_with_basis<S, P>(
+        repr: &SumRepr<T, Fermions>,
+        num_orbitals: u16,
+        dim: usize,
+        state: S,
+        position: P,
+    ) -> Result<Self, Error>
+    where
+        S: Fn(usize) -> usize,
+        P: Fn(usize) -> Option<usize>,
+    {
+        let mut mtx = Self::new(dim, dim);
+
+        for (&coeff, &code) in repr.iter() {
+            let ops = fock::ladder_ops(code);
+            if let Some(idx) = ops
+                .iter()
+                .map(|op| op.index())
+                .find(|&i| i >= num_orbitals.into())
+            {
+                return Err(Error::QubitIndex {
+                    msg: format!(
+                        "orbital index {idx} outside of register of \
+                         {num_orbitals} orbitals"
+                    ),
+                });
+            }
+
+            if ops.is_empty() {
+                for col in 0..dim {
+                    mtx.entries.push((col, col, Complex::from(coeff)));
+                }
+                continue;
+            }
+
+            for col in 0..dim {
+                let Some((out, odd)) = fock::apply(&ops, state(col)) else {
+                    continue;
+                };
+                let Some(row) = position(out) else {
+                    continue;
+                };
+                let value = Complex::from(if odd { -coeff } else { coeff });
+                mtx.entries.push((row, col, value));
+                mtx.entries.push((col, row, value));
+            }
+        }
+
+        Ok(mtx)
+    }
+}
+
+/// Sector of the Fock space with fixed number of particles and, optionally,
+/// fixed spin projection.
+///
+/// Orbitals with odd indices are spin up, see
+/// [`Orbital::index()`](crate::code::fermions::Orbital::index).  The spin
+/// projection is specified as `ms2 = 2 * Sz`, i.e. the difference between the
+/// number of spin-up and spin-down electrons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sector {
+    num_orbitals: u16,
+    states:       Vec<usize>,
+}
+
+impl Sector {
+    /// Enumerate basis states of the sector.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the Fock space dimension of `num_orbitals` does not
+    /// fit in `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::Sector;
+    /// let sector = Sector::new(4, 2, None).unwrap();
+    /// assert_eq!(sector.dim(), 6);
+    ///
+    /// let sector = Sector::new(4, 2, Some(0)).unwrap();
+    /// assert_eq!(sector.states(), &[0b0011, 0b0110, 0b1001, 0b1100]);
+    /// ```
+    pub fn new(
+        num_orbitals: u16,
+        num_particles: u16,
+        ms2: Option<i32>,
+    ) -> Result<Self, Error> {
+        let dim = register_dim(num_orbitals)?;
+        let up_mask = (0..num_orbitals)
+            .filter(|i| i % 2 == 1)
+            .fold(0, |acc, i| acc | 1 << i);
+
+        let mut states = Vec::new();
+        if num_particles <= num_orbitals {
+            // Enumerate states with `num_particles` bits set in increasing
+            // order (Gosper's hack)
+            let mut state: usize = (1 << num_particles) - 1;
+            while state < dim {
+                let num_up = i64::from((state & up_mask).count_ones());
+                let num_down = i64::from(num_particles) - num_up;
+                if ms2.map_or(true, |ms2| num_up - num_down == i64::from(ms2)) {
+                    states.push(state);
+                }
+                if state == 0 {
+                    break;
+                }
+                let c = state & state.wrapping_neg();
+                let r = state + c;
+                state = (((r ^ state) >> 2) / c) | r;
+            }
+        }
+
+        Ok(Self {
+            num_orbitals,
+            states,
+        })
+    }
+
+    /// Number of orbitals in the register.
+    #[must_use]
+    pub fn num_orbitals(&self) -> u16 {
+        self.num_orbitals
+    }
+
+    /// Dimension of the sector.
+    #[must_use]
+    pub fn dim(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Basis states of the sector as Fock states in increasing order.
+    #[must_use]
+    pub fn states(&self) -> &[usize] {
+        &self.states
+    }
+
+    /// Position of a Fock state in the basis of the sector.
+    #[must_use]
+    pub fn position(
+        &self,
+        state: usize,
+    ) -> Option<usize> {
+        self.states.binary_search(&state).ok()
+    }
+}
+
+pub(crate) mod fock {
+    use crate::code::fermions::Fermions;
+
+    /// Creation or annihilation operator acting on a given orbital index.
+    #[derive(Clone, Copy, Debug)]
+    pub(crate) enum Ladder {
+        Cr(u32),
+        An(u32),
+    }
+
+    impl Ladder {
+        pub(crate) fn index(self) -> u32 {
+            match self {
+                Self::Cr(i) | Self::An(i) => i,
+            }
+        }
+    }
+
+    /// Ladder operators of the code, in the order of multiplication.
+    pub(crate) fn ladder_ops(code: Fermions) -> Vec<Ladder> {
+        match code {
+            Fermions::Offset => vec![],
+            Fermions::One {
+                cr,
+                an,
+            } => vec![Ladder::Cr(cr.0.index()), Ladder::An(an.0.index())],
+            Fermions::Two {
+                cr,
+                an,
+            } => vec![
+                Ladder::Cr(cr.0 .0.index()),
+                Ladder::Cr(cr.1 .0.index()),
+                Ladder::An(an.0 .0.index()),
+                Ladder::An(an.1 .0.index()),
+            ],
+        }
+    }
+
+    /// Apply product of ladder operators to a Fock state.
+    ///
+    /// Returns the resulting state, together with the flag set if the sign
+    /// of the state is flipped, or `None` if the state is annihilated.
+    pub(crate) fn apply(
+        ops: &[Ladder],
+        state: usize,
+    ) -> Option<(usize, bool)> {
+        let mut state = state;
+        let mut odd = false;
+        for op in ops.iter().rev() {
+            let bit = 1 << op.index();
+            let occupied = state & bit != 0;
+            match op {
+                Ladder::Cr(_) if occupied => return None,
+                Ladder::An(_) if !occupied => return None,
+                _ => (),
+            }
+            odd ^= (state & (bit - 1)).count_ones() % 2 == 1;
+            state ^= bit;
+        }
+        Some((state, odd))
+    }
+}
+
+/// Write matrix in the `MatrixMarket` coordinate format.
+///
+/// Indices are written 1-based, as required by the format.
+///
+/// # Errors
+///
+/// Returns I/O error, if writing fails.
+fn write_matrix_market<'a, T, W, I>(
+    writer: &mut W,
+    shape: (usize, usize),
+    nnz: usize,
+    entries: I,
+) -> std::io::Result<()>
+where
+    T: Float + Display + 'a,
+    W: Write,
+    I: Iterator<Item = (usize, usize, &'a Complex<T>)>,
+{
+    writeln!(writer, "%%MatrixMarket matrix coordinate complex general")?;
+    writeln!(writer, "% generated by f2q")?;
+    writeln!(writer, "{} {} {nnz}", shape.0, shape.1)?;
+    for (i, j, x) in entries {
+        writeln!(writer, "{} {} {} {}", i + 1, j + 1, x.re, x.im)?;
+    }
+    Ok(())
+}
+
+impl<T> Coo<Complex<T>>
+where
+    T: Float + Display,
+{
+    /// Write the matrix in the `MatrixMarket` coordinate format.
+    ///
+    /// Duplicate entries are written as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error, if writing fails.
+    pub fn write_matrix_market<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write_matrix_market(writer, self.shape(), self.len(), self.iter())
+    }
+}
+
+impl<T> Csr<Complex<T>>
+where
+    T: Float + Display,
+{
+    /// Write the matrix in the `MatrixMarket` coordinate format.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error, if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::matrix::{Coo, Csr};
+    /// # use num::Complex;
+    /// let mut mtx = Coo::new(2, 2);
+    /// mtx.push(1, 0, Complex::new(0.5, -1.0));
+    /// let mtx = Csr::from(mtx);
+    ///
+    /// let mut buf = Vec::new();
+    /// mtx.write_matrix_market(&mut buf).unwrap();
+    /// let text = String::from_utf8(buf).unwrap();
+    ///
+    /// assert!(text.ends_with("2 2 1\n2 1 0.5 -1\n"));
+    /// ```
+    pub fn write_matrix_market<W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write_matrix_market(writer, self.shape(), self.nnz(), self.iter())
+    }
+}
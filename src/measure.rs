@@ -0,0 +1,531 @@
+//! Measurement of Hamiltonians in groups of commuting terms.
+//!
+//! Pauli strings that commute qubit-wise, i.e. whose Pauli operators at each
+//! qubit are either equal or one of them is the identity, can be measured
+//! simultaneously.  Each group has a measurement basis: a Pauli string that
+//! contains the nontrivial operators of all terms of the group.  Rotating
+//! every qubit of the basis into `Z` with single-qubit Clifford gates
+//! diagonalizes all terms at once.
+//!
+//! # Outcomes
+//!
+//! Measured bitstrings are represented as integers `u64`, where bit `j` is
+//! the outcome of qubit `j`, i.e. the same little-endian convention as in
+//! [`Paulis::new()`].  Note that many frameworks print bitstrings
+//! big-endian, with the outcome of qubit 0 as the *last* character.  The
+//! eigenvalue of a term for a given outcome is `(-1)^k`, where `k` is the
+//! number of qubits in the support of the term that returned `1`, see:
+//! [`eigenvalue()`].  Bitstrings printed in either order can be converted
+//! with [`parse_outcome()`].
+//!
+//! # Energy estimation
+//!
+//! Given [`Counts`] of outcomes measured in several bases, [`estimate()`]
+//! assigns each term of the Hamiltonian to the first basis it is compatible
+//! with, and returns the energy together with the variance of the estimate.
+
+use std::collections::HashMap;
+
+use crate::{
+    circuit::{
+        Circuit,
+        Gate,
+    },
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    terms::SumRepr,
+    Error,
+};
+
+/// Check if two Pauli strings commute qubit-wise.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, measure::qubit_wise_commute};
+/// let xi = Paulis::with_ops([Sigma::X, Sigma::I]);
+/// let xz = Paulis::with_ops([Sigma::X, Sigma::Z]);
+/// let zz = Paulis::with_ops([Sigma::Z, Sigma::Z]);
+///
+/// assert!(qubit_wise_commute(xi, xz));
+/// assert!(!qubit_wise_commute(xz, zz));
+/// ```
+#[must_use]
+pub fn qubit_wise_commute(
+    code: Paulis,
+    other: Paulis,
+) -> bool {
+    code.into_iter()
+        .zip(other)
+        .all(|(p, q)| p == Sigma::I || q == Sigma::I || p == q)
+}
+
+/// Mask of qubits a Pauli string acts on nontrivially.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, measure::support_mask};
+/// let code = Paulis::with_ops([Sigma::X, Sigma::I, Sigma::Y]);
+///
+/// assert_eq!(support_mask(code), 0b101);
+/// ```
+#[must_use]
+pub fn support_mask(code: Paulis) -> u64 {
+    code.into_iter()
+        .enumerate()
+        .filter(|(_, pauli)| *pauli != Sigma::I)
+        .fold(0, |mask, (j, _)| mask | 1 << j)
+}
+
+/// Eigenvalue `±1` of a term for a measured outcome in a compatible basis.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, measure::eigenvalue};
+/// let code = Paulis::with_ops([Sigma::Z, Sigma::I, Sigma::Z]);
+///
+/// assert_eq!(eigenvalue(code, 0b000), 1.0);
+/// assert_eq!(eigenvalue(code, 0b010), 1.0);
+/// assert_eq!(eigenvalue(code, 0b011), -1.0);
+/// assert_eq!(eigenvalue(code, 0b101), 1.0);
+/// ```
+#[must_use]
+pub fn eigenvalue(
+    code: Paulis,
+    outcome: u64,
+) -> f64 {
+    if (support_mask(code) & outcome).count_ones() % 2 == 0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn is_compatible(
+    basis: Paulis,
+    code: Paulis,
+) -> bool {
+    code.into_iter()
+        .zip(basis)
+        .all(|(p, b)| p == Sigma::I || p == b)
+}
+
+/// Terms measured simultaneously in a common basis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    basis: Paulis,
+    terms: Vec<(f64, Paulis)>,
+}
+
+impl Group {
+    /// Measurement basis.
+    #[must_use]
+    pub fn basis(&self) -> Paulis {
+        self.basis
+    }
+
+    /// Terms of the group with their coefficients.
+    #[must_use]
+    pub fn terms(&self) -> &[(f64, Paulis)] {
+        &self.terms
+    }
+
+    /// Check if a term can be measured in the basis of this group.
+    ///
+    /// This is the case, if every nontrivial Pauli operator of the term
+    /// agrees with the operator of the basis at the same qubit.
+    #[must_use]
+    pub fn is_compatible(
+        &self,
+        code: Paulis,
+    ) -> bool {
+        is_compatible(self.basis, code)
+    }
+
+    /// Circuit rotating the basis into the computational basis, followed by
+    /// measurement of all qubits.
+    ///
+    /// Qubits measured in `X` are rotated with `H`, qubits measured in `Y` with
+    /// `Sdg` followed by `H`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the basis does not fit in the register.
+    #[must_use]
+    pub fn circuit(
+        &self,
+        num_qubits: u16,
+    ) -> Circuit {
+        let mut circ = Circuit::new(num_qubits);
+        for (i, pauli) in (0..64).zip(self.basis) {
+            match pauli {
+                Sigma::X => circ.push(Gate::H(i)),
+                Sigma::Y => {
+                    circ.push(Gate::Sdg(i));
+                    circ.push(Gate::H(i));
+                }
+                _ => (),
+            }
+        }
+        for i in 0..num_qubits {
+            circ.push(Gate::Measure(i));
+        }
+        circ
+    }
+}
+
+/// Partition of a Hamiltonian into groups of qubit-wise commuting terms.
+///
+/// Terms are assigned greedily in the order of decreasing absolute values of
+/// coefficients (ties resolved by the order of codes), each to the first
+/// group it is compatible with.  The identity term needs no measurement and
+/// is kept separately.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, measure::Grouping, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (1.0, Paulis::identity()),
+///     (0.5, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+///     (0.4, Paulis::with_ops([Sigma::X, Sigma::X])),
+///     (0.3, Paulis::with_ops([Sigma::Z, Sigma::I])),
+///     (0.2, Paulis::with_ops([Sigma::I, Sigma::X])),
+/// ]);
+/// let grouping = Grouping::qubit_wise(&repr);
+///
+/// assert_eq!(grouping.identity(), 1.0);
+/// assert_eq!(grouping.groups().len(), 2);
+/// assert_eq!(
+///     grouping.groups()[0].basis(),
+///     Paulis::with_ops([Sigma::Z, Sigma::Z])
+/// );
+/// assert_eq!(
+///     grouping.groups()[1].basis(),
+///     Paulis::with_ops([Sigma::X, Sigma::X])
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grouping {
+    identity: f64,
+    groups:   Vec<Group>,
+}
+
+impl Grouping {
+    /// Group terms of the Hamiltonian that commute qubit-wise.
+    ///
+    /// Terms with zero coefficients are dropped.
+    #[must_use]
+    pub fn qubit_wise(repr: &SumRepr<f64, Paulis>) -> Self {
+        let mut terms: Vec<_> = repr
+            .iter()
+            .filter(|(coeff, code)| {
+                **code != Paulis::identity() && **coeff != 0.0
+            })
+            .map(|(&coeff, &code)| (coeff, code))
+            .collect();
+        terms.sort_by(|(x, p), (y, q)| {
+            y.abs().total_cmp(&x.abs()).then_with(|| p.cmp(q))
+        });
+
+        let mut groups: Vec<Group> = Vec::new();
+        for (coeff, code) in terms {
+            if let Some(group) = groups
+                .iter_mut()
+                .find(|group| qubit_wise_commute(group.basis, code))
+            {
+                for (i, pauli) in (0..64).zip(code) {
+                    if pauli != Sigma::I {
+                        group.basis.set(i, pauli);
+                    }
+                }
+                group.terms.push((coeff, code));
+            } else {
+                groups.push(Group {
+                    basis: code,
+                    terms: vec![(coeff, code)],
+                });
+            }
+        }
+
+        Self {
+            identity: repr.identity_shift(),
+            groups,
+        }
+    }
+
+    /// Coefficient of the identity.
+    #[must_use]
+    pub fn identity(&self) -> f64 {
+        self.identity
+    }
+
+    /// Groups of terms.
+    #[must_use]
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Measurement circuits, one for each group.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the Hamiltonian does not fit in the register.
+    pub fn circuits(
+        &self,
+        num_qubits: u16,
+    ) -> Result<Vec<Circuit>, Error> {
+        let min_num_qubits = self
+            .groups
+            .iter()
+            .map(|group| u16::from(group.basis.min_register_size()))
+            .max()
+            .unwrap_or(0);
+        if num_qubits < min_num_qubits {
+            return Err(Error::Circuit {
+                msg: format!(
+                    "Hamiltonian acts on {min_num_qubits} qubits, register \
+                     has {num_qubits}"
+                ),
+            });
+        }
+        Ok(self
+            .groups
+            .iter()
+            .map(|group| group.circuit(num_qubits))
+            .collect())
+    }
+}
+
+/// Order of characters in a printed bitstring.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The last character is the outcome of qubit 0 (e.g. Qiskit)
+    #[default]
+    BigEndian,
+    /// The first character is the outcome of qubit 0
+    LittleEndian,
+}
+
+/// Convert a bitstring of `0`s and `1`s to an outcome.
+///
+/// # Errors
+///
+/// Returns error if the string is empty, longer than 64 characters, or
+/// contains other characters.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::measure::{parse_outcome, BitOrder};
+/// assert_eq!(parse_outcome("0011", BitOrder::BigEndian), Ok(0b0011));
+/// assert_eq!(parse_outcome("0011", BitOrder::LittleEndian), Ok(0b1100));
+/// ```
+pub fn parse_outcome(
+    bits: &str,
+    order: BitOrder,
+) -> Result<u64, Error> {
+    if bits.is_empty() || bits.len() > 64 {
+        return Err(Error::Measure {
+            msg: format!("bitstring must have 1 to 64 characters: {bits:?}"),
+        });
+    }
+    let parse = |outcome: u64, c: char| match c {
+        '0' => Ok(outcome << 1),
+        '1' => Ok(outcome << 1 | 1),
+        _ => Err(Error::Measure {
+            msg: format!("invalid bitstring: {bits:?}"),
+        }),
+    };
+    match order {
+        BitOrder::BigEndian => bits.chars().try_fold(0, parse),
+        BitOrder::LittleEndian => bits.chars().rev().try_fold(0, parse),
+    }
+}
+
+/// Number of shots of each outcome measured in a basis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counts {
+    basis:  Paulis,
+    counts: HashMap<u64, u64>,
+}
+
+impl Counts {
+    /// Empty counts for a measurement basis.
+    #[must_use]
+    pub fn new(basis: Paulis) -> Self {
+        Self {
+            basis,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Measurement basis.
+    #[must_use]
+    pub fn basis(&self) -> Paulis {
+        self.basis
+    }
+
+    /// Add `count` shots of `outcome`.
+    pub fn add(
+        &mut self,
+        outcome: u64,
+        count: u64,
+    ) {
+        *self.counts.entry(outcome).or_default() += count;
+    }
+
+    /// Total number of shots.
+    #[must_use]
+    pub fn num_shots(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Iterate over pairs: `(outcome, count)`.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.counts
+            .iter()
+            .map(|(&outcome, &count)| (outcome, count))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mean(
+        &self,
+        value: impl Fn(u64) -> f64,
+    ) -> f64 {
+        let num_shots = self.num_shots() as f64;
+        self.iter()
+            .map(|(outcome, count)| value(outcome) * count as f64)
+            .sum::<f64>()
+            / num_shots
+    }
+}
+
+/// Expectation value of a single term.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TermEstimate {
+    pub code:        Paulis,
+    pub coeff:       f64,
+    /// Estimate of the expectation value of the Pauli string
+    pub expectation: f64,
+    /// Variance of the estimate: `(1 - expectation^2) / num_shots`
+    pub variance:    f64,
+    /// Index of the counts the term was estimated from
+    pub counts:      usize,
+}
+
+/// Energy estimated from measured counts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Estimate {
+    pub energy:   f64,
+    /// Variance of the energy estimate
+    pub variance: f64,
+    /// Estimates of terms other than the identity, in the order of codes
+    pub terms:    Vec<TermEstimate>,
+}
+
+/// Estimate the energy from counts measured in different bases.
+///
+/// Each term is estimated from the first counts whose basis it is compatible
+/// with.  Terms estimated from the same counts are correlated: the variance
+/// of their contribution is computed from the sample variance of the
+/// single-shot energy estimator `Σ_j h_j λ_j(outcome)`, divided by the
+/// number of shots.  Contributions of different counts are independent.
+///
+/// # Errors
+///
+/// Returns error if there is a term with a nonzero coefficient not
+/// compatible with any basis of nonempty counts.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, measure::{estimate, Counts}, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (1.0, Paulis::identity()),
+///     (0.5, Paulis::with_ops([Sigma::Z, Sigma::I])),
+///     (-0.5, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+/// ]);
+/// let mut counts = Counts::new(Paulis::with_ops([Sigma::Z, Sigma::Z]));
+/// counts.add(0b00, 30);
+/// counts.add(0b01, 10);
+///
+/// let est = estimate(&repr, &[counts]).unwrap();
+///
+/// // <ZI> = 0.5, <ZZ> = 0.5
+/// assert_eq!(est.energy, 1.0);
+/// assert_eq!(est.terms[0].expectation, 0.5);
+/// ```
+pub fn estimate(
+    repr: &SumRepr<f64, Paulis>,
+    counts: &[Counts],
+) -> Result<Estimate, Error> {
+    let mut terms: Vec<_> = repr
+        .iter()
+        .filter(|(coeff, code)| **code != Paulis::identity() && **coeff != 0.0)
+        .map(|(&coeff, &code)| (coeff, code))
+        .collect();
+    terms.sort_by_key(|&(_, code)| code);
+
+    let mut assigned: Vec<Vec<(f64, Paulis)>> = vec![Vec::new(); counts.len()];
+    let mut estimates = Vec::with_capacity(terms.len());
+    for (coeff, code) in terms {
+        let Some(k) = counts
+            .iter()
+            .position(|c| c.num_shots() > 0 && is_compatible(c.basis, code))
+        else {
+            return Err(Error::Measure {
+                msg: format!("term {code} not measured in any basis"),
+            });
+        };
+        assigned[k].push((coeff, code));
+
+        #[allow(clippy::cast_precision_loss)]
+        let num_shots = counts[k].num_shots() as f64;
+        let expectation = counts[k].mean(|outcome| eigenvalue(code, outcome));
+        estimates.push(TermEstimate {
+            code,
+            coeff,
+            expectation,
+            variance: (1.0 - expectation * expectation) / num_shots,
+            counts: k,
+        });
+    }
+
+    let mut energy = repr.identity_shift();
+    let mut variance = 0.0;
+    for (terms, c) in assigned.iter().zip(counts) {
+        if terms.is_empty() {
+            continue;
+        }
+        let single_shot = |outcome| {
+            terms
+                .iter()
+                .map(|&(coeff, code)| coeff * eigenvalue(code, outcome))
+                .sum::<f64>()
+        };
+        let mean = c.mean(single_shot);
+        energy += mean;
+
+        let num_shots = c.num_shots();
+        variance += if num_shots > 1 {
+            let sq_dev =
+                c.mean(|outcome| (single_shot(outcome) - mean).powi(2));
+            #[allow(clippy::cast_precision_loss)]
+            let n = num_shots as f64;
+            sq_dev / (n - 1.0)
+        } else {
+            f64::INFINITY
+        };
+    }
+
+    Ok(Estimate {
+        energy,
+        variance,
+        terms: estimates,
+    })
+}
@@ -0,0 +1,513 @@
+//! Interoperability with the string form of OpenFermion operators.
+//!
+//! A `FermionOperator` is printed by OpenFermion as a sum of products of
+//! ladder operators, separated by `+`:
+//!
+//! ```text
+//! 0.5 [0^ 1] +
+//! 0.5 [1^ 0] +
+//! 0.25 [2^ 3^ 1 0]
+//! ```
+//!
+//! where `p^` denotes the creation and `p` the annihilation operator acting
+//! on the spin orbital `p`, indexed as in [`Orbital::index()`].  The empty
+//! product `[]` is the identity, and the zero operator is printed as `0`.
+//!
+//! Terms of [`FermiSum`] stand for a code together with its adjoint, so
+//! only Hermitian operators that conserve the number of particles, with at
+//! most two-body terms, can be read.  Products of ladder operators are
+//! brought to the normal order first, i.e. with all creation operators to
+//! the left, by anticommuting them.  The result is then mapped to canonical
+//! codes, as described in the documentation of the [`integrals`] module.
+//!
+//! A `QubitOperator` is printed in the same way, as a sum of Pauli strings,
+//! e.g. `0.5 [X0 Y1 Z3]`, where the number following each Pauli operator
+//! is the index of the qubit it acts on.  Such sums are read into
+//! [`PauliSum`].
+//!
+//! [`Orbital::index()`]: crate::code::fermions::Orbital::index()
+//! [`integrals`]: crate::integrals
+
+use std::{
+    collections::BTreeMap,
+    io::{
+        self,
+        Write,
+    },
+};
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    integrals::{
+        add_one_body,
+        add_two_body,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+    },
+    Error,
+};
+
+/// Absolute tolerance of imaginary and anti-Hermitian parts of the input,
+/// as in OpenFermion.
+const TOLERANCE: f64 = 1e-8;
+
+fn openfermion_error(msg: impl Into<String>) -> Error {
+    Error::OpenFermion {
+        msg: msg.into()
+    }
+}
+
+/// Split the input into terms: `(coefficient, operators)`, with the sign of
+/// the separator applied to the coefficient.
+fn parse_terms(input: &str) -> Result<Vec<(f64, &str)>, Error> {
+    let mut terms = Vec::new();
+    let mut rest = input.trim();
+    if rest == "0" {
+        return Ok(terms);
+    }
+    while !rest.is_empty() {
+        let mut sign = 1.0;
+        if !terms.is_empty() {
+            rest = match rest.as_bytes()[0] {
+                b'+' => &rest[1..],
+                b'-' => {
+                    sign = -1.0;
+                    &rest[1..]
+                }
+                _ => {
+                    return Err(openfermion_error(format!(
+                        "expected '+' or '-' between terms: {rest}"
+                    )))
+                }
+            };
+        }
+        let (coeff, tail) = rest.split_once('[').ok_or_else(|| {
+            openfermion_error(format!("missing '[' in term: {rest}"))
+        })?;
+        let (ops, tail) = tail.split_once(']').ok_or_else(|| {
+            openfermion_error(format!("missing ']' in term: {rest}"))
+        })?;
+        terms.push((sign * parse_coeff(coeff.trim())?, ops));
+        rest = tail.trim_start();
+    }
+    Ok(terms)
+}
+
+/// Parse a real coefficient, possibly written as a Python complex number
+/// with zero imaginary part, e.g. `(0.5+0j)`.
+fn parse_coeff(s: &str) -> Result<f64, Error> {
+    let parse = |x: &str| {
+        x.parse::<f64>().map_err(|_| {
+            openfermion_error(format!("cannot parse coefficient: {s}"))
+        })
+    };
+    match s {
+        "" | "+" => return Ok(1.0),
+        "-" => return Ok(-1.0),
+        _ => (),
+    }
+
+    let x = s
+        .strip_prefix('(')
+        .and_then(|x| x.strip_suffix(')'))
+        .unwrap_or(s);
+    let Some(x) = x.strip_suffix('j') else {
+        return parse(x);
+    };
+    // Split at the sign of the imaginary part, skipping exponents.
+    let bytes = x.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&i| {
+            matches!(bytes[i], b'+' | b'-')
+                && !matches!(bytes[i - 1], b'e' | b'E')
+        })
+        .unwrap_or(0);
+    let (re, im) = x.split_at(split);
+    let re = if re.is_empty() { 0.0 } else { parse(re)? };
+    let im = parse(im)?;
+    if im.abs() > TOLERANCE {
+        return Err(openfermion_error(format!(
+            "complex coefficients are not supported: {s}"
+        )));
+    }
+    Ok(re)
+}
+
+/// Ladder operator: `(index, is_creation)`.
+type Ladder = (u32, bool);
+
+fn parse_ladders(ops: &str) -> Result<Vec<Ladder>, Error> {
+    ops.split_whitespace()
+        .map(|op| {
+            let (index, creation) = match op.strip_suffix('^') {
+                Some(index) => (index, true),
+                None => (op, false),
+            };
+            index.parse().map(|index| (index, creation)).map_err(|_| {
+                openfermion_error(format!("invalid ladder operator: {op}"))
+            })
+        })
+        .collect()
+}
+
+/// Bring a product of ladder operators to the normal order.
+///
+/// Each annihilation operator followed by a creation operator is swapped
+/// with it, using: `a_p a+_q = δ_pq - a+_q a_p`.
+fn normal_order(
+    coeff: f64,
+    ops: &[Ladder],
+    out: &mut Vec<(f64, Vec<Ladder>)>,
+) {
+    let Some(k) = ops.windows(2).position(|w| !w[0].1 && w[1].1) else {
+        out.push((coeff, ops.to_vec()));
+        return;
+    };
+    let mut swapped = ops.to_vec();
+    swapped.swap(k, k + 1);
+    normal_order(-coeff, &swapped, out);
+    if ops[k].0 == ops[k + 1].0 {
+        let mut contracted = ops.to_vec();
+        contracted.drain(k..k + 2);
+        normal_order(coeff, &contracted, out);
+    }
+}
+
+/// Sort indices in place, in increasing order, and return the sign of the
+/// permutation, or `None` if an index is repeated.
+fn sort_indices(indices: &mut [u32]) -> Option<f64> {
+    let mut sign = 1.0;
+    for i in 1..indices.len() {
+        for j in (1..=i).rev() {
+            match indices[j - 1].cmp(&indices[j]) {
+                std::cmp::Ordering::Less => break,
+                std::cmp::Ordering::Equal => return None,
+                std::cmp::Ordering::Greater => {
+                    indices.swap(j - 1, j);
+                    sign = -sign;
+                }
+            }
+        }
+    }
+    Some(sign)
+}
+
+/// Normal-ordered products: `(creation, annihilation)` indices, with
+/// creation indices in increasing and annihilation indices in decreasing
+/// order.
+type Product = (Vec<u32>, Vec<u32>);
+
+/// Parse the string form of an OpenFermion `FermionOperator`.
+///
+/// See the [module documentation](self) for the supported operators.
+/// Coefficients can be real or complex numbers with zero imaginary part.
+/// Terms with a missing coefficient have coefficient one.
+///
+/// # Errors
+///
+/// Returns [`Error::OpenFermion`], if the input cannot be parsed, or if
+/// the operator is not Hermitian, does not conserve the number of
+/// particles, or contains terms beyond two-body.  Imaginary and
+/// anti-Hermitian parts up to `1e-8` in absolute value are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::fermions::Fermions, openfermion::parse_fermion_operator};
+/// let repr = parse_fermion_operator(
+///     "1.0 [] + 0.5 [0^ 1] + 0.5 [1^ 0] + 0.25 [1 0 0^ 1^]",
+/// )
+/// .unwrap();
+///
+/// // a_1 a_0 a+_0 a+_1 = 1 - n_0 - n_1 + a+_0 a+_1 a_1 a_0
+/// assert_eq!(repr.coeff(Fermions::Offset), Some(&1.25));
+/// assert_eq!(repr.coeff(Fermions::try_from((0, 1)).unwrap()), Some(&0.5));
+/// assert_eq!(repr.coeff(Fermions::try_from((0, 0)).unwrap()), Some(&-0.125));
+/// assert_eq!(repr.coeff(Fermions::try_from((1, 1)).unwrap()), Some(&-0.125));
+/// assert_eq!(
+///     repr.coeff(Fermions::try_from((0, 1, 1, 0)).unwrap()),
+///     Some(&0.125)
+/// );
+///
+/// assert!(parse_fermion_operator("0.5 [0^ 1]").is_err());
+/// assert!(parse_fermion_operator("0.5 [0^ 1^]").is_err());
+/// ```
+pub fn parse_fermion_operator(input: &str) -> Result<FermiSum, Error> {
+    let mut products = BTreeMap::<Product, f64>::new();
+    let mut ordered = Vec::new();
+    for (coeff, ops) in parse_terms(input)? {
+        let ladders = parse_ladders(ops)?;
+        let num_creation = ladders.iter().filter(|op| op.1).count();
+        if 2 * num_creation != ladders.len() {
+            return Err(openfermion_error(format!(
+                "term does not conserve the number of particles: [{ops}]"
+            )));
+        }
+
+        ordered.clear();
+        normal_order(coeff, &ladders, &mut ordered);
+        for (coeff, ladders) in &ordered {
+            let mut cr: Vec<_> =
+                ladders.iter().filter(|op| op.1).map(|op| op.0).collect();
+            let mut an: Vec<_> =
+                ladders.iter().filter(|op| !op.1).map(|op| op.0).collect();
+            let (Some(cr_sign), Some(an_sign)) =
+                (sort_indices(&mut cr), sort_indices(&mut an))
+            else {
+                continue;
+            };
+            if cr.len() > 2 {
+                return Err(openfermion_error(format!(
+                    "terms beyond two-body are not supported: [{ops}]"
+                )));
+            }
+            an.reverse();
+            let sign = if an.len() == 2 { -an_sign } else { an_sign };
+            *products.entry((cr, an)).or_default() += cr_sign * sign * coeff;
+        }
+    }
+
+    for ((cr, an), &coeff) in &products {
+        let adjoint = (
+            an.iter().rev().copied().collect(),
+            cr.iter().rev().copied().collect(),
+        );
+        let coeff_adj = products.get(&adjoint).copied().unwrap_or(0.0);
+        if (coeff - coeff_adj).abs() > TOLERANCE {
+            let ops: Vec<_> = cr
+                .iter()
+                .map(|p| format!("{p}^"))
+                .chain(an.iter().map(u32::to_string))
+                .collect();
+            return Err(openfermion_error(format!(
+                "operator is not Hermitian, coefficients of [{}] and its \
+                 adjoint differ: {coeff} != {coeff_adj}",
+                ops.join(" ")
+            )));
+        }
+    }
+
+    let mut repr = FermiSum::new();
+    for ((cr, an), coeff) in products {
+        match (cr.as_slice(), an.as_slice()) {
+            ([], []) => repr.add_term(Fermions::Offset, coeff),
+            (&[p], &[q]) => add_one_body(coeff, p, q, &mut repr),
+            (&[p, q], &[r, s]) => add_two_body(coeff, p, q, r, s, &mut repr),
+            _ => unreachable!("number of particles is conserved"),
+        }
+    }
+    Ok(repr)
+}
+
+/// Write a sum of terms in the string form of an OpenFermion
+/// `FermionOperator`.
+///
+/// Each term is written together with its adjoint, unless the code is
+/// self-adjoint, in the increasing order of codes.  Terms are separated by
+/// `" +\n"`, as in OpenFermion.  An empty sum is written as `0`.  The
+/// output ends with a newline.
+///
+/// # Errors
+///
+/// Returns I/O error, if writing fails.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::fermions::Fermions, openfermion::write_fermion_operator, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (0.5, Fermions::try_from((0, 1)).unwrap()),
+///     (0.25, Fermions::try_from((1, 1)).unwrap()),
+///     (1.0, Fermions::Offset),
+/// ]);
+///
+/// let mut buf = Vec::new();
+/// write_fermion_operator(&repr, &mut buf).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     "1.0 [] +\n0.5 [0^ 1] +\n0.5 [1^ 0] +\n0.5 [1^ 1]\n"
+/// );
+/// ```
+pub fn write_fermion_operator<W: Write>(
+    repr: &FermiSum,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut terms: Vec<_> =
+        repr.iter().map(|(&coeff, &code)| (code, coeff)).collect();
+    terms.sort_unstable_by_key(|&(code, _)| code);
+
+    let mut products = Vec::with_capacity(2 * terms.len());
+    for (code, coeff) in terms {
+        match code {
+            Fermions::Offset => products.push((coeff, String::new())),
+            Fermions::One {
+                cr,
+                an,
+            } => {
+                let (p, q) = (cr.0.index(), an.0.index());
+                if p == q {
+                    products.push((2.0 * coeff, format!("{p}^ {q}")));
+                } else {
+                    products.push((coeff, format!("{p}^ {q}")));
+                    products.push((coeff, format!("{q}^ {p}")));
+                }
+            }
+            Fermions::Two {
+                cr,
+                an,
+            } => {
+                let (p, q) = (cr.0 .0.index(), cr.1 .0.index());
+                let (r, s) = (an.0 .0.index(), an.1 .0.index());
+                if (p, q) == (s, r) {
+                    products.push((2.0 * coeff, format!("{p}^ {q}^ {r} {s}")));
+                } else {
+                    products.push((coeff, format!("{p}^ {q}^ {r} {s}")));
+                    products.push((coeff, format!("{s}^ {r}^ {q} {p}")));
+                }
+            }
+        }
+    }
+
+    write_products(&products, writer)
+}
+
+/// Write products of operators, separated by `" +\n"`.
+fn write_products<W: Write>(
+    products: &[(f64, String)],
+    writer: &mut W,
+) -> io::Result<()> {
+    if products.is_empty() {
+        return writeln!(writer, "0");
+    }
+    for (i, (coeff, ops)) in products.iter().enumerate() {
+        let sep = if i + 1 < products.len() { " +" } else { "" };
+        writeln!(writer, "{coeff:?} [{ops}]{sep}")?;
+    }
+    Ok(())
+}
+
+fn parse_paulis(ops: &str) -> Result<Paulis, Error> {
+    let mut code = Paulis::identity();
+    for op in ops.split_whitespace() {
+        let pauli = match op.as_bytes()[0] {
+            b'X' => Sigma::X,
+            b'Y' => Sigma::Y,
+            b'Z' => Sigma::Z,
+            _ => {
+                return Err(openfermion_error(format!(
+                    "invalid Pauli operator: {op}"
+                )))
+            }
+        };
+        let index = op[1..]
+            .parse::<u16>()
+            .ok()
+            .filter(|&index| index < 64)
+            .ok_or_else(|| {
+                openfermion_error(format!(
+                    "qubit index must be within 0..64: {op}"
+                ))
+            })?;
+        if code.pauli(index) != Some(Sigma::I) {
+            return Err(openfermion_error(format!(
+                "repeated qubit index: [{ops}]"
+            )));
+        }
+        code.set(index, pauli);
+    }
+    Ok(code)
+}
+
+/// Parse the string form of an OpenFermion `QubitOperator`.
+///
+/// Coefficients can be real or complex numbers with zero imaginary part.
+/// Terms with a missing coefficient have coefficient one.  Terms with the
+/// same Pauli string are added together.
+///
+/// # Errors
+///
+/// Returns [`Error::OpenFermion`], if the input cannot be parsed, a qubit
+/// index is repeated within a term or is not within `0..64`, or a
+/// coefficient has imaginary part larger than `1e-8` in absolute value.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, openfermion::parse_qubit_operator};
+/// let repr = parse_qubit_operator("0.5 [] + (0.25+0j) [X0 Z2] - [Y1]").unwrap();
+///
+/// assert_eq!(repr.coeff(Paulis::identity()), Some(&0.5));
+/// assert_eq!(
+///     repr.coeff(Paulis::with_ops([Sigma::X, Sigma::I, Sigma::Z])),
+///     Some(&0.25)
+/// );
+/// assert_eq!(
+///     repr.coeff(Paulis::with_ops([Sigma::I, Sigma::Y])),
+///     Some(&-1.0)
+/// );
+/// ```
+pub fn parse_qubit_operator(input: &str) -> Result<PauliSum, Error> {
+    let mut repr = PauliSum::new();
+    for (coeff, ops) in parse_terms(input)? {
+        repr.add_term(parse_paulis(ops)?, coeff);
+    }
+    Ok(repr)
+}
+
+/// Write a sum of Pauli strings in the string form of an OpenFermion
+/// `QubitOperator`.
+///
+/// Terms are written in the increasing order of codes, separated by
+/// `" +\n"`, as in OpenFermion.  An empty sum is written as `0`.  The
+/// output ends with a newline.
+///
+/// # Errors
+///
+/// Returns I/O error, if writing fails.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, openfermion::write_qubit_operator, terms::SumRepr};
+/// let repr = SumRepr::from([
+///     (0.5, Paulis::with_ops([Sigma::X, Sigma::I, Sigma::Z])),
+///     (-1.0, Paulis::identity()),
+/// ]);
+///
+/// let mut buf = Vec::new();
+/// write_qubit_operator(&repr, &mut buf).unwrap();
+///
+/// assert_eq!(String::from_utf8(buf).unwrap(), "-1.0 [] +\n0.5 [X0 Z2]\n");
+/// ```
+pub fn write_qubit_operator<W: Write>(
+    repr: &PauliSum,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut terms: Vec<_> =
+        repr.iter().map(|(&coeff, &code)| (code, coeff)).collect();
+    terms.sort_unstable_by_key(|&(code, _)| code);
+
+    let products: Vec<_> = terms
+        .into_iter()
+        .map(|(code, coeff)| {
+            let ops: Vec<_> = code
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, pauli)| pauli != Sigma::I)
+                .map(|(i, pauli)| format!("{pauli}{i}"))
+                .collect();
+            (coeff, ops.join(" "))
+        })
+        .collect();
+    write_products(&products, writer)
+}
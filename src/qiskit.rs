@@ -0,0 +1,225 @@
+//! Interoperability with Qiskit.
+//!
+//! Qiskit represents sums of Pauli strings as `SparsePauliOp`, a list of
+//! Pauli labels together with a list of coefficients.  Labels are
+//! big-endian: the *last* character of a label acts on qubit 0.  In
+//! contrast, [`Paulis`] stores the operator acting on qubit 0 first, and
+//! its [`Display`] implementation prints it first, too.  The conversion
+//! below reverses the order of characters accordingly.
+//!
+//! [`Display`]: std::fmt::Display
+
+use num::Float;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    terms::{
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+fn qiskit_error(msg: impl Into<String>) -> Error {
+    Error::Qiskit {
+        msg: msg.into()
+    }
+}
+
+/// Qiskit `SparsePauliOp`, as lists of labels and real coefficients.
+///
+/// The serialized form, e.g. as JSON:
+///
+/// ```json
+/// { "labels": ["IIXZ", "YIII"], "coeffs": [0.5, -0.25] }
+/// ```
+///
+/// can be passed directly to the constructor:
+/// `SparsePauliOp(data["labels"], data["coeffs"])`.
+///
+/// Implements [`Terms`], with Pauli strings mapped from big-endian labels.
+/// Labels must be of equal length, at most 64, and contain only the
+/// characters `I`, `X`, `Y`, `Z`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{
+/// #     code::qubits::{Paulis, Sigma},
+/// #     qiskit::SparsePauliOp,
+/// #     terms::{SumRepr, Terms},
+/// # };
+/// let op = SparsePauliOp {
+///     labels: vec!["IIXZ".to_string(), "YIII".to_string()],
+///     coeffs: vec![0.5, -0.25],
+/// };
+///
+/// let mut repr = SumRepr::new();
+/// (&op).add_to(&mut repr).unwrap();
+///
+/// assert_eq!(
+///     repr.coeff(Paulis::with_ops([Sigma::Z, Sigma::X])),
+///     Some(&0.5)
+/// );
+/// assert_eq!(
+///     repr.coeff(Paulis::with_ops([Sigma::I, Sigma::I, Sigma::I, Sigma::Y])),
+///     Some(&-0.25)
+/// );
+///
+/// assert_eq!(SparsePauliOp::from_terms(&repr, None).unwrap(), op);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SparsePauliOp {
+    pub labels: Vec<String>,
+    pub coeffs: Vec<f64>,
+}
+
+impl SparsePauliOp {
+    /// Convert a sum of Pauli strings.
+    ///
+    /// Labels have length `num_qubits`, or, if not specified, the minimal
+    /// register size that fits all codes, but at least one.  Terms are
+    /// listed in the increasing order of codes.  An empty sum is converted
+    /// to the identity with coefficient zero, since Qiskit does not allow
+    /// empty operators.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Qiskit`], if `num_qubits` is greater than 64 or too
+    /// small to fit a code, or if a coefficient cannot be converted to
+    /// `f64`.
+    pub fn from_terms<T>(
+        repr: &SumRepr<T, Paulis>,
+        num_qubits: Option<u16>,
+    ) -> Result<Self, Error>
+    where
+        T: Float,
+    {
+        let min_size = repr
+            .iter()
+            .map(|(_, code)| u16::from(code.min_register_size()))
+            .max()
+            .unwrap_or(0);
+        let num_qubits = num_qubits.unwrap_or(min_size.max(1));
+        if num_qubits > 64 {
+            return Err(qiskit_error(format!(
+                "number of qubits must be at most 64: {num_qubits}"
+            )));
+        }
+        if num_qubits < min_size {
+            return Err(qiskit_error(format!(
+                "number of qubits too small to fit codes: {num_qubits} < \
+                 {min_size}"
+            )));
+        }
+
+        let mut terms: Vec<_> = repr.iter().collect();
+        terms.sort_unstable_by_key(|&(_, code)| code);
+        if terms.is_empty() {
+            return Ok(Self {
+                labels: vec!["I".repeat(usize::from(num_qubits))],
+                coeffs: vec![0.0],
+            });
+        }
+
+        let mut labels = Vec::with_capacity(terms.len());
+        let mut coeffs = Vec::with_capacity(terms.len());
+        for (&coeff, &code) in terms {
+            labels.push(
+                (0..num_qubits)
+                    .rev()
+                    .map(|i| {
+                        code.pauli(i).expect("index within 0..64").to_string()
+                    })
+                    .collect(),
+            );
+            coeffs.push(coeff.to_f64().ok_or_else(|| {
+                qiskit_error("cannot convert coefficient to f64")
+            })?);
+        }
+
+        Ok(Self {
+            labels,
+            coeffs,
+        })
+    }
+
+    /// Number of qubits: the length of labels.
+    ///
+    /// Returns `None`, if there are no labels.
+    #[must_use]
+    pub fn num_qubits(&self) -> Option<usize> {
+        self.labels.first().map(String::len)
+    }
+}
+
+fn parse_label(label: &str) -> Result<Paulis, Error> {
+    if label.len() > 64 {
+        return Err(qiskit_error(format!(
+            "label longer than 64 qubits: {label}"
+        )));
+    }
+    let mut code = Paulis::identity();
+    for (i, ch) in (0..).zip(label.bytes().rev()) {
+        let pauli = match ch {
+            b'I' => Sigma::I,
+            b'X' => Sigma::X,
+            b'Y' => Sigma::Y,
+            b'Z' => Sigma::Z,
+            _ => {
+                return Err(qiskit_error(format!(
+                    "invalid Pauli label: {label}"
+                )))
+            }
+        };
+        code.set(i, pauli);
+    }
+    Ok(code)
+}
+
+impl<T> Terms<(T, Paulis)> for &SparsePauliOp
+where
+    T: Float,
+{
+    type Error = Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, Paulis)>,
+    ) -> Result<(), Self::Error> {
+        if self.labels.len() != self.coeffs.len() {
+            return Err(qiskit_error(format!(
+                "number of labels and coefficients differ: {} != {}",
+                self.labels.len(),
+                self.coeffs.len()
+            )));
+        }
+        let num_qubits = self.num_qubits().unwrap_or(0);
+        // Validate all terms first, so that nothing is added on error.
+        let terms = self
+            .labels
+            .iter()
+            .zip(&self.coeffs)
+            .map(|(label, &coeff)| {
+                if label.len() != num_qubits {
+                    return Err(qiskit_error(format!(
+                        "labels must be of equal length: {label}"
+                    )));
+                }
+                let coeff = T::from(coeff).ok_or_else(|| {
+                    qiskit_error(format!("cannot convert coefficient: {coeff}"))
+                })?;
+                Ok((coeff, parse_label(label)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        repr.extend(terms);
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+//! Orbital rotations of fermionic Hamiltonians.
+//!
+//! A rotation of the basis of `N` spin orbitals is given by a real
+//! orthogonal `N x N` matrix `U`, whose columns are the new orbitals
+//! expressed in the old ones:
+//!
+//! ```text
+//! b+_p = sum_q U_qp a+_q.
+//! ```
+//!
+//! The Hamiltonian is rewritten in terms of the new operators, by
+//! substituting `a+_q = sum_p U_qp b+_p` (and likewise for annihilation
+//! operators).  In terms of integrals, this is the usual transformation
+//! `h' = U^T h U`, applied to every index of one- and two-electron terms.
+//! The constant offset is unchanged.
+//!
+//! The result is re-canonicalised, with the conventions described in the
+//! documentation of the [`integrals`](crate::integrals) module.  Since
+//! coefficients are real, only real rotations are supported.  Complex
+//! unitary rotations would in general produce complex coefficients.
+//!
+//! Terms that cancel out only up to the floating point round-off are kept
+//! in the result, and can be removed with [`SumRepr::prune()`].
+
+use std::collections::BTreeMap;
+
+use crate::{
+    code::fermions::{
+        Fermions,
+        Spin,
+    },
+    integrals::{
+        add_one_body,
+        add_two_body,
+        spin_orbital,
+    },
+    terms::{
+        FermiSum,
+        SumRepr,
+    },
+    Error,
+};
+
+/// Tolerance of the orthogonality check.
+const TOLERANCE: f64 = 1e-10;
+
+fn rotation_error(msg: impl Into<String>) -> Error {
+    Error::Rotation {
+        msg: msg.into()
+    }
+}
+
+/// Check that a square matrix is orthogonal and return its dimension.
+fn check_matrix(
+    matrix: &[f64],
+    num_orbitals: u32,
+) -> Result<usize, Error> {
+    let n = usize::try_from(num_orbitals)
+        .ok()
+        .filter(|n| n.checked_mul(*n).is_some())
+        .ok_or_else(|| rotation_error("too many orbitals"))?;
+    if matrix.len() != n * n {
+        return Err(rotation_error(format!(
+            "matrix should have length {}, found: {}",
+            n * n,
+            matrix.len()
+        )));
+    }
+    for i in 0..n {
+        for j in 0..n {
+            let dot: f64 =
+                (0..n).map(|p| matrix[p * n + i] * matrix[p * n + j]).sum();
+            let expected = if i == j { 1.0 } else { 0.0 };
+            if (dot - expected).abs() > TOLERANCE {
+                return Err(rotation_error(format!(
+                    "matrix is not orthogonal: (U^T U)_{i}{j} = {dot}"
+                )));
+            }
+        }
+    }
+    Ok(n)
+}
+
+/// Orbitals rotated among themselves by a diagonal block of the matrix.
+#[derive(Clone, Debug, PartialEq)]
+struct Block {
+    orbitals: Vec<u32>,
+    matrix:   Vec<f64>,
+}
+
+/// Real orthogonal rotation of spin orbitals.
+///
+/// The matrix is stored as a flat slice of length `N^2` in the row-major
+/// order: `u[p * N + q]`, where `N` is the number of spin orbitals.  See
+/// the [module documentation](self) for the convention.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{
+/// #     code::fermions::Fermions,
+/// #     rotation::OrbitalRotation,
+/// #     terms::SumRepr,
+/// # };
+/// # fn main() -> Result<(), f2q::Error> {
+/// // Rotate by 90 degrees: b+_0 = -a+_1, b+_1 = a+_0
+/// let rotation = OrbitalRotation::new(2, &[0.0, 1.0, -1.0, 0.0])?;
+///
+/// let repr = SumRepr::from([
+///     (1.0, Fermions::Offset),
+///     (0.5, Fermions::try_from((0, 0)).unwrap()),
+/// ]);
+/// let rotated = rotation.rotate(&repr)?;
+///
+/// assert_eq!(rotated.coeff(Fermions::Offset), Some(&1.0));
+/// assert_eq!(
+///     rotated.coeff(Fermions::try_from((1, 1)).unwrap()),
+///     Some(&0.5)
+/// );
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrbitalRotation {
+    num_orbitals: u32,
+    blocks:       Vec<Block>,
+}
+
+impl OrbitalRotation {
+    /// Rotation of `num_orbitals` spin orbitals.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rotation`], if the length of `matrix` is not
+    /// `num_orbitals^2`, or if the matrix is not orthogonal.
+    pub fn new(
+        num_orbitals: u32,
+        matrix: &[f64],
+    ) -> Result<Self, Error> {
+        check_matrix(matrix, num_orbitals)?;
+
+        Ok(Self {
+            num_orbitals,
+            blocks: vec![Block {
+                orbitals: (0..num_orbitals).collect(),
+                matrix:   matrix.to_vec(),
+            }],
+        })
+    }
+
+    /// Rotation of `num_orbitals` spatial orbitals, applied to both spins.
+    ///
+    /// The matrix has length `num_orbitals^2` and acts on spatial orbitals.
+    /// Spin orbitals are indexed as in [`Orbital::index()`], and orbitals of
+    /// opposite spin are not mixed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`OrbitalRotation::new()`].
+    ///
+    /// [`Orbital::index()`]: crate::code::fermions::Orbital::index()
+    pub fn spatial(
+        num_orbitals: u32,
+        matrix: &[f64],
+    ) -> Result<Self, Error> {
+        check_matrix(matrix, num_orbitals)?;
+        let num_spin = num_orbitals
+            .checked_mul(2)
+            .ok_or_else(|| rotation_error("too many orbitals"))?;
+
+        Ok(Self {
+            num_orbitals: num_spin,
+            blocks:       Spin::both()
+                .map(|sigma| Block {
+                    orbitals: (0..num_orbitals)
+                        .map(|p| spin_orbital(p, sigma))
+                        .collect(),
+                    matrix:   matrix.to_vec(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Number of spin orbitals.
+    #[must_use]
+    pub fn num_orbitals(&self) -> u32 {
+        self.num_orbitals
+    }
+
+    /// Rotate the orbitals of a Hamiltonian.
+    ///
+    /// Two-electron terms are transformed as dense tensors, one combination
+    /// of rotated blocks of orbitals at a time.  A rotation of `N` spin
+    /// orbitals, as constructed by [`OrbitalRotation::new()`], takes memory
+    /// for `2 N^4` coefficients, e.g. 1.6 GB for 100 spin orbitals.  A
+    /// [spatial](OrbitalRotation::spatial()) rotation of `n` orbitals
+    /// rotates each spin separately, and takes memory for `2 n^4`
+    /// coefficients, e.g. 100 MB for 50 spatial orbitals.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rotation`], if an orbital index in `repr` is not
+    /// less than the number of spin orbitals of the rotation.
+    pub fn rotate(
+        &self,
+        repr: &FermiSum,
+    ) -> Result<FermiSum, Error> {
+        if let Some(index) = repr
+            .iter()
+            .filter_map(|(_, code)| code.max_index())
+            .find(|&index| index >= self.num_orbitals)
+        {
+            return Err(rotation_error(format!(
+                "orbital index out of range: {index} >= {}",
+                self.num_orbitals
+            )));
+        }
+
+        // Block of each orbital and its position in the block
+        let mut position = vec![(0, 0); self.num_orbitals as usize];
+        for (b, block) in self.blocks.iter().enumerate() {
+            for (i, &orbital) in block.orbitals.iter().enumerate() {
+                position[orbital as usize] = (b, i);
+            }
+        }
+
+        let mut rotated = SumRepr::new();
+        // Entries of T + T^†, for each term c * (T + T^†).
+        let mut h1 = Vec::new();
+        let mut h2 = Vec::new();
+        for (&coeff, &code) in repr.iter() {
+            match code {
+                Fermions::Offset => rotated.add_term(code, coeff),
+                Fermions::One {
+                    cr,
+                    an,
+                } => {
+                    let (p, q) = (cr.0.index(), an.0.index());
+                    h1.push((coeff, [p, q]));
+                    h1.push((coeff, [q, p]));
+                }
+                Fermions::Two {
+                    cr,
+                    an,
+                } => {
+                    let (p, q) = (cr.0 .0.index(), cr.1 .0.index());
+                    let (r, s) = (an.0 .0.index(), an.1 .0.index());
+                    h2.push((coeff, [p, q, r, s]));
+                    h2.push((coeff, [s, r, q, p]));
+                }
+            }
+        }
+
+        self.rotate_tensor(h1, &position, |value, [p, q]| {
+            add_one_body(value, p, q, &mut rotated);
+        });
+        self.rotate_tensor(h2, &position, |value, [p, q, r, s]| {
+            add_two_body(value, p, q, r, s, &mut rotated);
+        });
+
+        Ok(rotated)
+    }
+
+    /// Rotate every index of a sparse tensor given by its entries, and pass
+    /// non-zero entries of the result to `add`.
+    ///
+    /// Since the matrix is block-diagonal, entries with indices in
+    /// different combinations of blocks transform independently.  Each
+    /// combination is transformed as a dense tensor.
+    fn rotate_tensor<const K: usize>(
+        &self,
+        entries: Vec<(f64, [u32; K])>,
+        position: &[(usize, usize)],
+        mut add: impl FnMut(f64, [u32; K]),
+    ) {
+        let mut groups: BTreeMap<[usize; K], Vec<(f64, [usize; K])>> =
+            BTreeMap::new();
+        for (coeff, indices) in entries {
+            let key = indices.map(|index| position[index as usize].0);
+            let local = indices.map(|index| position[index as usize].1);
+            groups.entry(key).or_default().push((coeff, local));
+        }
+
+        for (key, group) in groups {
+            let dims = key.map(|b| self.blocks[b].orbitals.len());
+            let flat = |local: [usize; K]| {
+                local.iter().zip(&dims).fold(0, |acc, (&i, &n)| acc * n + i)
+            };
+            let mut h = vec![0.0; dims.iter().product()];
+            for (coeff, local) in group {
+                h[flat(local)] += coeff;
+            }
+            for (axis, &b) in key.iter().enumerate() {
+                h = transform_axis(
+                    &h,
+                    dims[..axis].iter().product(),
+                    dims[axis + 1..].iter().product(),
+                    &self.blocks[b].matrix,
+                );
+            }
+
+            for (k, &value) in h.iter().enumerate().filter(|(_, &v)| v != 0.0) {
+                let mut indices = [0; K];
+                let mut rest = k;
+                for axis in (0..K).rev() {
+                    let block = &self.blocks[key[axis]];
+                    indices[axis] = block.orbitals[rest % dims[axis]];
+                    rest /= dims[axis];
+                }
+                add(value, indices);
+            }
+        }
+    }
+}
+
+/// Contract one index of a flat tensor with the matrix:
+///
+/// ```text
+/// out[o, i, t] = sum_p h[o, p, t] * U_pi,
+/// ```
+///
+/// where `o` and `t` are the (flattened) outer and inner indices.
+fn transform_axis(
+    h: &[f64],
+    outer: usize,
+    inner: usize,
+    matrix: &[f64],
+) -> Vec<f64> {
+    let n = h.len() / (outer * inner);
+    let mut out = vec![0.0; h.len()];
+    for o in 0..outer {
+        for p in 0..n {
+            let src = &h[(o * n + p) * inner..(o * n + p + 1) * inner];
+            if src.iter().all(|&v| v == 0.0) {
+                continue;
+            }
+            for i in 0..n {
+                let u = matrix[p * n + i];
+                if u == 0.0 {
+                    continue;
+                }
+                let dst =
+                    &mut out[(o * n + i) * inner..(o * n + i + 1) * inner];
+                for (d, &s) in dst.iter_mut().zip(src) {
+                    *d += s * u;
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,235 @@
+//! Randomized compilation of Hamiltonian evolution.
+//!
+//! Terms of a Hamiltonian are sampled in proportion to the magnitude of their
+//! coefficients using Walker's alias method, so that each sample costs
+//! `O(1)`.  All routines take the random number generator as an argument;
+//! seeded generators, e.g. [`rand::rngs::StdRng`], give reproducible
+//! sequences.
+
+use num::Float;
+use rand::{
+    distributions::Distribution,
+    Rng,
+};
+
+use crate::{
+    code::qubits::Paulis,
+    terms::SumRepr,
+};
+
+/// Walker's alias table for sampling from a discrete distribution.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::sample::AliasTable;
+/// use rand::{
+///     distributions::Distribution,
+///     rngs::StdRng,
+///     SeedableRng,
+/// };
+///
+/// let table = AliasTable::new(&[1.0, 0.0, 3.0]).unwrap();
+/// let mut rng = StdRng::seed_from_u64(1);
+///
+/// let samples: Vec<_> = table.sample_iter(&mut rng).take(100).collect();
+/// assert!(samples.iter().all(|&i| i == 0 || i == 2));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AliasTable {
+    prob:  Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Build the table for a distribution proportional to `weights`.
+    ///
+    /// Returns `None` if `weights` is empty, any weight is negative or not
+    /// finite, or all weights are zero.
+    #[must_use]
+    pub fn new(weights: &[f64]) -> Option<Self> {
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return None;
+        }
+        let total: f64 = weights.iter().sum();
+        if weights.is_empty() || total <= 0.0 {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = weights.len() as f64;
+        let mut prob: Vec<_> = weights.iter().map(|w| w * n / total).collect();
+        let mut alias: Vec<_> = (0..weights.len()).collect();
+
+        let (mut small, mut large): (Vec<_>, Vec<_>) =
+            (0..weights.len()).partition(|&i| prob[i] < 1.0);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            alias[s] = l;
+            prob[l] -= 1.0 - prob[s];
+            if prob[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Remaining entries are equal to one up to rounding errors
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+
+        Some(Self {
+            prob,
+            alias,
+        })
+    }
+
+    /// Number of outcomes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Distribution<usize> for AliasTable {
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> usize {
+        let i = rng.gen_range(0..self.prob.len());
+        if rng.gen::<f64>() < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+/// qDRIFT sampler for a sum of Pauli strings.
+///
+/// For a Hamiltonian `H = Σ_j h_j P_j` with `λ = Σ_j |h_j|`, the evolution
+/// `exp(-i H t)` is approximated by a product of `N` rotations
+/// `exp(-i τ sgn(h_j) P_j)` with `τ = λ t / N`, where each term `j` is drawn
+/// independently with probability `|h_j| / λ`.
+///
+/// The identity term only contributes a global phase and is excluded from
+/// sampling.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, sample::QDrift, terms::SumRepr};
+/// use rand::{
+///     rngs::StdRng,
+///     SeedableRng,
+/// };
+///
+/// let repr = SumRepr::from([
+///     (1.0, Paulis::identity()),
+///     (0.5, Paulis::with_ops([Sigma::X])),
+///     (-1.5, Paulis::with_ops([Sigma::Z])),
+/// ]);
+/// let qdrift = QDrift::new(&repr).unwrap();
+/// assert_eq!(qdrift.lambda(), 2.0);
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let seq = qdrift.sequence(100, 1.0, &mut rng);
+///
+/// assert_eq!(seq.len(), 100);
+/// assert!(seq.iter().all(|&(angle, _)| f64::abs(angle) == 0.02));
+/// ```
+#[derive(Clone, Debug)]
+pub struct QDrift<T> {
+    terms:  Vec<(T, Paulis)>,
+    table:  AliasTable,
+    lambda: T,
+}
+
+impl<T> QDrift<T>
+where
+    T: Float,
+{
+    /// Prepare the sampler.
+    ///
+    /// Returns `None` if the sum has no terms other than the identity with
+    /// nonzero coefficients.
+    #[must_use]
+    pub fn new(repr: &SumRepr<T, Paulis>) -> Option<Self> {
+        let mut terms: Vec<_> = repr
+            .iter()
+            .filter(|(coeff, code)| {
+                **code != Paulis::identity() && !coeff.is_zero()
+            })
+            .map(|(&coeff, &code)| (coeff, code))
+            .collect();
+        // Fix the order of terms, so that sampling is reproducible
+        terms.sort_by_key(|&(_, code)| code);
+
+        let weights: Option<Vec<_>> = terms
+            .iter()
+            .map(|(coeff, _)| coeff.abs().to_f64())
+            .collect();
+        let table = AliasTable::new(&weights?)?;
+        let lambda = terms
+            .iter()
+            .fold(T::zero(), |acc, (coeff, _)| acc + coeff.abs());
+
+        Some(Self {
+            terms,
+            table,
+            lambda,
+        })
+    }
+
+    /// Normalization: sum of absolute values of coefficients.
+    #[must_use]
+    pub fn lambda(&self) -> T {
+        self.lambda
+    }
+
+    /// Terms to sample from, with their original coefficients.
+    #[must_use]
+    pub fn terms(&self) -> &[(T, Paulis)] {
+        &self.terms
+    }
+
+    /// Sample a single term.
+    ///
+    /// Returns the index of the term in [`QDrift::terms()`].
+    pub fn sample_index<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> usize {
+        self.table.sample(rng)
+    }
+
+    /// Sample a qDRIFT sequence of `num_samples` rotations approximating
+    /// evolution for `time`.
+    ///
+    /// Each element of the returned sequence is a pair: `(angle, code)`
+    /// representing the unitary `exp(-i * angle * code)`.  The rotations
+    /// should be applied in the order of the sequence.
+    pub fn sequence<R: Rng + ?Sized>(
+        &self,
+        num_samples: usize,
+        time: T,
+        rng: &mut R,
+    ) -> Vec<(T, Paulis)> {
+        if num_samples == 0 {
+            return Vec::new();
+        }
+        let tau = self.lambda * time
+            / T::from(num_samples).expect("conversion from usize");
+
+        (0..num_samples)
+            .map(|_| {
+                let (coeff, code) = self.terms[self.sample_index(rng)];
+                (if coeff < T::zero() { -tau } else { tau }, code)
+            })
+            .collect()
+    }
+}
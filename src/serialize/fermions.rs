@@ -1,8 +1,15 @@
-use std::marker::PhantomData;
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+};
 
 use num::Num;
 use serde::{
-    de::Visitor,
+    de::{
+        DeserializeSeed,
+        IgnoredAny,
+        Visitor,
+    },
     ser::SerializeSeq,
     Deserialize,
     Serialize,
@@ -16,7 +23,11 @@ use crate::{
         Orbital,
     },
     serialize::Encoding,
-    terms::SumRepr,
+    stream::FermiTerms,
+    terms::{
+        Sorted,
+        SumRepr,
+    },
 };
 
 impl Serialize for Fermions {
@@ -117,9 +128,9 @@ struct FermiSumTerm<T> {
     value: T,
 }
 
-struct FermiSumSerSequence<'a, T>(&'a SumRepr<T, Fermions>);
+struct FermiSumSerSequence<'a, 'b, T>(&'b Sorted<'a, T, Fermions>);
 
-impl<'a, T> Serialize for FermiSumSerSequence<'a, T>
+impl<'a, 'b, T> Serialize for FermiSumSerSequence<'a, 'b, T>
 where
     T: Num + Serialize,
 {
@@ -143,16 +154,16 @@ where
 }
 
 #[derive(Serialize)]
-struct FermiSumSer<'a, T>
+struct FermiSumSer<'a, 'b, T>
 where
     T: Num,
 {
     r#type:   &'a str,
     encoding: Encoding,
-    terms:    FermiSumSerSequence<'a, T>,
+    terms:    FermiSumSerSequence<'a, 'b, T>,
 }
 
-impl<T> Serialize for SumRepr<T, Fermions>
+impl<'a, T> Serialize for Sorted<'a, T, Fermions>
 where
     T: Num + Serialize,
 {
@@ -172,6 +183,22 @@ where
     }
 }
 
+/// Terms are serialized in the order of codes.
+impl<T> Serialize for SumRepr<T, Fermions>
+where
+    T: Num + Serialize,
+{
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Sorted::by_code(self).serialize(serializer)
+    }
+}
+
 struct FermiSumDeSequence<T>(SumRepr<T, Fermions>);
 
 struct FermiSumVisitor<T> {
@@ -266,3 +293,155 @@ where
         Ok(sumde.terms.0)
     }
 }
+
+struct FermiTermsSeqVisitor<'a, T, F> {
+    f:       &'a mut F,
+    _marker: PhantomData<T>,
+}
+
+impl<'de, 'a, T, F, E> Visitor<'de> for FermiTermsSeqVisitor<'a, T, F>
+where
+    T: Num + Deserialize<'de>,
+    F: FnMut(T, Fermions) -> Result<(), E>,
+    E: Display,
+{
+    type Value = ();
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        write!(formatter, "sequence of objects with keys: 'code', 'value'")
+    }
+
+    fn visit_seq<A>(
+        self,
+        seq: A,
+    ) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut seq = seq;
+        while let Some(FermiSumTerm {
+            code,
+            value,
+        }) = seq.next_element()?
+        {
+            (self.f)(value, code).map_err(A::Error::custom)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, 'a, T, F, E> DeserializeSeed<'de> for FermiTermsSeqVisitor<'a, T, F>
+where
+    T: Num + Deserialize<'de>,
+    F: FnMut(T, Fermions) -> Result<(), E>,
+    E: Display,
+{
+    type Value = ();
+
+    fn deserialize<D>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, F, E> Visitor<'de> for FermiTerms<T, F>
+where
+    T: Num + Deserialize<'de>,
+    F: FnMut(T, Fermions) -> Result<(), E>,
+    E: Display,
+{
+    type Value = ();
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        write!(formatter, "map with keys: 'type', 'encoding', 'terms'")
+    }
+
+    fn visit_map<A>(
+        mut self,
+        map: A,
+    ) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut map = map;
+        let (mut has_type, mut has_encoding, mut has_terms) =
+            (false, false, false);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => {
+                    if map.next_value::<String>()? != "sumrepr" {
+                        return Err(A::Error::custom(
+                            "type should be: 'sumrepr'",
+                        ));
+                    }
+                    has_type = true;
+                }
+                "encoding" => {
+                    if map.next_value::<Encoding>()? != Encoding::Fermions {
+                        return Err(A::Error::custom(
+                            "encoding should be: 'fermions'",
+                        ));
+                    }
+                    has_encoding = true;
+                }
+                "terms" => {
+                    map.next_value_seed(FermiTermsSeqVisitor {
+                        f:       &mut self.f,
+                        _marker: PhantomData,
+                    })?;
+                    has_terms = true;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !has_type {
+            return Err(A::Error::missing_field("type"));
+        }
+        if !has_encoding {
+            return Err(A::Error::missing_field("encoding"));
+        }
+        if !has_terms {
+            return Err(A::Error::missing_field("terms"));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, T, F, E> DeserializeSeed<'de> for FermiTerms<T, F>
+where
+    T: Num + Deserialize<'de>,
+    F: FnMut(T, Fermions) -> Result<(), E>,
+    E: Display,
+{
+    type Value = ();
+
+    fn deserialize<D>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
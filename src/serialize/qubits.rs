@@ -1,4 +1,7 @@
-use std::marker::PhantomData;
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+};
 
 use num::Num;
 use serde::{
@@ -14,7 +17,11 @@ use crate::{
         Sigma,
     },
     serialize::Encoding,
-    terms::SumRepr,
+    stream::PauliTerms,
+    terms::{
+        Sorted,
+        SumRepr,
+    },
 };
 
 impl Serialize for Sigma {
@@ -156,9 +163,9 @@ struct PauliSumTerm<T> {
     value: T,
 }
 
-struct PauliSumSerSequence<'a, T>(&'a SumRepr<T, Paulis>);
+struct PauliSumSerSequence<'a, 'b, T>(&'b Sorted<'a, T, Paulis>);
 
-impl<'a, T> Serialize for PauliSumSerSequence<'a, T>
+impl<'a, 'b, T> Serialize for PauliSumSerSequence<'a, 'b, T>
 where
     T: Num + Copy + Serialize,
 {
@@ -182,16 +189,16 @@ where
 }
 
 #[derive(Serialize)]
-struct PauliSumSer<'a, T>
+struct PauliSumSer<'a, 'b, T>
 where
     T: Num + Copy,
 {
     r#type:   &'a str,
     encoding: Encoding,
-    terms:    PauliSumSerSequence<'a, T>,
+    terms:    PauliSumSerSequence<'a, 'b, T>,
 }
 
-impl<T> Serialize for SumRepr<T, Paulis>
+impl<'a, T> Serialize for Sorted<'a, T, Paulis>
 where
     T: Num + Copy + Serialize,
 {
@@ -211,6 +218,88 @@ where
     }
 }
 
+/// Terms are serialized in the order of codes.
+impl<T> Serialize for SumRepr<T, Paulis>
+where
+    T: Num + Copy + Serialize,
+{
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Sorted::by_code(self).serialize(serializer)
+    }
+}
+
+struct PauliTermsSerSequence<'a, I>(&'a PauliTerms<I>);
+
+impl<'a, I, T, E> Serialize for PauliTermsSerSequence<'a, I>
+where
+    I: Iterator<Item = Result<(T, Paulis), E>>,
+    T: Num + Copy + Serialize,
+    E: Display,
+{
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let iter = self
+            .0
+            .iter
+            .borrow_mut()
+            .take()
+            .ok_or_else(|| S::Error::custom("terms already serialized"))?;
+        let mut seq = serializer.serialize_seq(None)?;
+        for term in iter {
+            let (coeff, code) = term.map_err(S::Error::custom)?;
+            seq.serialize_element(&PauliSumTerm {
+                code,
+                value: coeff,
+            })?;
+        }
+
+        seq.end()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(bound(serialize = "PauliTermsSerSequence<'a, I>: Serialize"))]
+struct PauliTermsSer<'a, I> {
+    r#type:   &'a str,
+    encoding: Encoding,
+    terms:    PauliTermsSerSequence<'a, I>,
+}
+
+impl<I, T, E> Serialize for PauliTerms<I>
+where
+    I: Iterator<Item = Result<(T, Paulis), E>>,
+    T: Num + Copy + Serialize,
+    E: Display,
+{
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (PauliTermsSer {
+            r#type:   "sumrepr",
+            encoding: Encoding::Qubits,
+            terms:    PauliTermsSerSequence(self),
+        })
+        .serialize(serializer)
+    }
+}
+
 struct PauliSumDeSequence<T>(SumRepr<T, Paulis>);
 
 struct PauliSumVisitor<T> {
@@ -0,0 +1,377 @@
+//! Out-of-core conversion of large Hamiltonians.
+//!
+//! The types in this module let you convert Hamiltonians that do not fit in
+//! memory:
+//!
+//! - [`FermiTerms`] deserializes a `SumRepr<T, Fermions>` term by term, without
+//!   storing the terms,
+//! - [`SpillSum`] accumulates Pauli terms in memory up to a fixed number of
+//!   terms, and spills them to sorted runs on disk, which are merged and
+//!   reduced at the end by [`Merge`],
+//! - [`PauliTerms`] serializes terms produced by an iterator in the same format
+//!   as `SumRepr<T, Paulis>`.
+//!
+//! Peak memory is then bounded by the size of the in-memory buffer, regardless
+//! of the size of the Hamiltonian.
+//!
+//! Runs are stored as binary files of records of 24 bytes: a Pauli code as
+//! `u128`, followed by a coefficient as `f64`, both little-endian.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use f2q::{
+//! #     code::qubits::{Paulis, Sigma},
+//! #     stream::SpillSum,
+//! # };
+//! # fn main() -> std::io::Result<()> {
+//! let mut spill = SpillSum::new(std::env::temp_dir(), 2);
+//! spill.try_extend([
+//!     (1.0, Paulis::with_ops([Sigma::Z])),
+//!     (0.5, Paulis::identity()),
+//!     (1.0, Paulis::with_ops([Sigma::X])),
+//!     (0.5, Paulis::identity()),
+//! ])?;
+//! assert_eq!(spill.num_runs(), 2);
+//!
+//! let terms = spill.finish()?.collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(
+//!     terms,
+//!     &[
+//!         (1.0, Paulis::identity()),
+//!         (1.0, Paulis::with_ops([Sigma::X])),
+//!         (1.0, Paulis::with_ops([Sigma::Z])),
+//!     ]
+//! );
+//! #   Ok(())
+//! # }
+//! ```
+
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{
+        self,
+        BufRead,
+        BufReader,
+        BufWriter,
+        Read,
+        Write,
+    },
+    marker::PhantomData,
+    path::PathBuf,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use crate::{
+    code::qubits::Paulis,
+    terms::{
+        packed,
+        PackedSum,
+    },
+};
+
+/// Size of a record in a run file.
+const RECORD_SIZE: usize = 24;
+
+/// Deserialize terms of a sum of fermionic terms one by one.
+///
+/// This implements [`serde::de::DeserializeSeed`] for the format of
+/// `SumRepr<T, Fermions>`.  Each term is passed to the callback as soon as it
+/// is read, and the terms are not stored.  Terms with the same code are not
+/// accumulated.  If the callback returns an error, deserialization stops and
+/// the error message is returned as a deserialization error.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::fermions::Fermions, stream::FermiTerms};
+/// use serde::de::DeserializeSeed;
+///
+/// let input = r#"{
+///     "type": "sumrepr",
+///     "encoding": "fermions",
+///     "terms": [
+///         { "code": [], "value": 0.5 },
+///         { "code": [0, 1], "value": 1.0 }
+///     ]
+/// }"#;
+///
+/// let mut terms = vec![];
+/// FermiTerms::new(|coeff: f64, code| {
+///     terms.push((coeff, code));
+///     Ok::<_, String>(())
+/// })
+/// .deserialize(&mut serde_json::Deserializer::from_str(input))
+/// .unwrap();
+///
+/// assert_eq!(
+///     terms,
+///     &[
+///         (0.5, Fermions::Offset),
+///         (1.0, Fermions::try_from((0, 1)).unwrap())
+///     ]
+/// );
+/// ```
+pub struct FermiTerms<T, F> {
+    pub(crate) f: F,
+    _marker:      PhantomData<fn(T)>,
+}
+
+impl<T, F> FermiTerms<T, F> {
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Serialize terms produced by an iterator.
+///
+/// The output has the same format as that of `SumRepr<T, Paulis>`, with terms
+/// in the order of the iterator, which should produce each code only once.
+/// Iterator items are results, and the first error stops serialization.
+/// The value can be serialized only once.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, stream::PauliTerms};
+/// let terms = PauliTerms::new(
+///     [(0.5, Paulis::identity()), (1.0, Paulis::with_ops([Sigma::X]))]
+///         .into_iter()
+///         .map(Ok::<_, String>),
+/// );
+///
+/// assert_eq!(
+///     serde_json::to_string(&terms).unwrap(),
+///     r#"{"type":"sumrepr","encoding":"qubits","terms":[{"code":"I","value":0.5},{"code":"X","value":1.0}]}"#
+/// );
+/// ```
+pub struct PauliTerms<I> {
+    pub(crate) iter: RefCell<Option<I>>,
+}
+
+impl<I> PauliTerms<I> {
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: RefCell::new(Some(iter)),
+        }
+    }
+}
+
+static SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Sum of Pauli strings spilled to disk in sorted runs.
+///
+/// Terms are accumulated in a [`PackedSum`] until it holds `max_terms` terms.
+/// The buffer is then written to a new run file in the directory supplied,
+/// and cleared.  Run files are removed when no longer needed.
+#[derive(Debug)]
+pub struct SpillSum {
+    dir:       PathBuf,
+    id:        usize,
+    max_terms: usize,
+    buffer:    PackedSum<f64>,
+    runs:      Vec<PathBuf>,
+}
+
+impl SpillSum {
+    /// Create new, empty sum.
+    ///
+    /// Run files will be created in `dir`, each with at most `max_terms`
+    /// terms.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        max_terms: usize,
+    ) -> Self {
+        Self {
+            dir:       dir.into(),
+            id:        SPILL_ID.fetch_add(1, Ordering::Relaxed),
+            max_terms: max_terms.max(1),
+            buffer:    PackedSum::new(),
+            runs:      Vec::new(),
+        }
+    }
+
+    /// Number of runs spilled to disk so far.
+    #[must_use]
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Add terms to the sum, spilling the buffer to disk whenever it is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error if a run cannot be written.
+    pub fn try_extend<I>(
+        &mut self,
+        terms: I,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = (f64, Paulis)>,
+    {
+        let mut terms = terms.into_iter();
+        loop {
+            let room = self.max_terms.saturating_sub(self.buffer.len()).max(1);
+            let mut num_taken = 0;
+            self.buffer
+                .extend(terms.by_ref().take(room).inspect(|_| num_taken += 1));
+            if self.buffer.len() >= self.max_terms {
+                self.spill()?;
+            }
+            if num_taken < room {
+                return Ok(());
+            }
+        }
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        let path = self.dir.join(format!(
+            "f2q-{}-{}-{}.run",
+            std::process::id(),
+            self.id,
+            self.runs.len()
+        ));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        self.runs.push(path);
+        for (coeff, code) in std::mem::take(&mut self.buffer) {
+            writer.write_all(&code.index().to_le_bytes())?;
+            writer.write_all(&coeff.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Merge the runs and the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O error if a run cannot be opened.
+    pub fn finish(mut self) -> io::Result<Merge> {
+        let mut sources = Vec::with_capacity(self.runs.len() + 1);
+        for path in &self.runs {
+            sources.push(Source::Run(BufReader::new(File::open(path)?)));
+        }
+        sources
+            .push(Source::Buffer(std::mem::take(&mut self.buffer).into_iter()));
+
+        Merge::new(sources, std::mem::take(&mut self.runs))
+    }
+}
+
+impl Drop for SpillSum {
+    fn drop(&mut self) {
+        for path in &self.runs {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Source {
+    Run(BufReader<File>),
+    Buffer(packed::IntoIter<f64>),
+}
+
+impl Source {
+    fn next_term(&mut self) -> io::Result<Option<(u128, f64)>> {
+        match self {
+            Self::Run(reader) => {
+                if reader.fill_buf()?.is_empty() {
+                    return Ok(None);
+                }
+                let mut buf = [0; RECORD_SIZE];
+                reader.read_exact(&mut buf)?;
+                let (code, coeff) = buf.split_at(16);
+                Ok(Some((
+                    u128::from_le_bytes(code.try_into().expect("16 bytes")),
+                    f64::from_le_bytes(coeff.try_into().expect("8 bytes")),
+                )))
+            }
+            Self::Buffer(iter) => {
+                Ok(iter.next().map(|(coeff, code)| (code.index(), coeff)))
+            }
+        }
+    }
+}
+
+/// Terms of [`SpillSum`] in the increasing order of codes.
+///
+/// Sorted runs are merged, and coefficients of terms with the same code are
+/// added together.  Run files are removed when the iterator is dropped.
+#[derive(Debug)]
+pub struct Merge {
+    sources: Vec<Source>,
+    heads:   BinaryHeap<Reverse<(u128, usize)>>,
+    coeffs:  Vec<f64>,
+    runs:    Vec<PathBuf>,
+}
+
+impl Merge {
+    fn new(
+        sources: Vec<Source>,
+        runs: Vec<PathBuf>,
+    ) -> io::Result<Self> {
+        let mut merge = Self {
+            heads: BinaryHeap::with_capacity(sources.len()),
+            coeffs: vec![0.0; sources.len()],
+            sources,
+            runs,
+        };
+        for i in 0..merge.sources.len() {
+            merge.advance(i)?;
+        }
+        Ok(merge)
+    }
+
+    fn advance(
+        &mut self,
+        i: usize,
+    ) -> io::Result<()> {
+        if let Some((code, coeff)) = self.sources[i].next_term()? {
+            self.coeffs[i] = coeff;
+            self.heads.push(Reverse((code, i)));
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<(f64, Paulis)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((code, i)) = self.heads.pop()?;
+        let mut coeff = self.coeffs[i];
+        if let Err(err) = self.advance(i) {
+            return Some(Err(err));
+        }
+        while let Some(&Reverse((next_code, j))) = self.heads.peek() {
+            if next_code != code {
+                break;
+            }
+            self.heads.pop();
+            coeff += self.coeffs[j];
+            if let Err(err) = self.advance(j) {
+                return Some(Err(err));
+            }
+        }
+
+        Some(Ok((coeff, Paulis::from(code))))
+    }
+}
+
+impl Drop for Merge {
+    fn drop(&mut self) {
+        self.sources.clear();
+        for path in &self.runs {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! Represent sum of terms.
 
+use std::ops::Mul;
+
 use crate::{
     code::{
         fermions::Fermions,
@@ -12,10 +14,67 @@ use crate::{
 pub type FermiSum = SumRepr<f64, Fermions>;
 pub type PauliSum = SumRepr<f64, Paulis>;
 
+pub use adapters::{
+    Chain,
+    Filter,
+    MapCode,
+    MapCoeff,
+    Scale,
+    TakeWhile,
+};
+pub use compact::Compacting;
+pub use diff::Diff;
+#[doc(inline)]
+pub use packed::PackedSum;
+pub use sorted::{
+    SortOrder,
+    Sorted,
+};
 #[doc(inline)]
 pub use sumrepr::SumRepr;
 
+mod adapters;
+mod compact;
+mod diff;
+mod metrics;
+mod ops;
+pub mod packed;
+mod prune;
+mod sorted;
+
 /// Convert and serialize sum of terms in various encodings
+///
+/// Sources of terms can be transformed with adapters: [`Terms::map_coeff()`],
+/// [`Terms::map_code()`], [`Terms::scale()`], [`Terms::filter()`],
+/// [`Terms::take_while()`] and [`Terms::chain()`], without storing the
+/// intermediate terms.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{
+/// #     code::{fermions::Fermions, qubits::{Paulis, Sigma}},
+/// #     map::JordanWigner,
+/// #     terms::{PauliSum, SumRepr, Terms},
+/// # };
+/// # fn main() -> Result<(), f2q::Error> {
+/// let fermi_repr = SumRepr::from([
+///     (1.0, Fermions::try_from((0, 0)).unwrap()),
+///     (1e-3, Fermions::try_from((1, 1)).unwrap()),
+/// ]);
+///
+/// let mut pauli_repr = PauliSum::new();
+/// JordanWigner::new(&fermi_repr)
+///     .scale(0.5)
+///     .filter(|(coeff, _)| f64::abs(*coeff) > 1e-3)
+///     .add_to(&mut pauli_repr)?;
+///
+/// assert_eq!(pauli_repr.coeff(Paulis::identity()), Some(&0.5));
+/// assert_eq!(pauli_repr.coeff(Paulis::with_ops([Sigma::Z])), Some(&-0.5));
+/// assert_eq!(pauli_repr.len(), 2);
+/// #   Ok(())
+/// # }
+/// ```
 pub trait Terms<T> {
     type Error;
 
@@ -27,7 +86,167 @@ pub trait Terms<T> {
     fn add_to(
         self,
         repr: &mut impl Extend<T>,
-    ) -> Result<(), Error>;
+    ) -> Result<(), Self::Error>;
+
+    /// Transform coefficients of terms with a function.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{SumRepr, Terms};
+    /// let repr = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    ///
+    /// let mut result = SumRepr::new();
+    /// repr.map_coeff(|coeff| coeff * coeff)
+    ///     .add_to(&mut result)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(1.0, 1), (4.0, 2)]));
+    /// ```
+    fn map_coeff<C, K, U, F>(
+        self,
+        f: F,
+    ) -> MapCoeff<Self, F, C>
+    where
+        Self: Sized + Terms<(C, K)>,
+        F: FnMut(C) -> U,
+    {
+        MapCoeff::new(self, f)
+    }
+
+    /// Transform codes of terms with a function.
+    ///
+    /// Terms whose codes are mapped to the same value are accumulated by
+    /// the sink, as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{SumRepr, Terms};
+    /// let repr = SumRepr::from([(1.0, 1), (2.0, 2), (3.0, 3)]);
+    ///
+    /// let mut result = SumRepr::new();
+    /// repr.map_code(|code| code % 2).add_to(&mut result).unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(4.0, 1), (2.0, 0)]));
+    /// ```
+    fn map_code<C, K, L, F>(
+        self,
+        f: F,
+    ) -> MapCode<Self, F, K>
+    where
+        Self: Sized + Terms<(C, K)>,
+        F: FnMut(K) -> L,
+    {
+        MapCode::new(self, f)
+    }
+
+    /// Multiply coefficients of terms by a factor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{SumRepr, Terms};
+    /// let repr = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    ///
+    /// let mut result = SumRepr::new();
+    /// repr.scale(0.5).add_to(&mut result).unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(0.5, 1), (1.0, 2)]));
+    /// ```
+    fn scale<C, K>(
+        self,
+        factor: C,
+    ) -> Scale<Self, C>
+    where
+        Self: Sized + Terms<(C, K)>,
+        C: Mul<Output = C> + Copy,
+    {
+        Scale::new(self, factor)
+    }
+
+    /// Keep only terms that satisfy a predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{SumRepr, Terms};
+    /// let repr = SumRepr::from([(1.0, 1), (-2.0, 2)]);
+    ///
+    /// let mut result = SumRepr::new();
+    /// repr.filter(|&(coeff, _)| coeff > 0.0)
+    ///     .add_to(&mut result)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(1.0, 1)]));
+    /// ```
+    fn filter<F>(
+        self,
+        f: F,
+    ) -> Filter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&T) -> bool,
+    {
+        Filter::new(self, f)
+    }
+
+    /// Keep terms up to the first one that fails a predicate.
+    ///
+    /// The remaining terms are still produced by the source, but they are
+    /// discarded.  This is useful only for sources that produce terms in a
+    /// known order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{StackRepr, SumRepr, Terms};
+    /// let mut iter = [(1.0, 1), (2.0, 2), (3.0, 3), (0.0, 4)].into_iter();
+    /// let repr = StackRepr::new(|| iter.next());
+    ///
+    /// let mut result = SumRepr::new();
+    /// repr.take_while(|&(coeff, _)| coeff < 2.5)
+    ///     .add_to(&mut result)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(1.0, 1), (2.0, 2)]));
+    /// ```
+    fn take_while<F>(
+        self,
+        f: F,
+    ) -> TakeWhile<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&T) -> bool,
+    {
+        TakeWhile::new(self, f)
+    }
+
+    /// Add terms of `self`, followed by terms of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::{SumRepr, Terms};
+    /// let a = SumRepr::from([(1.0, 1)]);
+    /// let b = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    ///
+    /// let mut result = SumRepr::new();
+    /// a.chain(b).add_to(&mut result).unwrap();
+    ///
+    /// assert_eq!(result, SumRepr::from([(2.0, 1), (2.0, 2)]));
+    /// ```
+    fn chain<S>(
+        self,
+        other: S,
+    ) -> Chain<Self, S>
+    where
+        Self: Sized,
+        S: Terms<T>,
+        S::Error: Into<Self::Error>,
+    {
+        Chain::new(self, other)
+    }
 }
 
 pub mod sumrepr {
@@ -36,6 +255,8 @@ pub mod sumrepr {
         ops::Add,
     };
 
+    use rayon::prelude::*;
+
     use super::Terms;
     use crate::{
         code::Code,
@@ -43,7 +264,7 @@ pub mod sumrepr {
     };
 
     /// Weighted sum of codes
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     pub struct SumRepr<T, K> {
         terms: HashMap<K, T>,
     }
@@ -175,6 +396,42 @@ pub mod sumrepr {
         pub fn iter_mut(&mut self) -> impl Iterator<Item = (&mut T, &K)> {
             self.terms.iter_mut().map(|(code, coeff)| (coeff, code))
         }
+
+        /// Iterate over terms in the sum in parallel.
+        ///
+        /// This is the parallel counterpart of [`SumRepr::iter()`].
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use f2q::terms::SumRepr;
+        /// use rayon::prelude::*;
+        ///
+        /// let repr = SumRepr::from([(0.5, 1), (0.5, 2)]);
+        /// let sum: f64 = repr.par_iter().map(|(coeff, _)| coeff).sum();
+        ///
+        /// assert_eq!(sum, 1.0);
+        /// ```
+        pub fn par_iter(&self) -> impl ParallelIterator<Item = (&T, &K)>
+        where
+            T: Sync,
+            K: Sync,
+        {
+            self.terms.par_iter().map(|(code, coeff)| (coeff, code))
+        }
+    }
+
+    impl<T, K> PartialEq for SumRepr<T, K>
+    where
+        T: PartialEq,
+        K: Code,
+    {
+        fn eq(
+            &self,
+            other: &Self,
+        ) -> bool {
+            self.terms == other.terms
+        }
     }
 
     pub struct IntoIter<T, K>(std::collections::hash_map::IntoIter<K, T>);
@@ -245,6 +502,47 @@ pub mod sumrepr {
             self.terms.insert(code, coeff)
         }
 
+        /// Remove the term with the given code.
+        ///
+        /// Returns the coefficient, if present, or `None`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use f2q::terms::SumRepr;
+        /// let mut repr = SumRepr::from([(0.5, 1)]);
+        ///
+        /// assert_eq!(repr.remove(1), Some(0.5));
+        /// assert_eq!(repr.remove(1), None);
+        /// assert!(repr.is_empty());
+        /// ```
+        pub fn remove(
+            &mut self,
+            code: K,
+        ) -> Option<T> {
+            self.terms.remove(&code)
+        }
+
+        /// Retain only the terms specified by the predicate.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// # use f2q::terms::SumRepr;
+        /// let mut repr = SumRepr::from([(0.5, 1), (-0.5, 2), (1.0, 3)]);
+        /// repr.retain(|&coeff, &code| coeff > 0.0 && code < 3);
+        ///
+        /// assert_eq!(repr, SumRepr::from([(0.5, 1)]));
+        /// ```
+        pub fn retain<F>(
+            &mut self,
+            mut f: F,
+        ) where
+            F: FnMut(&T, &K) -> bool,
+        {
+            self.terms.retain(|code, coeff| f(coeff, code));
+        }
+
         /// Add coefficient to the given code.
         ///
         /// # Examples
@@ -303,7 +601,7 @@ pub mod sumrepr {
         fn add_to(
             self,
             repr: &mut impl Extend<(T, K)>,
-        ) -> Result<(), Error> {
+        ) -> Result<(), Self::Error> {
             repr.extend(self);
             Ok(())
         }
@@ -319,7 +617,7 @@ pub mod sumrepr {
         fn add_to(
             self,
             repr: &mut impl Extend<(T, K)>,
-        ) -> Result<(), Error> {
+        ) -> Result<(), Self::Error> {
             repr.extend(self.iter().map(|(&t, &k)| (t, k)));
             Ok(())
         }
@@ -372,7 +670,7 @@ where
     fn add_to(
         mut self,
         repr: &mut impl Extend<(T, K)>,
-    ) -> Result<(), Error> {
+    ) -> Result<(), Self::Error> {
         while let Some((coeff, code)) = (self.f)() {
             repr.extend(Some((coeff, code)));
         }
@@ -406,7 +704,7 @@ where
     fn add_to(
         mut self,
         repr: &mut impl Extend<(T, K)>,
-    ) -> Result<(), Error> {
+    ) -> Result<(), Self::Error> {
         while let Some((coeff, code)) = (self.f)() {
             repr.extend(Some((coeff, code)));
         }
@@ -0,0 +1,326 @@
+//! Adapters over sources of terms.
+//!
+//! The adapters are created by the provided methods of [`Terms`].  They do not
+//! store any terms themselves: each adapter wraps the sink passed to
+//! [`Terms::add_to()`] and transforms terms on their way to it.
+
+use std::{
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use crate::terms::Terms;
+
+/// Sink passing transformed items on.
+struct MapSink<'a, E, F> {
+    sink: &'a mut E,
+    f:    F,
+}
+
+impl<'a, A, B, E, F> Extend<A> for MapSink<'a, E, F>
+where
+    E: Extend<B>,
+    F: FnMut(A) -> B,
+{
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = A>,
+    {
+        self.sink.extend(iter.into_iter().map(&mut self.f));
+    }
+}
+
+/// Sink passing on items that satisfy a predicate.
+struct FilterSink<'a, E, F> {
+    sink: &'a mut E,
+    f:    F,
+}
+
+impl<'a, A, E, F> Extend<A> for FilterSink<'a, E, F>
+where
+    E: Extend<A>,
+    F: FnMut(&A) -> bool,
+{
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = A>,
+    {
+        let f = &mut self.f;
+        self.sink.extend(iter.into_iter().filter(|item| f(item)));
+    }
+}
+
+/// Sink passing on items until the first one that fails a predicate.
+struct TakeWhileSink<'a, E, F> {
+    sink: &'a mut E,
+    f:    F,
+    done: bool,
+}
+
+impl<'a, A, E, F> Extend<A> for TakeWhileSink<'a, E, F>
+where
+    E: Extend<A>,
+    F: FnMut(&A) -> bool,
+{
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = A>,
+    {
+        if self.done {
+            return;
+        }
+        let f = &mut self.f;
+        let done = &mut self.done;
+        self.sink.extend(iter.into_iter().take_while(|item| {
+            *done = !f(item);
+            !*done
+        }));
+    }
+}
+
+/// Terms with coefficients transformed by a function.
+///
+/// See [`Terms::map_coeff()`].
+#[derive(Debug)]
+pub struct MapCoeff<S, F, T> {
+    terms:   S,
+    f:       F,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<S, F, T> MapCoeff<S, F, T> {
+    pub(crate) fn new(
+        terms: S,
+        f: F,
+    ) -> Self {
+        Self {
+            terms,
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S, F, T, U, K> Terms<(U, K)> for MapCoeff<S, F, T>
+where
+    S: Terms<(T, K)>,
+    F: FnMut(T) -> U,
+{
+    type Error = S::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(U, K)>,
+    ) -> Result<(), Self::Error> {
+        let mut f = self.f;
+        self.terms.add_to(&mut MapSink {
+            sink: repr,
+            f:    |(coeff, code)| (f(coeff), code),
+        })
+    }
+}
+
+/// Terms with codes transformed by a function.
+///
+/// See [`Terms::map_code()`].
+#[derive(Debug)]
+pub struct MapCode<S, F, K> {
+    terms:   S,
+    f:       F,
+    _marker: PhantomData<fn(K)>,
+}
+
+impl<S, F, K> MapCode<S, F, K> {
+    pub(crate) fn new(
+        terms: S,
+        f: F,
+    ) -> Self {
+        Self {
+            terms,
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S, F, T, K, L> Terms<(T, L)> for MapCode<S, F, K>
+where
+    S: Terms<(T, K)>,
+    F: FnMut(K) -> L,
+{
+    type Error = S::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, L)>,
+    ) -> Result<(), Self::Error> {
+        let mut f = self.f;
+        self.terms.add_to(&mut MapSink {
+            sink: repr,
+            f:    |(coeff, code)| (coeff, f(code)),
+        })
+    }
+}
+
+/// Terms with coefficients multiplied by a factor.
+///
+/// See [`Terms::scale()`].
+#[derive(Debug)]
+pub struct Scale<S, T> {
+    terms:  S,
+    factor: T,
+}
+
+impl<S, T> Scale<S, T> {
+    pub(crate) fn new(
+        terms: S,
+        factor: T,
+    ) -> Self {
+        Self {
+            terms,
+            factor,
+        }
+    }
+}
+
+impl<S, T, K> Terms<(T, K)> for Scale<S, T>
+where
+    S: Terms<(T, K)>,
+    T: Mul<Output = T> + Copy,
+{
+    type Error = S::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, K)>,
+    ) -> Result<(), Self::Error> {
+        let factor = self.factor;
+        self.terms.add_to(&mut MapSink {
+            sink: repr,
+            f:    |(coeff, code)| (factor * coeff, code),
+        })
+    }
+}
+
+/// Terms that satisfy a predicate.
+///
+/// See [`Terms::filter()`].
+#[derive(Debug)]
+pub struct Filter<S, F> {
+    terms: S,
+    f:     F,
+}
+
+impl<S, F> Filter<S, F> {
+    pub(crate) fn new(
+        terms: S,
+        f: F,
+    ) -> Self {
+        Self {
+            terms,
+            f,
+        }
+    }
+}
+
+impl<S, F, T> Terms<T> for Filter<S, F>
+where
+    S: Terms<T>,
+    F: FnMut(&T) -> bool,
+{
+    type Error = S::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<T>,
+    ) -> Result<(), Self::Error> {
+        self.terms.add_to(&mut FilterSink {
+            sink: repr,
+            f:    self.f,
+        })
+    }
+}
+
+/// Terms up to the first one that fails a predicate.
+///
+/// See [`Terms::take_while()`].
+#[derive(Debug)]
+pub struct TakeWhile<S, F> {
+    terms: S,
+    f:     F,
+}
+
+impl<S, F> TakeWhile<S, F> {
+    pub(crate) fn new(
+        terms: S,
+        f: F,
+    ) -> Self {
+        Self {
+            terms,
+            f,
+        }
+    }
+}
+
+impl<S, F, T> Terms<T> for TakeWhile<S, F>
+where
+    S: Terms<T>,
+    F: FnMut(&T) -> bool,
+{
+    type Error = S::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<T>,
+    ) -> Result<(), Self::Error> {
+        self.terms.add_to(&mut TakeWhileSink {
+            sink: repr,
+            f:    self.f,
+            done: false,
+        })
+    }
+}
+
+/// Terms of one source followed by terms of another.
+///
+/// See [`Terms::chain()`].
+#[derive(Debug)]
+pub struct Chain<A, B> {
+    first:  A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    pub(crate) fn new(
+        first: A,
+        second: B,
+    ) -> Self {
+        Self {
+            first,
+            second,
+        }
+    }
+}
+
+impl<A, B, T> Terms<T> for Chain<A, B>
+where
+    A: Terms<T>,
+    B: Terms<T>,
+    B::Error: Into<A::Error>,
+{
+    type Error = A::Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<T>,
+    ) -> Result<(), Self::Error> {
+        self.first.add_to(repr)?;
+        self.second.add_to(repr).map_err(Into::into)
+    }
+}
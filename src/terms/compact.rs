@@ -0,0 +1,122 @@
+//! Removal of terms that cancel out.
+
+use num::Float;
+
+use crate::{
+    code::Code,
+    terms::SumRepr,
+};
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Float,
+    K: Code,
+{
+    /// Remove terms with absolute values of coefficients not greater than
+    /// `tolerance`.
+    ///
+    /// With `tolerance` equal to zero, only terms whose coefficients are
+    /// exactly zero are removed.  Terms with coefficients that are NaN are
+    /// never removed.
+    ///
+    /// Returns the number of removed terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let mut repr = SumRepr::from([
+    ///     (1.0, Paulis::identity()),
+    ///     (1.0, Paulis::with_ops([Sigma::Z])),
+    ///     (-1.0, Paulis::with_ops([Sigma::Z])),
+    ///     (1e-17, Paulis::with_ops([Sigma::X])),
+    /// ]);
+    /// assert_eq!(repr.len(), 3);
+    ///
+    /// assert_eq!(repr.compact(0.0), 1);
+    /// assert_eq!(repr.len(), 2);
+    ///
+    /// assert_eq!(repr.compact(1e-15), 1);
+    /// assert_eq!(repr.len(), 1);
+    /// ```
+    pub fn compact(
+        &mut self,
+        tolerance: T,
+    ) -> usize {
+        let len = self.len();
+        self.retain(|coeff, _| coeff.is_nan() || coeff.abs() > tolerance);
+        len - self.len()
+    }
+
+    /// Accumulate terms, removing those that cancel out.
+    ///
+    /// The returned adapter implements [`Extend`].  Each term is added as
+    /// with [`SumRepr::add_term()`], and if the resulting coefficient is
+    /// within `tolerance` from zero, the term is removed from the sum.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{
+    /// #     code::fermions::Fermions,
+    /// #     map::JordanWigner,
+    /// #     terms::{PauliSum, SumRepr, Terms},
+    /// # };
+    /// # fn main() -> Result<(), f2q::Error> {
+    /// // 2 n_0 - 1 = -Z_0: the identity cancels out
+    /// let fermi_repr = SumRepr::from([
+    ///     (1.0, Fermions::try_from((0, 0)).unwrap()),
+    ///     (-1.0, Fermions::Offset),
+    /// ]);
+    ///
+    /// let mut pauli_repr = PauliSum::new();
+    /// JordanWigner::new(&fermi_repr).add_to(&mut pauli_repr.compacting(0.0))?;
+    ///
+    /// assert_eq!(pauli_repr.len(), 1);
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn compacting(
+        &mut self,
+        tolerance: T,
+    ) -> Compacting<'_, T, K> {
+        Compacting {
+            repr: self,
+            tolerance,
+        }
+    }
+}
+
+/// Adapter accumulating terms into [`SumRepr`], removing terms that cancel
+/// out.
+///
+/// See [`SumRepr::compacting()`].
+#[derive(Debug)]
+pub struct Compacting<'a, T, K> {
+    repr:      &'a mut SumRepr<T, K>,
+    tolerance: T,
+}
+
+impl<'a, T, K> Extend<(T, K)> for Compacting<'a, T, K>
+where
+    T: Float,
+    K: Code,
+{
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = (T, K)>,
+    {
+        for (coeff, code) in iter {
+            self.repr.add_term(code, coeff);
+            if self
+                .repr
+                .coeff(code)
+                .is_some_and(|coeff| coeff.abs() <= self.tolerance)
+            {
+                self.repr.remove(code);
+            }
+        }
+    }
+}
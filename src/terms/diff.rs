@@ -0,0 +1,164 @@
+//! Approximate comparison of sums of terms.
+
+use num::Float;
+
+use crate::{
+    code::Code,
+    terms::SumRepr,
+};
+
+/// Terms that differ between two sums.
+///
+/// See [`SumRepr::diff()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diff<T, K> {
+    /// Terms of the left sum missing in the right one.
+    pub left_only:  Vec<(T, K)>,
+    /// Terms of the right sum missing in the left one.
+    pub right_only: Vec<(T, K)>,
+    /// Codes with coefficients that differ: `(left, right, code)`.
+    pub changed:    Vec<(T, T, K)>,
+}
+
+impl<T, K> Diff<T, K> {
+    /// Number of terms that differ.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.left_only.len() + self.right_only.len() + self.changed.len()
+    }
+
+    /// Returns `true` if the sums are approximately equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Check if `a` and `b` are equal up to the tolerance.
+fn is_close<T>(
+    a: T,
+    b: T,
+    atol: T,
+    rtol: T,
+) -> bool
+where
+    T: Float,
+{
+    (a - b).abs() <= atol + rtol * a.abs().max(b.abs())
+}
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Float,
+    K: Code,
+{
+    /// Compare two sums, up to absolute and relative tolerance.
+    ///
+    /// Coefficients `a` and `b` of terms with the same code are considered
+    /// equal if `|a - b| <= atol + rtol * max(|a|, |b|)`.  A code missing in
+    /// one of the sums is treated as a term with coefficient zero, so e.g.
+    /// terms that cancel out do not count as a difference.  Coefficients that
+    /// are NaN are never equal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let a = SumRepr::from([
+    ///     (1.0, Paulis::identity()),
+    ///     (0.5, Paulis::with_ops([Sigma::Z])),
+    /// ]);
+    /// let b = SumRepr::from([
+    ///     (1.0 + 1e-14, Paulis::identity()),
+    ///     (0.5, Paulis::with_ops([Sigma::Z])),
+    ///     (1e-15, Paulis::with_ops([Sigma::X])),
+    /// ]);
+    ///
+    /// assert!(a.approx_eq(&b, 1e-12, 0.0));
+    /// assert!(!a.approx_eq(&b, 1e-16, 0.0));
+    /// ```
+    #[must_use]
+    pub fn approx_eq(
+        &self,
+        other: &Self,
+        atol: T,
+        rtol: T,
+    ) -> bool {
+        let zero = T::zero();
+        self.iter().all(|(&a, &code)| {
+            is_close(a, other.coeff(code).copied().unwrap_or(zero), atol, rtol)
+        }) && other.iter().all(|(&b, &code)| {
+            self.coeff(code).is_some() || is_close(zero, b, atol, rtol)
+        })
+    }
+}
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Float,
+    K: Code + Ord,
+{
+    /// List terms that differ between two sums, in the increasing order of
+    /// codes.
+    ///
+    /// Coefficients are compared as in [`SumRepr::approx_eq()`].  Terms
+    /// present in only one of the sums are reported only if their
+    /// coefficients are not close to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, terms::SumRepr};
+    /// let n_0 = Fermions::try_from((0, 0)).unwrap();
+    /// let n_1 = Fermions::try_from((1, 1)).unwrap();
+    ///
+    /// let a = SumRepr::from([(1.0, Fermions::Offset), (0.5, n_0)]);
+    /// let b = SumRepr::from([(1.0, Fermions::Offset), (0.25, n_0), (2.0, n_1)]);
+    ///
+    /// let diff = a.diff(&b, 1e-12, 1e-9);
+    ///
+    /// assert_eq!(diff.len(), 2);
+    /// assert!(diff.left_only.is_empty());
+    /// assert_eq!(diff.right_only, &[(2.0, n_1)]);
+    /// assert_eq!(diff.changed, &[(0.5, 0.25, n_0)]);
+    /// ```
+    #[must_use]
+    pub fn diff(
+        &self,
+        other: &Self,
+        atol: T,
+        rtol: T,
+    ) -> Diff<T, K> {
+        let zero = T::zero();
+        let mut diff = Diff {
+            left_only:  Vec::new(),
+            right_only: Vec::new(),
+            changed:    Vec::new(),
+        };
+
+        for (&a, &code) in self.iter() {
+            match other.coeff(code) {
+                Some(&b) => {
+                    if !is_close(a, b, atol, rtol) {
+                        diff.changed.push((a, b, code));
+                    }
+                }
+                None => {
+                    if !is_close(a, zero, atol, rtol) {
+                        diff.left_only.push((a, code));
+                    }
+                }
+            }
+        }
+        for (&b, &code) in other.iter() {
+            if self.coeff(code).is_none() && !is_close(zero, b, atol, rtol) {
+                diff.right_only.push((b, code));
+            }
+        }
+
+        diff.left_only.sort_unstable_by_key(|&(_, code)| code);
+        diff.right_only.sort_unstable_by_key(|&(_, code)| code);
+        diff.changed.sort_unstable_by_key(|&(_, _, code)| code);
+        diff
+    }
+}
@@ -0,0 +1,249 @@
+//! Norms and cost metrics of Hamiltonians.
+
+use num::Float;
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::Paulis,
+    },
+    terms::SumRepr,
+};
+
+impl<T> SumRepr<T, Paulis>
+where
+    T: Float,
+{
+    /// Sum of absolute values of coefficients, excluding the identity.
+    ///
+    /// This is the normalization factor λ of the linear combination of
+    /// unitaries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Paulis::identity()),
+    ///     (-0.5, Paulis::with_ops([Sigma::X])),
+    ///     (0.25, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+    /// ]);
+    ///
+    /// assert_eq!(repr.one_norm(), 0.75);
+    /// ```
+    #[must_use]
+    pub fn one_norm(&self) -> T {
+        self.iter()
+            .filter(|(_, code)| **code != Paulis::identity())
+            .fold(T::zero(), |acc, (coeff, _)| acc + coeff.abs())
+    }
+
+    /// Largest absolute value of coefficients, excluding the identity.
+    ///
+    /// Returns `None` if there are no such terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Paulis::identity()),
+    ///     (-0.5, Paulis::with_ops([Sigma::X])),
+    ///     (0.25, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+    /// ]);
+    ///
+    /// assert_eq!(repr.max_coeff(), Some(0.5));
+    /// ```
+    #[must_use]
+    pub fn max_coeff(&self) -> Option<T> {
+        self.iter()
+            .filter(|(_, code)| **code != Paulis::identity())
+            .map(|(coeff, _)| coeff.abs())
+            .reduce(T::max)
+    }
+
+    /// Number of terms of each weight.
+    ///
+    /// The weight of a Pauli string is the number of nontrivial Pauli
+    /// operators, see [`Paulis::num_nontrivial()`].  The returned vector is
+    /// indexed by weight, up to the maximal weight present in the sum.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Paulis::identity()),
+    ///     (-0.5, Paulis::with_ops([Sigma::X])),
+    ///     (0.25, Paulis::with_ops([Sigma::Z, Sigma::I, Sigma::Z])),
+    /// ]);
+    ///
+    /// assert_eq!(repr.weight_histogram(), &[1, 1, 1]);
+    /// ```
+    #[must_use]
+    pub fn weight_histogram(&self) -> Vec<usize> {
+        let mut hist = Vec::new();
+        for (_, code) in self.iter() {
+            let weight = usize::from(code.num_nontrivial());
+            if hist.len() <= weight {
+                hist.resize(weight + 1, 0);
+            }
+            hist[weight] += 1;
+        }
+        hist
+    }
+
+    /// Minimal number of qubits the Hamiltonian acts on.
+    ///
+    /// This is the maximum of [`Paulis::min_register_size()`] over all terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Paulis::identity()),
+    ///     (0.25, Paulis::with_ops([Sigma::Z, Sigma::I, Sigma::Z])),
+    /// ]);
+    ///
+    /// assert_eq!(repr.num_qubits(), 3);
+    /// ```
+    #[must_use]
+    pub fn num_qubits(&self) -> u8 {
+        self.iter()
+            .map(|(_, code)| code.min_register_size())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Coefficient of the identity.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let repr = SumRepr::from([(3.0, Paulis::identity())]);
+    /// assert_eq!(repr.identity_shift(), 3.0);
+    ///
+    /// let repr = SumRepr::from([(1.0, Paulis::with_ops([Sigma::X]))]);
+    /// assert_eq!(repr.identity_shift(), 0.0);
+    /// ```
+    #[must_use]
+    pub fn identity_shift(&self) -> T {
+        self.coeff(Paulis::identity()).copied().unwrap_or(T::zero())
+    }
+}
+
+impl<T> SumRepr<T, Fermions>
+where
+    T: Float,
+{
+    /// Sum of absolute values of coefficients, excluding the offset.
+    ///
+    /// A term with coefficient `c` and code `T` stands for `c * (T + T^†)`.
+    /// This is therefore the sum of the stored coefficients, not the 1-norm
+    /// of the operator expanded in products of creation and annihilation
+    /// operators.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Fermions::Offset),
+    ///     (-0.5, Fermions::try_from((0, 1)).unwrap()),
+    ///     (0.25, Fermions::try_from((0, 1, 1, 0)).unwrap()),
+    /// ]);
+    ///
+    /// assert_eq!(repr.one_norm(), 0.75);
+    /// ```
+    #[must_use]
+    pub fn one_norm(&self) -> T {
+        self.iter()
+            .filter(|(_, code)| **code != Fermions::Offset)
+            .fold(T::zero(), |acc, (coeff, _)| acc + coeff.abs())
+    }
+
+    /// Largest absolute value of coefficients, excluding the offset.
+    ///
+    /// Returns `None` if there are no such terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Fermions::Offset),
+    ///     (-0.5, Fermions::try_from((0, 1)).unwrap()),
+    /// ]);
+    ///
+    /// assert_eq!(repr.max_coeff(), Some(0.5));
+    /// ```
+    #[must_use]
+    pub fn max_coeff(&self) -> Option<T> {
+        self.iter()
+            .filter(|(_, code)| **code != Fermions::Offset)
+            .map(|(coeff, _)| coeff.abs())
+            .reduce(T::max)
+    }
+
+    /// Number of terms of each order: offset, one-electron and two-electron.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Fermions::Offset),
+    ///     (-0.5, Fermions::try_from((0, 1)).unwrap()),
+    ///     (0.5, Fermions::try_from((1, 1)).unwrap()),
+    /// ]);
+    ///
+    /// assert_eq!(repr.order_histogram(), [1, 2, 0]);
+    /// ```
+    #[must_use]
+    pub fn order_histogram(&self) -> [usize; 3] {
+        let mut hist = [0; 3];
+        for (_, code) in self.iter() {
+            match code {
+                Fermions::Offset => hist[0] += 1,
+                Fermions::One {
+                    ..
+                } => hist[1] += 1,
+                Fermions::Two {
+                    ..
+                } => hist[2] += 1,
+            }
+        }
+        hist
+    }
+
+    /// Minimal number of orbitals the Hamiltonian acts on.
+    ///
+    /// This is the largest orbital index in the sum plus one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::fermions::Fermions, terms::SumRepr};
+    /// let repr = SumRepr::from([
+    ///     (3.0, Fermions::Offset),
+    ///     (-0.5, Fermions::try_from((0, 5, 2, 1)).unwrap()),
+    /// ]);
+    ///
+    /// assert_eq!(repr.num_orbitals(), 6);
+    /// ```
+    #[must_use]
+    pub fn num_orbitals(&self) -> u32 {
+        self.iter()
+            .filter_map(|(_, code)| code.max_index())
+            .max()
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Coefficient of the constant offset.
+    #[must_use]
+    pub fn offset(&self) -> T {
+        self.coeff(Fermions::Offset).copied().unwrap_or(T::zero())
+    }
+}
@@ -0,0 +1,273 @@
+//! Arithmetic of sums of terms.
+//!
+//! Sums can be added, subtracted, negated and scaled by a coefficient.  Terms
+//! with equal codes are accumulated as with [`SumRepr::add_term()`].  Terms
+//! whose coefficients cancel are kept in the sum.
+
+use std::ops::{
+    Add,
+    AddAssign,
+    Div,
+    DivAssign,
+    Mul,
+    MulAssign,
+    Neg,
+    Sub,
+    SubAssign,
+};
+
+use crate::{
+    code::Code,
+    terms::SumRepr,
+};
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Add<Output = T> + Mul<Output = T> + Copy,
+    K: Code,
+{
+    /// Linear combination of sums.
+    ///
+    /// Each sum is multiplied by the accompanying coefficient and the results
+    /// are added together.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::terms::SumRepr;
+    /// let a = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    /// let b = SumRepr::from([(1.0, 2), (1.0, 3)]);
+    ///
+    /// let repr = SumRepr::linear_combination([(2.0, &a), (-1.0, &b)]);
+    ///
+    /// assert_eq!(repr, SumRepr::from([(2.0, 1), (3.0, 2), (-1.0, 3)]));
+    /// ```
+    pub fn linear_combination<'a, I>(sums: I) -> Self
+    where
+        I: IntoIterator<Item = (T, &'a Self)>,
+        T: 'a,
+        K: 'a,
+    {
+        let mut repr = Self::new();
+        for (scalar, sum) in sums {
+            repr.extend(
+                sum.iter().map(|(&coeff, &code)| (scalar * coeff, code)),
+            );
+        }
+        repr
+    }
+}
+
+impl<T, K> Neg for SumRepr<T, K>
+where
+    T: Add<Output = T> + Neg<Output = T>,
+    K: Code,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.into_iter()
+            .map(|(coeff, code)| (-coeff, code))
+            .collect()
+    }
+}
+
+impl<T, K> AddAssign for SumRepr<T, K>
+where
+    T: Add<Output = T>,
+    K: Code,
+{
+    fn add_assign(
+        &mut self,
+        rhs: Self,
+    ) {
+        self.extend(rhs);
+    }
+}
+
+impl<T, K> AddAssign<&SumRepr<T, K>> for SumRepr<T, K>
+where
+    T: Add<Output = T> + Copy,
+    K: Code,
+{
+    fn add_assign(
+        &mut self,
+        rhs: &Self,
+    ) {
+        self.extend(rhs.iter().map(|(&coeff, &code)| (coeff, code)));
+    }
+}
+
+impl<T, K> SubAssign for SumRepr<T, K>
+where
+    T: Add<Output = T> + Neg<Output = T>,
+    K: Code,
+{
+    fn sub_assign(
+        &mut self,
+        rhs: Self,
+    ) {
+        self.extend(rhs.into_iter().map(|(coeff, code)| (-coeff, code)));
+    }
+}
+
+impl<T, K> SubAssign<&SumRepr<T, K>> for SumRepr<T, K>
+where
+    T: Add<Output = T> + Neg<Output = T> + Copy,
+    K: Code,
+{
+    fn sub_assign(
+        &mut self,
+        rhs: &Self,
+    ) {
+        self.extend(rhs.iter().map(|(&coeff, &code)| (-coeff, code)));
+    }
+}
+
+impl<T, K> Add for SumRepr<T, K>
+where
+    T: Add<Output = T>,
+    K: Code,
+{
+    type Output = Self;
+
+    fn add(
+        mut self,
+        rhs: Self,
+    ) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, K> Add<&SumRepr<T, K>> for SumRepr<T, K>
+where
+    T: Add<Output = T> + Copy,
+    K: Code,
+{
+    type Output = Self;
+
+    fn add(
+        mut self,
+        rhs: &Self,
+    ) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, K> Sub for SumRepr<T, K>
+where
+    T: Add<Output = T> + Neg<Output = T>,
+    K: Code,
+{
+    type Output = Self;
+
+    fn sub(
+        mut self,
+        rhs: Self,
+    ) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, K> Sub<&SumRepr<T, K>> for SumRepr<T, K>
+where
+    T: Add<Output = T> + Neg<Output = T> + Copy,
+    K: Code,
+{
+    type Output = Self;
+
+    fn sub(
+        mut self,
+        rhs: &Self,
+    ) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, K> MulAssign<T> for SumRepr<T, K>
+where
+    T: MulAssign + Copy,
+    K: Code,
+{
+    fn mul_assign(
+        &mut self,
+        rhs: T,
+    ) {
+        for (coeff, _) in self.iter_mut() {
+            *coeff *= rhs;
+        }
+    }
+}
+
+impl<T, K> DivAssign<T> for SumRepr<T, K>
+where
+    T: DivAssign + Copy,
+    K: Code,
+{
+    fn div_assign(
+        &mut self,
+        rhs: T,
+    ) {
+        for (coeff, _) in self.iter_mut() {
+            *coeff /= rhs;
+        }
+    }
+}
+
+impl<T, K> Mul<T> for SumRepr<T, K>
+where
+    T: MulAssign + Copy,
+    K: Code,
+{
+    type Output = Self;
+
+    fn mul(
+        mut self,
+        rhs: T,
+    ) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<T, K> Div<T> for SumRepr<T, K>
+where
+    T: DivAssign + Copy,
+    K: Code,
+{
+    type Output = Self;
+
+    fn div(
+        mut self,
+        rhs: T,
+    ) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+macro_rules! impl_scalar_mul {
+    ($($t:ty)*) => {
+        $(
+            impl<K> Mul<SumRepr<$t, K>> for $t
+            where
+                K: Code,
+            {
+                type Output = SumRepr<$t, K>;
+
+                fn mul(
+                    self,
+                    rhs: SumRepr<$t, K>,
+                ) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_mul!(f32 f64);
@@ -0,0 +1,309 @@
+//! Columnar storage of sums of Pauli strings.
+
+use std::ops::Add;
+
+use crate::{
+    code::qubits::Paulis,
+    terms::{
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+/// Number of terms accumulated in a single batch by [`Extend`].
+const BATCH_SIZE: usize = 1 << 20;
+
+/// Weighted sum of Pauli strings, stored in two parallel arrays.
+///
+/// Codes are kept as a sorted array of `u128` indices (see
+/// [`Paulis::index()`]), and coefficients in an array of the same length.
+/// This takes 16 bytes per code plus the size of the coefficient, much less
+/// than a [`SumRepr`], at the cost of slower insertion.  Lookup is by binary
+/// search.
+///
+/// Terms are added in batches: each batch is sorted, accumulated and then
+/// merged with the stored terms in a single pass.  Iteration runs in the
+/// increasing order of codes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::{code::qubits::{Paulis, Sigma}, terms::PackedSum};
+/// let mut repr = PackedSum::new();
+/// repr.extend([
+///     (0.5, Paulis::with_ops([Sigma::Z])),
+///     (1.0, Paulis::identity()),
+///     (0.5, Paulis::with_ops([Sigma::Z])),
+/// ]);
+///
+/// assert_eq!(repr.len(), 2);
+/// assert_eq!(repr.coeff(Paulis::with_ops([Sigma::Z])), Some(&1.0));
+/// assert_eq!(repr.coeff(Paulis::with_ops([Sigma::X])), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackedSum<T> {
+    codes:  Vec<u128>,
+    coeffs: Vec<T>,
+}
+
+impl<T> PackedSum<T> {
+    /// Create new, empty sum
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            codes:  Vec::new(),
+            coeffs: Vec::new(),
+        }
+    }
+
+    /// Creates an empty sum with at least the specified capacity.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            codes:  Vec::with_capacity(capacity),
+            coeffs: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of terms in the sum.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Shrink the capacity of the underlying arrays as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.codes.shrink_to_fit();
+        self.coeffs.shrink_to_fit();
+    }
+
+    /// Returns coefficient in the sum for a given code.
+    #[must_use]
+    pub fn coeff(
+        &self,
+        code: Paulis,
+    ) -> Option<&T> {
+        self.codes
+            .binary_search(&code.index())
+            .ok()
+            .map(|i| &self.coeffs[i])
+    }
+
+    /// Iterate over terms in the increasing order of codes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::PackedSum};
+    /// let repr = PackedSum::from_iter([
+    ///     (0.5, Paulis::with_ops([Sigma::Z])),
+    ///     (1.0, Paulis::identity()),
+    /// ]);
+    ///
+    /// let terms: Vec<_> = repr.iter().collect();
+    /// assert_eq!(
+    ///     terms,
+    ///     &[
+    ///         (&1.0, Paulis::identity()),
+    ///         (&0.5, Paulis::with_ops([Sigma::Z]))
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&T, Paulis)> {
+        self.coeffs
+            .iter()
+            .zip(self.codes.iter().map(|&code| Paulis::from(code)))
+    }
+
+    /// Iterate over terms, allow mutable access to coefficients.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&mut T, Paulis)> {
+        self.coeffs
+            .iter_mut()
+            .zip(self.codes.iter().map(|&code| Paulis::from(code)))
+    }
+}
+
+impl<T> PackedSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    /// Add a batch of terms to the sum.
+    ///
+    /// The batch is sorted and terms with equal codes are accumulated.  Terms
+    /// already present in the sum are updated in place; the remaining ones
+    /// are merged into the arrays starting from the back, so that no
+    /// additional copy of the stored terms is made.
+    fn merge(
+        &mut self,
+        mut batch: Vec<(u128, T)>,
+    ) {
+        batch.sort_unstable_by_key(|&(code, _)| code);
+        batch.dedup_by(|(code, coeff), (prev_code, prev_coeff)| {
+            if code == prev_code {
+                *prev_coeff = *prev_coeff + *coeff;
+                true
+            } else {
+                false
+            }
+        });
+
+        batch.retain(|&(code, coeff)| {
+            if let Ok(i) = self.codes.binary_search(&code) {
+                self.coeffs[i] = self.coeffs[i] + coeff;
+                false
+            } else {
+                true
+            }
+        });
+        let Some(&(_, fill)) = batch.first() else {
+            return;
+        };
+
+        let mut i = self.len();
+        let mut j = batch.len();
+        self.codes.resize(i + j, 0);
+        self.coeffs.resize(i + j, fill);
+        while j > 0 {
+            let (code, coeff) = batch[j - 1];
+            let k = i + j - 1;
+            if i > 0 && self.codes[i - 1] > code {
+                self.codes[k] = self.codes[i - 1];
+                self.coeffs[k] = self.coeffs[i - 1];
+                i -= 1;
+            } else {
+                self.codes[k] = code;
+                self.coeffs[k] = coeff;
+                j -= 1;
+            }
+        }
+    }
+}
+
+impl<T> Extend<(T, Paulis)> for PackedSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = (T, Paulis)>,
+    {
+        let mut iter = iter.into_iter();
+        loop {
+            let batch: Vec<_> = iter
+                .by_ref()
+                .take(BATCH_SIZE)
+                .map(|(coeff, code)| (code.index(), coeff))
+                .collect();
+            if batch.is_empty() {
+                break;
+            }
+            self.merge(batch);
+        }
+    }
+}
+
+/// Iterator over terms of [`PackedSum`] in the increasing order of codes.
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    codes:  std::vec::IntoIter<u128>,
+    coeffs: std::vec::IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (T, Paulis);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.coeffs.next()?, Paulis::from(self.codes.next()?)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.codes.size_hint()
+    }
+}
+
+impl<T> IntoIterator for PackedSum<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = (T, Paulis);
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            codes:  self.codes.into_iter(),
+            coeffs: self.coeffs.into_iter(),
+        }
+    }
+}
+
+impl<T> FromIterator<(T, Paulis)> for PackedSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = (T, Paulis)>>(iter: I) -> Self {
+        let mut repr = PackedSum::new();
+        repr.extend(iter);
+        repr
+    }
+}
+
+impl<T> From<SumRepr<T, Paulis>> for PackedSum<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn from(value: SumRepr<T, Paulis>) -> Self {
+        let mut repr = Self::with_capacity(value.len());
+        repr.merge(
+            value
+                .into_iter()
+                .map(|(coeff, code)| (code.index(), coeff))
+                .collect(),
+        );
+        repr
+    }
+}
+
+impl<T> From<PackedSum<T>> for SumRepr<T, Paulis>
+where
+    T: Add<Output = T>,
+{
+    fn from(value: PackedSum<T>) -> Self {
+        let mut repr = Self::with_capacity(value.len());
+        for (code, coeff) in value.codes.into_iter().zip(value.coeffs) {
+            repr.update(Paulis::from(code), coeff);
+        }
+        repr
+    }
+}
+
+impl<T> Terms<(T, Paulis)> for PackedSum<T> {
+    type Error = Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, Paulis)>,
+    ) -> Result<(), Self::Error> {
+        repr.extend(self);
+        Ok(())
+    }
+}
+
+impl<T> Terms<(T, Paulis)> for &PackedSum<T>
+where
+    T: Copy,
+{
+    type Error = Error;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(T, Paulis)>,
+    ) -> Result<(), Self::Error> {
+        repr.extend(self.iter().map(|(&coeff, code)| (coeff, code)));
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Removal of small terms.
+
+use num::Float;
+
+use crate::{
+    code::Code,
+    terms::SumRepr,
+};
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Float,
+    K: Code,
+{
+    /// Remove terms with absolute values of coefficients less than
+    /// `threshold`.
+    ///
+    /// Returns the removed terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let mut repr = SumRepr::from([
+    ///     (1.0, Paulis::identity()),
+    ///     (-1e-14, Paulis::with_ops([Sigma::X])),
+    ///     (0.25, Paulis::with_ops([Sigma::Z])),
+    /// ]);
+    ///
+    /// let dropped = repr.prune(1e-12);
+    ///
+    /// assert_eq!(repr.len(), 2);
+    /// assert_eq!(dropped.len(), 1);
+    /// assert_eq!(dropped.coeff(Paulis::with_ops([Sigma::X])), Some(&-1e-14));
+    /// ```
+    pub fn prune(
+        &mut self,
+        threshold: T,
+    ) -> Self {
+        let dropped: Vec<_> = self
+            .iter()
+            .filter(|(coeff, _)| coeff.abs() < threshold)
+            .map(|(_, &code)| code)
+            .collect();
+        self.remove_codes(dropped)
+    }
+
+    /// Remove the smallest terms, so that the sum of absolute values of
+    /// removed coefficients does not exceed `budget`.
+    ///
+    /// By the triangle inequality, the spectral norm of the removed part of
+    /// the Hamiltonian is then bounded by `budget` as well.  Terms are removed
+    /// in the order of increasing absolute values of coefficients.  Terms with
+    /// equal absolute values are removed all together or not at all, hence
+    /// the result does not depend on the order of iteration.  Terms with
+    /// coefficients that are NaN are never removed.
+    ///
+    /// Returns the removed terms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use f2q::{code::qubits::{Paulis, Sigma}, terms::SumRepr};
+    /// let mut repr = SumRepr::from([
+    ///     (1.0, Paulis::identity()),
+    ///     (0.01, Paulis::with_ops([Sigma::X])),
+    ///     (-0.02, Paulis::with_ops([Sigma::Y])),
+    ///     (0.03, Paulis::with_ops([Sigma::Z])),
+    /// ]);
+    ///
+    /// let dropped = repr.prune_by_norm(0.04);
+    ///
+    /// assert_eq!(repr.len(), 2);
+    /// assert_eq!(dropped.len(), 2);
+    /// assert_eq!(dropped.one_norm(), 0.03);
+    /// ```
+    pub fn prune_by_norm(
+        &mut self,
+        budget: T,
+    ) -> Self {
+        let mut terms: Vec<_> = self
+            .iter()
+            .map(|(coeff, &code)| (coeff.abs(), code))
+            .filter(|(abs, _)| !abs.is_nan())
+            .collect();
+        terms.sort_by(|(x, _), (y, _)| {
+            x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut norm = T::zero();
+        let mut num_dropped = 0;
+        while num_dropped < terms.len() {
+            let abs = terms[num_dropped].0;
+            let num_equal = terms[num_dropped..]
+                .iter()
+                .take_while(|(x, _)| *x == abs)
+                .count()
+                .max(1);
+            let chunk_norm = terms[num_dropped..num_dropped + num_equal]
+                .iter()
+                .fold(T::zero(), |acc, (x, _)| acc + *x);
+            if norm + chunk_norm <= budget {
+                norm = norm + chunk_norm;
+                num_dropped += num_equal;
+            } else {
+                break;
+            }
+        }
+
+        self.remove_codes(terms[..num_dropped].iter().map(|&(_, code)| code))
+    }
+
+    fn remove_codes(
+        &mut self,
+        codes: impl IntoIterator<Item = K>,
+    ) -> Self {
+        let mut dropped = Self::new();
+        for code in codes {
+            if let Some(coeff) = self.remove(code) {
+                dropped.update(code, coeff);
+            }
+        }
+        dropped
+    }
+}
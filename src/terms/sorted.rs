@@ -0,0 +1,103 @@
+//! Terms in a deterministic order.
+
+use num::Float;
+
+use crate::{
+    code::Code,
+    terms::SumRepr,
+};
+
+/// Order of terms of a sum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Increasing order of codes
+    #[default]
+    Code,
+    /// Decreasing absolute values of coefficients, ties resolved by the order
+    /// of codes
+    Magnitude,
+}
+
+/// Sorted view of a sum of terms.
+///
+/// Serializes the same way as [`SumRepr`], with terms in the chosen order.
+///
+/// # Examples
+///
+/// ```rust
+/// # use f2q::terms::{SortOrder, SumRepr};
+/// let repr = SumRepr::from([(0.1, 3), (-0.5, 2), (0.2, 1)]);
+///
+/// let sorted = repr.sorted(SortOrder::Code);
+/// let codes: Vec<_> = sorted.iter().map(|(_, &code)| code).collect();
+/// assert_eq!(codes, &[1, 2, 3]);
+///
+/// let sorted = repr.sorted(SortOrder::Magnitude);
+/// let codes: Vec<_> = sorted.iter().map(|(_, &code)| code).collect();
+/// assert_eq!(codes, &[2, 1, 3]);
+/// ```
+#[derive(Debug)]
+pub struct Sorted<'a, T, K> {
+    terms: Vec<(&'a T, &'a K)>,
+}
+
+impl<'a, T, K> Sorted<'a, T, K> {
+    /// Number of terms.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over terms in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a T, &'a K)> + '_ {
+        self.terms.iter().copied()
+    }
+}
+
+impl<'a, T, K> Sorted<'a, T, K>
+where
+    K: Code + Ord,
+{
+    pub(crate) fn by_code(repr: &'a SumRepr<T, K>) -> Self {
+        let mut terms: Vec<_> = repr.iter().collect();
+        terms.sort_unstable_by_key(|&(_, code)| code);
+        Self {
+            terms,
+        }
+    }
+}
+
+impl<T, K> SumRepr<T, K>
+where
+    T: Float,
+    K: Code + Ord,
+{
+    /// View the terms sorted in the given order.
+    ///
+    /// Coefficients that are NaN are placed last, if sorted by magnitude.
+    #[must_use]
+    pub fn sorted(
+        &self,
+        order: SortOrder,
+    ) -> Sorted<'_, T, K> {
+        let mut sorted = Sorted::by_code(self);
+        if order == SortOrder::Magnitude {
+            // Stable sort keeps the order of codes for equal magnitudes
+            sorted.terms.sort_by(|(x, _), (y, _)| {
+                let (x, y) = (x.abs(), y.abs());
+                match (x.is_nan(), y.is_nan()) {
+                    (false, false) => {
+                        y.partial_cmp(&x).expect("numbers are not NaN")
+                    }
+                    (x_nan, y_nan) => x_nan.cmp(&y_nan),
+                }
+            });
+        }
+        sorted
+    }
+}
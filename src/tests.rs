@@ -1,5 +1,18 @@
+mod circuit;
+mod eigen;
+mod fcidump;
 mod fermions;
+mod integrals;
 mod jordan_wigner;
+mod lcu;
 mod math;
+mod matrix;
+mod measure;
+mod metrics;
+mod openfermion;
+mod qiskit;
 mod qubit;
+mod rotation;
+mod sample;
+mod stream;
 mod terms;
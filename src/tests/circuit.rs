@@ -0,0 +1,261 @@
+use num::Complex;
+
+use crate::{
+    circuit::{
+        Circuit,
+        Gate,
+        QasmVersion,
+        Trotter,
+        TrotterOrder,
+    },
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    matrix::{
+        Coo,
+        Csr,
+    },
+    terms::SumRepr,
+    Error,
+};
+
+type C64 = Complex<f64>;
+
+fn simulate(
+    circ: &Circuit,
+    state: &mut [C64],
+) {
+    let i = C64::i();
+    let h = std::f64::consts::FRAC_1_SQRT_2;
+    for gate in circ.gates() {
+        match *gate {
+            Gate::H(q) => {
+                let mask = 1 << q;
+                for b in (0..state.len()).filter(|b| b & mask == 0) {
+                    let (x, y) = (state[b], state[b | mask]);
+                    state[b] = (x + y) * h;
+                    state[b | mask] = (x - y) * h;
+                }
+            }
+            Gate::S(q) | Gate::Sdg(q) => {
+                let phase = if matches!(gate, Gate::S(_)) { i } else { -i };
+                for (b, amp) in state.iter_mut().enumerate() {
+                    if b & (1 << q) != 0 {
+                        *amp *= phase;
+                    }
+                }
+            }
+            Gate::Rz(phi, q) => {
+                for (b, amp) in state.iter_mut().enumerate() {
+                    let sign = if b & (1 << q) == 0 { -1.0 } else { 1.0 };
+                    *amp *= (i * sign * phi / 2.0).exp();
+                }
+            }
+            Gate::Cx(c, t) => {
+                for b in 0..state.len() {
+                    if b & (1 << c) != 0 && b & (1 << t) == 0 {
+                        state.swap(b, b | (1 << t));
+                    }
+                }
+            }
+            Gate::Measure(_) => unreachable!("measurements are not simulated"),
+        }
+    }
+}
+
+fn random_state(dim: usize) -> Vec<C64> {
+    (0..dim)
+        .map(|_| C64::new(rand::random::<f64>() - 0.5, rand::random::<f64>()))
+        .collect()
+}
+
+fn distance(
+    x: &[C64],
+    y: &[C64],
+) -> f64 {
+    x.iter()
+        .zip(y)
+        .map(|(a, b)| (a - b).norm())
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn pauli_rotation_01() {
+    let num_qubits = 4;
+    let dim = 1 << num_qubits;
+    let codes = [
+        Paulis::with_ops([Sigma::X]),
+        Paulis::with_ops([Sigma::I, Sigma::Y]),
+        Paulis::with_ops([Sigma::Z, Sigma::I, Sigma::Z]),
+        Paulis::with_ops([Sigma::X, Sigma::Y, Sigma::Z, Sigma::Y]),
+        Paulis::with_ops([Sigma::Y, Sigma::I, Sigma::I, Sigma::X]),
+    ];
+    let angle = 0.377;
+
+    for code in codes {
+        let mut circ = Circuit::new(num_qubits);
+        circ.add_pauli_rotation(angle, code);
+
+        let psi = random_state(dim);
+        let mut phi = psi.clone();
+        simulate(&circ, &mut phi);
+
+        // exp(-i θ P) = cos θ - i sin θ P
+        let mtx = Csr::from(
+            Coo::from_paulis(&SumRepr::from([(1.0, code)]), 4).unwrap(),
+        );
+        let mut expected = vec![C64::default(); dim];
+        mtx.mul_vec(&psi, &mut expected);
+        for (e, p) in expected.iter_mut().zip(&psi) {
+            *e = p * angle.cos() - C64::i() * angle.sin() * *e;
+        }
+
+        assert!(distance(&phi, &expected) < 1e-12, "{code}");
+    }
+}
+
+#[test]
+fn pauli_rotation_identity() {
+    let mut circ = Circuit::new(2);
+    circ.add_pauli_rotation(1.0, Paulis::identity());
+    assert!(circ.gates().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "qubit index out of bounds")]
+fn pauli_rotation_out_of_bounds() {
+    let mut circ = Circuit::new(2);
+    circ.add_pauli_rotation(
+        1.0,
+        Paulis::with_ops([Sigma::I, Sigma::I, Sigma::X]),
+    );
+}
+
+#[test]
+fn trotter_commuting() {
+    // Commuting terms: Trotter formula is exact
+    let repr = SumRepr::from([
+        (0.3, Paulis::with_ops([Sigma::Z])),
+        (-0.7, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+        (0.2, Paulis::with_ops([Sigma::I, Sigma::Z, Sigma::Z])),
+    ]);
+    let time = 1.3;
+    let circ = Trotter::new(time, 1).circuit(&repr).unwrap();
+    assert_eq!(circ.num_qubits(), 3);
+    assert_eq!(circ.num_cx(), 4);
+
+    let psi = random_state(8);
+    let mut phi = psi.clone();
+    simulate(&circ, &mut phi);
+
+    let expected: Vec<_> = psi
+        .iter()
+        .enumerate()
+        .map(|(b, amp)| {
+            let z = |q: usize| if b & (1 << q) == 0 { 1.0 } else { -1.0 };
+            let energy = 0.3 * z(0) - 0.7 * z(0) * z(1) + 0.2 * z(1) * z(2);
+            amp * (-C64::i() * energy * time).exp()
+        })
+        .collect();
+    assert!(distance(&phi, &expected) < 1e-12);
+}
+
+#[test]
+fn trotter_order_convergence() {
+    let repr = SumRepr::from([
+        (1.0, Paulis::identity()),
+        (0.5, Paulis::with_ops([Sigma::X])),
+        (0.8, Paulis::with_ops([Sigma::Z])),
+    ]);
+    let time = 1.0;
+    let psi = random_state(2);
+
+    // Exact evolution up to the global phase of the identity term
+    let lambda = (0.5_f64.powi(2) + 0.8_f64.powi(2)).sqrt();
+    let mtx = Csr::from(
+        Coo::from_paulis(
+            &SumRepr::from([
+                (0.5, Paulis::with_ops([Sigma::X])),
+                (0.8, Paulis::with_ops([Sigma::Z])),
+            ]),
+            1,
+        )
+        .unwrap(),
+    );
+    let mut hpsi = vec![C64::default(); 2];
+    mtx.mul_vec(&psi, &mut hpsi);
+    let exact: Vec<_> = psi
+        .iter()
+        .zip(&hpsi)
+        .map(|(p, hp)| {
+            p * (lambda * time).cos()
+                - C64::i() * (lambda * time).sin() / lambda * hp
+        })
+        .collect();
+
+    let error = |order, num_steps| {
+        let circ = Trotter::new(time, num_steps)
+            .order(order)
+            .circuit(&repr)
+            .unwrap();
+        let mut phi = psi.clone();
+        simulate(&circ, &mut phi);
+        distance(&phi, &exact)
+    };
+
+    let ratio = error(TrotterOrder::First, 10) / error(TrotterOrder::First, 20);
+    assert!((ratio - 2.0).abs() < 0.2, "{ratio}");
+    let ratio =
+        error(TrotterOrder::Second, 10) / error(TrotterOrder::Second, 20);
+    assert!((ratio - 4.0).abs() < 0.4, "{ratio}");
+}
+
+#[test]
+fn trotter_invalid() {
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([Sigma::I, Sigma::X]))]);
+    assert!(matches!(
+        Trotter::new(1.0, 0).circuit(&repr),
+        Err(Error::Circuit { .. })
+    ));
+    assert!(matches!(
+        Trotter::new(f64::NAN, 1).circuit(&repr),
+        Err(Error::Circuit { .. })
+    ));
+    assert!(matches!(
+        Trotter::new(1.0, 1).num_qubits(Some(1)).circuit(&repr),
+        Err(Error::Circuit { .. })
+    ));
+    let circ = Trotter::new(1.0, 1)
+        .num_qubits(Some(5))
+        .circuit(&repr)
+        .unwrap();
+    assert_eq!(circ.num_qubits(), 5);
+}
+
+#[test]
+fn write_qasm_01() {
+    let repr = SumRepr::from([(-0.25, Paulis::with_ops([Sigma::Y, Sigma::X]))]);
+    let circ = Trotter::new(2.0, 1).circuit(&repr).unwrap();
+
+    let mut buf = Vec::new();
+    circ.write_qasm(&mut buf, QasmVersion::V2).unwrap();
+    let qasm = String::from_utf8(buf).unwrap();
+
+    assert_eq!(
+        qasm,
+        "OPENQASM 2.0;
+include \"qelib1.inc\";
+qreg q[2];
+sdg q[0];
+h q[0];
+h q[1];
+cx q[0], q[1];
+rz(-1.0) q[1];
+cx q[0], q[1];
+h q[0];
+s q[0];
+h q[1];
+"
+    );
+}
@@ -0,0 +1,124 @@
+use num::Complex;
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    eigen::{
+        tridiagonal_eigen,
+        Lanczos,
+    },
+    matrix::{
+        Coo,
+        Csr,
+        Sector,
+    },
+    terms::{
+        FermiSum,
+        SumRepr,
+    },
+};
+
+fn hubbard_dimer(
+    t: f64,
+    u: f64,
+) -> FermiSum {
+    let mut repr = SumRepr::new();
+    repr.add_term(Fermions::try_from((0, 2)).unwrap(), -t);
+    repr.add_term(Fermions::try_from((1, 3)).unwrap(), -t);
+    // Self-adjoint terms are counted twice
+    repr.add_term(Fermions::try_from((0, 1, 1, 0)).unwrap(), u / 2.0);
+    repr.add_term(Fermions::try_from((2, 3, 3, 2)).unwrap(), u / 2.0);
+    repr
+}
+
+#[test]
+fn tridiagonal_eigen_01() {
+    let (values, vectors) = tridiagonal_eigen(&[2.0, 2.0], &[-1.0]).unwrap();
+
+    assert!((values[0] - 1.0).abs() < 1e-12);
+    assert!((values[1] - 3.0).abs() < 1e-12);
+    assert!((vectors[0][0] - vectors[0][1]).abs() < 1e-12);
+    assert!((vectors[1][0] + vectors[1][1]).abs() < 1e-12);
+}
+
+#[test]
+fn tridiagonal_eigen_02() {
+    // Path graph Laplacian-like matrix: eigenvalues 2 - 2 cos(k pi / (n+1))
+    let n = 12;
+    let (values, _) =
+        tridiagonal_eigen(&vec![2.0; n], &vec![-1.0; n - 1]).unwrap();
+
+    for (k, value) in values.iter().enumerate() {
+        let expected = 2.0
+            - 2.0
+                * (std::f64::consts::PI * (k + 1) as f64 / (n + 1) as f64)
+                    .cos();
+        assert!((value - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn lanczos_diagonal_01() {
+    use Sigma::*;
+
+    let repr = SumRepr::from([
+        (1.0, Paulis::with_ops([Z])),
+        (0.5, Paulis::with_ops([I, Z])),
+        (0.25, Paulis::with_ops([I, I, Z])),
+    ]);
+    let mtx = Csr::from(Coo::from_paulis(&repr, 3).unwrap());
+    let eigen = Lanczos::new(3).eigenvectors(true).solve(&mtx).unwrap();
+
+    let expected = [-1.75, -1.25, -0.75];
+    for (value, expected) in eigen.values.iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-10);
+    }
+
+    // Ground state is |111>
+    let ground = &eigen.vectors.unwrap()[0];
+    assert!((ground[0b111].norm() - 1.0).abs() < 1e-10);
+}
+
+#[test]
+fn lanczos_hubbard_dimer_01() {
+    let (t, u) = (1.0, 4.0);
+    let repr = hubbard_dimer(t, u);
+
+    let sector = Sector::new(4, 2, Some(0)).unwrap();
+    assert_eq!(sector.dim(), 4);
+
+    let mtx = Csr::from(Coo::from_fermions_in_sector(&repr, &sector).unwrap());
+    let eigen = Lanczos::new(1).eigenvectors(true).solve(&mtx).unwrap();
+
+    let expected = u / 2.0 - (u * u / 4.0 + 4.0 * t * t).sqrt();
+    assert!((eigen.values[0] - expected).abs() < 1e-10);
+
+    // Check residual of the eigenvector
+    let x = &eigen.vectors.unwrap()[0];
+    let mut y = vec![Complex::new(0.0, 0.0); x.len()];
+    mtx.mul_vec(x, &mut y);
+    for (y_i, x_i) in y.iter().zip(x) {
+        assert!((y_i - x_i * eigen.values[0]).norm() < 1e-8);
+    }
+}
+
+#[test]
+fn sector_01() {
+    let sector = Sector::new(6, 3, None).unwrap();
+    assert_eq!(sector.dim(), 20);
+    assert!(sector.states().windows(2).all(|w| w[0] < w[1]));
+
+    let sector = Sector::new(6, 3, Some(1)).unwrap();
+    assert_eq!(sector.dim(), 9);
+
+    let sector = Sector::new(4, 0, None).unwrap();
+    assert_eq!(sector.states(), &[0]);
+
+    let sector = Sector::new(2, 3, None).unwrap();
+    assert_eq!(sector.dim(), 0);
+}
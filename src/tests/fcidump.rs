@@ -0,0 +1,356 @@
+use rand::Rng;
+
+use crate::{
+    code::fermions::Fermions,
+    eigen::Lanczos,
+    fcidump::{
+        Fcidump,
+        Header,
+    },
+    matrix::{
+        Coo,
+        Csr,
+        Sector,
+    },
+    terms::{
+        FermiSum,
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+const H2_STO3G: &str = " &FCI NORB=   2,NELEC= 2,MS2=0,
+  ORBSYM=1,5,
+  ISYM=1,
+ &END
+  0.6744887663568382     1    1    1    1
+  0.1812104620151        2    1    2    1
+  0.6634114006126878     2    2    1    1
+  0.6973979494693556     2    2    2    2
+ -1.2524635735648981     1    1    0    0
+ -0.4759487172683919     2    2    0    0
+  0.7137539936876182     0    0    0    0
+";
+
+fn to_fermisum(fcidump: &Fcidump) -> FermiSum {
+    let mut repr = SumRepr::new();
+    fcidump.add_to(&mut repr).unwrap();
+    repr
+}
+
+#[test]
+fn fcidump_parse_header() {
+    let fcidump: Fcidump = H2_STO3G.parse().unwrap();
+
+    assert_eq!(
+        fcidump.header,
+        Header {
+            norb:   2,
+            nelec:  2,
+            ms2:    0,
+            orbsym: vec![1, 5],
+            isym:   1,
+        }
+    );
+    assert_eq!(fcidump.core_energy, 0.7137539936876182);
+    assert_eq!(
+        fcidump.one_electron,
+        &[(-1.2524635735648981, (0, 0)), (-0.4759487172683919, (1, 1))]
+    );
+    assert_eq!(
+        fcidump.two_electron,
+        &[
+            (0.6744887663568382, (0, 0, 0, 0)),
+            (0.6634114006126878, (0, 0, 1, 1)),
+            (0.1812104620151, (0, 1, 0, 1)),
+            (0.6973979494693556, (1, 1, 1, 1)),
+        ]
+    );
+}
+
+#[test]
+fn fcidump_parse_header_variants() {
+    let input = "&fci norb=3, nelec=2, ms2=2,
+        orbsym=1,1,1,
+        isym=1, pntgrp='C1' /
+     1.0D-01  1 2 0 0
+     2.0d+00  3 0 0 0
+
+     0.5  3 2 1 1
+    ";
+    let fcidump: Fcidump = input.parse().unwrap();
+
+    assert_eq!(fcidump.header.norb, 3);
+    assert_eq!(fcidump.header.ms2, 2);
+    assert_eq!(fcidump.header.orbsym, &[1, 1, 1]);
+    assert_eq!(fcidump.core_energy, 0.0);
+    assert_eq!(fcidump.one_electron, &[(0.1, (0, 1))]);
+    assert_eq!(fcidump.two_electron, &[(0.5, (0, 0, 1, 2))]);
+
+    let fcidump: Fcidump = "&FCI NORB=1 &END".parse().unwrap();
+    assert_eq!(fcidump.header, Header::new(1, 0));
+}
+
+#[test]
+fn fcidump_parse_errors() {
+    let cases = [
+        "",
+        "NORB=1 &END",
+        "&FCI NORB=1,",
+        "&FCI NELEC=1 &END",
+        "&FCI NORB=1,2 &END",
+        "&FCI NORB=2,ORBSYM=1 &END",
+        "&FCI NORB=1,UHF=.TRUE. &END",
+        "&FCI NORB=1 &END\n 1.0 2 1 0 0",
+        "&FCI NORB=1 &END\n 1.0 0 1 0 0",
+        "&FCI NORB=1 &END\n 1.0 1 1 1",
+        "&FCI NORB=1 &END\n 1.0x 1 1 1 1",
+    ];
+    for input in cases {
+        assert!(
+            matches!(input.parse::<Fcidump>(), Err(Error::Fcidump { .. })),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn fcidump_permutational_symmetry() {
+    let input = "&FCI NORB=3 &END
+        0.25 1 2 3 1
+        0.5  2 3 0 0
+        1.0  0 0 0 0
+    ";
+    let expected = to_fermisum(&input.parse().unwrap());
+
+    let permuted = [
+        [2, 1, 3, 1],
+        [1, 2, 1, 3],
+        [2, 1, 1, 3],
+        [3, 1, 1, 2],
+        [1, 3, 1, 2],
+        [3, 1, 2, 1],
+        [1, 3, 2, 1],
+    ];
+    for [i, j, k, l] in permuted {
+        let input = format!(
+            "&FCI NORB=3 &END
+            0.25 {i} {j} {k} {l}
+            0.5  3 2 0 0
+            1.0  0 0 0 0"
+        );
+        let fcidump: Fcidump = input.parse().unwrap();
+        assert_eq!(fcidump.two_electron, &[(0.25, (0, 1, 0, 2))]);
+        assert_eq!(to_fermisum(&fcidump), expected);
+    }
+}
+
+#[test]
+fn fcidump_hubbard_dimer() {
+    let (t, u) = (1.0, 4.0);
+    let input = format!(
+        "&FCI NORB=2,NELEC=2 &END
+        {u} 1 1 1 1
+        {u} 2 2 2 2
+        {} 1 2 0 0",
+        -t
+    );
+    let repr = to_fermisum(&input.parse().unwrap());
+
+    let mut expected = SumRepr::new();
+    expected.add_term(Fermions::Offset, 0.0);
+    // Spin down and spin up
+    expected.add_term(Fermions::try_from((0, 2)).unwrap(), -t);
+    expected.add_term(Fermions::try_from((1, 3)).unwrap(), -t);
+    expected.add_term(Fermions::try_from((0, 1, 1, 0)).unwrap(), u / 2.0);
+    expected.add_term(Fermions::try_from((2, 3, 3, 2)).unwrap(), u / 2.0);
+
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn fcidump_h2_energy() {
+    let fcidump: Fcidump = H2_STO3G.parse().unwrap();
+    let repr = to_fermisum(&fcidump);
+
+    let sector = Sector::new(4, 2, Some(0)).unwrap();
+    let mtx = Csr::from(Coo::from_fermions_in_sector(&repr, &sector).unwrap());
+    let energy = Lanczos::new(1).solve(&mtx).unwrap().values[0];
+
+    // Configuration interaction of the two closed-shell determinants
+    let h = |i: usize| fcidump.one_electron[i].0;
+    let g = |i: usize| fcidump.two_electron[i].0;
+    let e_nuc = fcidump.core_energy;
+    let e_0 = e_nuc + 2.0 * h(0) + g(0);
+    let e_1 = e_nuc + 2.0 * h(1) + g(3);
+    let k = g(2);
+    let expected =
+        0.5 * (e_0 + e_1) - (0.25 * (e_0 - e_1).powi(2) + k * k).sqrt();
+
+    assert!((energy - expected).abs() < 1e-10, "{energy} {expected}");
+    assert!((energy + 1.13725).abs() < 1e-5);
+}
+
+fn random_fcidump(norb: u32) -> Fcidump {
+    let mut rng = rand::thread_rng();
+    let mut one_electron = Vec::new();
+    let mut two_electron = Vec::new();
+    for i in 0..norb {
+        for j in i..norb {
+            one_electron.push((rng.gen_range(-1.0..1.0), (i, j)));
+        }
+    }
+    for (i, j) in one_electron.iter().map(|&(_, key)| key) {
+        for (k, l) in one_electron.iter().map(|&(_, key)| key) {
+            if (i, j) <= (k, l) {
+                two_electron.push((rng.gen_range(-1.0..1.0), (i, j, k, l)));
+            }
+        }
+    }
+
+    Fcidump {
+        header: Header::new(norb, 0),
+        core_energy: rng.gen_range(-1.0..1.0),
+        one_electron,
+        two_electron,
+    }
+}
+
+#[test]
+fn fcidump_from_terms_h2() {
+    let fcidump: Fcidump = H2_STO3G.parse().unwrap();
+    let repr = to_fermisum(&fcidump);
+
+    let result = Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).unwrap();
+    assert_eq!(result.header, Header::new(2, 0));
+
+    let result =
+        Fcidump::from_terms(&repr, fcidump.header.clone(), 1e-12).unwrap();
+    assert_eq!(result.header, fcidump.header);
+    assert_eq!(result.core_energy, fcidump.core_energy);
+    assert_eq!(result.one_electron, fcidump.one_electron);
+    assert_eq!(result.two_electron, fcidump.two_electron);
+}
+
+#[test]
+fn fcidump_from_terms_random() {
+    for norb in 1..5 {
+        let fcidump = random_fcidump(norb);
+        let repr = to_fermisum(&fcidump);
+
+        let result =
+            Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).unwrap();
+        assert_eq!(result.header.norb, norb);
+        assert_eq!(result.one_electron.len(), fcidump.one_electron.len());
+        assert_eq!(result.two_electron.len(), fcidump.two_electron.len());
+        assert!(to_fermisum(&result).approx_eq(&repr, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn fcidump_from_terms_inconsistent() {
+    let mut repr = to_fermisum(&random_fcidump(3));
+    assert!(Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).is_ok());
+
+    // Spin-dependent one-electron term
+    let code = Fermions::try_from((1, 5)).unwrap();
+    repr.add_term(code, 1e-3);
+    let err = Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).unwrap_err();
+    assert_eq!(
+        err,
+        Error::Fcidump {
+            msg: "term [1, 5] is not consistent with spatial integrals"
+                .to_string(),
+        }
+    );
+    assert!(Fcidump::from_terms(&repr, Header::new(0, 0), 1e-2).is_ok());
+    repr.add_term(code, -1e-3);
+
+    // Spin-flip term
+    repr.add_term(Fermions::try_from((0, 2)).unwrap(), 1.0);
+    repr.add_term(Fermions::try_from((0, 3)).unwrap(), 1.0);
+    assert!(Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).is_err());
+
+    // Same-spin term without the opposite-spin counterpart
+    let repr =
+        SumRepr::from([(1.0, Fermions::try_from((0, 2, 2, 0)).unwrap())]);
+    assert!(Fcidump::from_terms(&repr, Header::new(0, 0), 1e-12).is_err());
+}
+
+#[test]
+fn fcidump_from_terms_header() {
+    let repr = to_fermisum(&random_fcidump(2));
+
+    // More orbitals than needed are kept
+    let mut header = Header::new(3, 2);
+    header.ms2 = 2;
+    header.orbsym = vec![1, 2, 1];
+    header.isym = 3;
+    let result = Fcidump::from_terms(&repr, header.clone(), 1e-12).unwrap();
+    assert_eq!(result.header, header);
+
+    // Too few orbitals are extended
+    let result = Fcidump::from_terms(&repr, Header::new(1, 2), 1e-12).unwrap();
+    assert_eq!(result.header, Header::new(2, 2));
+
+    // Symmetry of a wrong number of orbitals
+    let mut header = Header::new(1, 2);
+    header.orbsym = vec![1];
+    let err = Fcidump::from_terms(&repr, header, 1e-12).unwrap_err();
+    assert_eq!(
+        err,
+        Error::Fcidump {
+            msg: "ORBSYM should have 2 entries, found: 1".to_string(),
+        }
+    );
+}
+
+#[test]
+fn fcidump_from_terms_adjoint_codes() {
+    let fcidump: Fcidump = "&FCI NORB=2 &END
+ 0.3 1 1 1 2"
+        .parse()
+        .unwrap();
+    let repr = to_fermisum(&fcidump);
+
+    // a+_0 a+_3 a_1 a_0 is the adjoint of a+_0 a+_1 a_3 a_0
+    let code = Fermions::try_from((0, 1, 3, 0)).unwrap();
+    let adjoint = Fermions::try_from((0, 3, 1, 0)).unwrap();
+    assert!(repr.coeff(code).is_some());
+    assert!(repr.coeff(adjoint).is_none());
+    let swapped: FermiSum = repr
+        .iter()
+        .map(|(&coeff, &c)| (coeff, if c == code { adjoint } else { c }))
+        .collect();
+
+    let result =
+        Fcidump::from_terms(&swapped, Header::new(0, 0), 1e-12).unwrap();
+    assert_eq!(result, fcidump);
+}
+
+#[test]
+fn fcidump_write_read() {
+    let mut fcidump = random_fcidump(4);
+    fcidump.header.nelec = 4;
+    fcidump.header.orbsym = vec![1, 2, 3, 4];
+
+    let mut buf = Vec::new();
+    fcidump.write(&mut buf).unwrap();
+    let result: Fcidump = String::from_utf8(buf).unwrap().parse().unwrap();
+
+    assert_eq!(result, fcidump);
+}
+
+#[test]
+fn fcidump_write_empty() {
+    let fcidump =
+        Fcidump::from_terms(&FermiSum::new(), Header::new(0, 0), 0.0).unwrap();
+    assert_eq!(fcidump.header.norb, 0);
+
+    let mut buf = Vec::new();
+    fcidump.write(&mut buf).unwrap();
+    let result: Fcidump = String::from_utf8(buf).unwrap().parse().unwrap();
+
+    assert_eq!(result, fcidump);
+}
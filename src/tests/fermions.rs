@@ -187,3 +187,36 @@ fn fermions_two_from_01() {
     Fermions::try_from((0, 1, 1, 1)).unwrap_err();
     Fermions::try_from((1, 2, 1, 0)).unwrap_err();
 }
+
+#[test]
+fn fermions_ord_01() {
+    let mut codes = vec![
+        Fermions::try_from((0, 1, 1, 0)).unwrap(),
+        Fermions::try_from((1, 1)).unwrap(),
+        Fermions::try_from((0, 2, 2, 0)).unwrap(),
+        Fermions::Offset,
+        Fermions::try_from((0, 3)).unwrap(),
+        Fermions::try_from((0, 2, 1, 0)).unwrap(),
+        Fermions::try_from((0, 1)).unwrap(),
+    ];
+    codes.sort();
+
+    assert_eq!(
+        codes,
+        &[
+            Fermions::Offset,
+            Fermions::try_from((0, 1)).unwrap(),
+            Fermions::try_from((0, 3)).unwrap(),
+            Fermions::try_from((1, 1)).unwrap(),
+            Fermions::try_from((0, 1, 1, 0)).unwrap(),
+            Fermions::try_from((0, 2, 1, 0)).unwrap(),
+            Fermions::try_from((0, 2, 2, 0)).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn orbital_ord_matches_index() {
+    let orbitals: Vec<_> = (0..16).map(Orbital::with_index).collect();
+    assert!(orbitals.windows(2).all(|w| w[0] < w[1]));
+}
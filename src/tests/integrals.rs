@@ -0,0 +1,377 @@
+use rand::Rng;
+
+use crate::{
+    code::fermions::{
+        Fermions,
+        Orbital,
+        Spin,
+    },
+    fcidump::Fcidump,
+    integrals::{
+        Convention,
+        Integrals,
+    },
+    terms::{
+        FermiSum,
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+/// Dense tensors in the chemists' notation, with all 8-fold symmetries.
+///
+/// Each entry overwrites all its permutations.
+fn random_tensors(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut rng = rand::thread_rng();
+    let mut h1 = vec![0.0; n * n];
+    let mut h2 = vec![0.0; n * n * n * n];
+    for p in 0..n {
+        for q in p..n {
+            let value = rng.gen_range(-1.0..1.0);
+            h1[p * n + q] = value;
+            h1[q * n + p] = value;
+        }
+    }
+    let idx =
+        |p: usize, q: usize, r: usize, s: usize| ((p * n + q) * n + r) * n + s;
+    for p in 0..n {
+        for q in 0..n {
+            for r in 0..n {
+                for s in 0..n {
+                    let value = rng.gen_range(-1.0..1.0);
+                    for (i, j, k, l) in [
+                        (p, q, r, s),
+                        (q, p, r, s),
+                        (p, q, s, r),
+                        (q, p, s, r),
+                        (r, s, p, q),
+                        (s, r, p, q),
+                        (r, s, q, p),
+                        (s, r, q, p),
+                    ] {
+                        h2[idx(i, j, k, l)] = value;
+                    }
+                }
+            }
+        }
+    }
+    (h1, h2)
+}
+
+fn to_fcidump(
+    n: usize,
+    constant: f64,
+    h1: &[f64],
+    h2: &[f64],
+) -> Fcidump {
+    let mut input = format!("&FCI NORB={n} &END\n");
+    for p in 0..n {
+        for q in 0..n {
+            for r in 0..n {
+                for s in 0..n {
+                    let value = h2[((p * n + q) * n + r) * n + s];
+                    let [i, j, k, l] = [p + 1, q + 1, r + 1, s + 1];
+                    input.push_str(&format!("{value:e} {i} {j} {k} {l}\n"));
+                }
+            }
+        }
+    }
+    for p in 0..n {
+        for q in 0..n {
+            let value = h1[p * n + q];
+            input.push_str(&format!("{value:e} {} {} 0 0\n", p + 1, q + 1));
+        }
+    }
+    input.push_str(&format!("{constant:e} 0 0 0 0\n"));
+    input.parse().unwrap()
+}
+
+#[test]
+fn integrals_chemist_fcidump() {
+    for n in 1..5 {
+        let (h1, h2) = random_tensors(n);
+        let repr = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .constant(0.5)
+                .one_electron(&h1)
+                .two_electron(&h2),
+        )
+        .unwrap();
+
+        let mut expected = SumRepr::new();
+        to_fcidump(n, 0.5, &h1, &h2).add_to(&mut expected).unwrap();
+
+        assert!(repr.approx_eq(&expected, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn integrals_physicist() {
+    for n in 1..5 {
+        let (h1, h2) = random_tensors(n);
+        let mut h2_phys = vec![0.0; h2.len()];
+        let idx = |p: usize, q: usize, r: usize, s: usize| {
+            ((p * n + q) * n + r) * n + s
+        };
+        for p in 0..n {
+            for q in 0..n {
+                for r in 0..n {
+                    for s in 0..n {
+                        h2_phys[idx(p, q, r, s)] = h2[idx(p, r, q, s)];
+                    }
+                }
+            }
+        }
+
+        let chemist = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron(&h1)
+                .two_electron(&h2),
+        )
+        .unwrap();
+        let physicist = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Physicist)
+                .one_electron(&h1)
+                .two_electron(&h2_phys),
+        )
+        .unwrap();
+
+        assert!(physicist.approx_eq(&chemist, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn integrals_hermitian_part() {
+    let h1 = [0.0, 1.0, 3.0, 0.0];
+    let repr = FermiSum::try_from(
+        Integrals::new(2, Convention::Chemist).one_electron(&h1),
+    )
+    .unwrap();
+
+    let mut expected = SumRepr::new();
+    expected.add_term(Fermions::Offset, 0.0);
+    expected.add_term(Fermions::try_from((0, 2)).unwrap(), 2.0);
+    expected.add_term(Fermions::try_from((1, 3)).unwrap(), 2.0);
+
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn integrals_hubbard_dimer() {
+    let (t, u) = (1.0, 4.0);
+    let h1 = [0.0, -t, -t, 0.0];
+    let mut h2 = [0.0; 16];
+    h2[0] = u;
+    h2[15] = u;
+    let repr = FermiSum::try_from(
+        Integrals::new(2, Convention::Physicist)
+            .one_electron(&h1)
+            .two_electron(&h2),
+    )
+    .unwrap();
+
+    let mut expected = SumRepr::new();
+    expected.add_term(Fermions::Offset, 0.0);
+    expected.add_term(Fermions::try_from((0, 2)).unwrap(), -t);
+    expected.add_term(Fermions::try_from((1, 3)).unwrap(), -t);
+    expected.add_term(Fermions::try_from((0, 1, 1, 0)).unwrap(), u / 2.0);
+    expected.add_term(Fermions::try_from((2, 3, 3, 2)).unwrap(), u / 2.0);
+
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn integrals_empty() {
+    let repr = FermiSum::try_from(
+        Integrals::new(3, Convention::Chemist).constant(1.5),
+    )
+    .unwrap();
+
+    assert_eq!(repr, SumRepr::from([(1.5, Fermions::Offset)]));
+}
+
+#[test]
+fn integrals_length_errors() {
+    let h = [0.0; 8];
+    let err = FermiSum::try_from(
+        Integrals::new(2, Convention::Chemist).one_electron(&h),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::Integrals { .. }), "{err}");
+
+    let err = FermiSum::try_from(
+        Integrals::new(2, Convention::Chemist).two_electron(&h),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::Integrals { .. }), "{err}");
+
+    let err = FermiSum::try_from(Integrals::new(u32::MAX, Convention::Chemist))
+        .unwrap_err();
+    assert!(matches!(err, Error::Integrals { .. }), "{err}");
+}
+
+/// Opposite-spin block in the chemists' notation: `(p^σ q^σ|r^τ s^τ)`,
+/// symmetric only under `p <-> q` and `r <-> s`.
+fn random_mixed_block(n: usize) -> Vec<f64> {
+    let mut rng = rand::thread_rng();
+    let mut h = vec![0.0; n * n * n * n];
+    let idx =
+        |p: usize, q: usize, r: usize, s: usize| ((p * n + q) * n + r) * n + s;
+    for p in 0..n {
+        for q in 0..n {
+            for r in 0..n {
+                for s in 0..n {
+                    let value = rng.gen_range(-1.0..1.0);
+                    for (i, j, k, l) in
+                        [(p, q, r, s), (q, p, r, s), (p, q, s, r), (q, p, s, r)]
+                    {
+                        h[idx(i, j, k, l)] = value;
+                    }
+                }
+            }
+        }
+    }
+    h
+}
+
+/// Swap indices of a tensor: `h[a, b, c, d] -> h[perm(a, b, c, d)]`.
+fn permute(
+    n: usize,
+    h: &[f64],
+    perm: impl Fn([usize; 4]) -> [usize; 4],
+) -> Vec<f64> {
+    let idx = |[p, q, r, s]: [usize; 4]| ((p * n + q) * n + r) * n + s;
+    let mut result = vec![0.0; h.len()];
+    for p in 0..n {
+        for q in 0..n {
+            for r in 0..n {
+                for s in 0..n {
+                    result[idx([p, q, r, s])] = h[idx(perm([p, q, r, s]))];
+                }
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn integrals_unrestricted_as_restricted() {
+    for n in 1..5 {
+        let (h1, h2) = random_tensors(n);
+        let restricted = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron(&h1)
+                .two_electron(&h2),
+        )
+        .unwrap();
+        let unrestricted = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron_spin(Spin::Up, &h1)
+                .one_electron_spin(Spin::Down, &h1)
+                .two_electron_spin(Spin::Up, Spin::Up, &h2)
+                .two_electron_spin(Spin::Down, Spin::Down, &h2)
+                .two_electron_spin(Spin::Up, Spin::Down, &h2),
+        )
+        .unwrap();
+
+        assert!(unrestricted.approx_eq(&restricted, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn integrals_unrestricted_one_orbital() {
+    let h_alpha = [-1.0];
+    let h_beta = [-2.0];
+    let h_aa = [3.0];
+    let h_ab = [0.5];
+    let repr = FermiSum::try_from(
+        Integrals::new(1, Convention::Chemist)
+            .one_electron_spin(Spin::Up, &h_alpha)
+            .one_electron_spin(Spin::Down, &h_beta)
+            .two_electron_spin(Spin::Up, Spin::Up, &h_aa)
+            .two_electron_spin(Spin::Up, Spin::Down, &h_ab),
+    )
+    .unwrap();
+
+    let up = Orbital::new(0, Spin::Up).index();
+    let down = Orbital::new(0, Spin::Down).index();
+    let mut expected = SumRepr::new();
+    expected.add_term(Fermions::Offset, 0.0);
+    expected.add_term(Fermions::try_from((up, up)).unwrap(), -0.5);
+    expected.add_term(Fermions::try_from((down, down)).unwrap(), -1.0);
+    expected.add_term(Fermions::try_from((down, up, up, down)).unwrap(), 0.25);
+
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn integrals_unrestricted_spin_blocks() {
+    for n in 1..5 {
+        let (h_alpha, h_aa) = random_tensors(n);
+        let (h_beta, h_bb) = random_tensors(n);
+        let h_ab = random_mixed_block(n);
+        let h_ba = permute(n, &h_ab, |[p, q, r, s]| [r, s, p, q]);
+
+        let chemist = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron_spin(Spin::Up, &h_alpha)
+                .one_electron_spin(Spin::Down, &h_beta)
+                .two_electron_spin(Spin::Up, Spin::Up, &h_aa)
+                .two_electron_spin(Spin::Down, Spin::Down, &h_bb)
+                .two_electron_spin(Spin::Up, Spin::Down, &h_ab),
+        )
+        .unwrap();
+
+        // The same Hamiltonian with spins exchanged, mapped back to orbitals
+        let exchanged = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron_spin(Spin::Up, &h_alpha)
+                .one_electron_spin(Spin::Down, &h_beta)
+                .two_electron_spin(Spin::Up, Spin::Up, &h_aa)
+                .two_electron_spin(Spin::Down, Spin::Down, &h_bb)
+                .two_electron_spin(Spin::Down, Spin::Up, &h_ba),
+        )
+        .unwrap();
+        assert!(exchanged.approx_eq(&chemist, 1e-12, 0.0));
+
+        // <p^σ q^τ|r^σ s^τ> = (p^σ r^σ|q^τ s^τ)
+        let to_physicist =
+            |h: &[f64]| permute(n, h, |[p, q, r, s]| [p, r, q, s]);
+        let physicist = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Physicist)
+                .one_electron_spin(Spin::Up, &h_alpha)
+                .one_electron_spin(Spin::Down, &h_beta)
+                .two_electron_spin(Spin::Up, Spin::Up, &to_physicist(&h_aa))
+                .two_electron_spin(Spin::Down, Spin::Down, &to_physicist(&h_bb))
+                .two_electron_spin(Spin::Up, Spin::Down, &to_physicist(&h_ab)),
+        )
+        .unwrap();
+        assert!(physicist.approx_eq(&chemist, 1e-12, 0.0));
+
+        // Different blocks give a different Hamiltonian
+        let swapped = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .one_electron_spin(Spin::Up, &h_beta)
+                .one_electron_spin(Spin::Down, &h_alpha)
+                .two_electron_spin(Spin::Up, Spin::Up, &h_aa)
+                .two_electron_spin(Spin::Down, Spin::Down, &h_bb)
+                .two_electron_spin(Spin::Up, Spin::Down, &h_ab),
+        )
+        .unwrap();
+        assert!(!swapped.approx_eq(&chemist, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn integrals_unrestricted_length_errors() {
+    let h1 = [0.0; 4];
+    let h2 = [0.0; 8];
+    let err = FermiSum::try_from(
+        Integrals::new(2, Convention::Chemist)
+            .one_electron(&h1)
+            .two_electron_spin(Spin::Down, Spin::Up, &h2),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::Integrals { .. }), "{err}");
+}
@@ -1,18 +1,33 @@
-use num::Float;
+use std::ops::{
+    Mul,
+    Neg,
+};
+
+use num::{
+    Float,
+    One,
+};
 
 use crate::{
     code::{
-        fermions::Fermions,
+        fermions::{
+            An,
+            Cr,
+            Fermions,
+            Orbital,
+        },
         qubits::{
             Paulis,
             Sigma,
         },
     },
     map::JordanWigner,
+    math::Root4,
     terms::{
         SumRepr,
         Terms,
     },
+    Error,
 };
 
 fn jw_get_result<T: Float>(repr: &SumRepr<T, Fermions>) -> Vec<(T, Paulis)> {
@@ -21,7 +36,7 @@ fn jw_get_result<T: Float>(repr: &SumRepr<T, Fermions>) -> Vec<(T, Paulis)> {
     jw_map.add_to(&mut pauli_repr).unwrap();
     let mut result = vec![];
     pauli_repr.add_to(&mut result).unwrap();
-    result.sort_by(|(_, pauli_a), (_, pauli_b)| pauli_a.cmp(pauli_b));
+    result.sort_by_key(|&(_, pauli)| pauli);
     result
 }
 
@@ -344,3 +359,586 @@ fn jw_two_elec_06() {
         ],
     );
 }
+
+fn jw_test_repr(num_orbitals: u32) -> SumRepr<f64, Fermions> {
+    let mut repr = SumRepr::new();
+    let mut coeff = 0.0;
+    let mut next_coeff = || {
+        coeff += 0.125;
+        coeff
+    };
+
+    repr.add_term(Fermions::Offset, next_coeff());
+    for p in 0..num_orbitals {
+        for q in p..num_orbitals {
+            repr.add_term(Fermions::try_from((p, q)).unwrap(), next_coeff());
+        }
+    }
+    for p in 0..num_orbitals {
+        for q in p + 1..num_orbitals {
+            for r in 0..num_orbitals {
+                for s in p..r {
+                    repr.add_term(
+                        Fermions::try_from((p, q, r, s)).unwrap(),
+                        next_coeff(),
+                    );
+                }
+            }
+        }
+    }
+    repr
+}
+
+#[test]
+fn jw_par_add_to_01() {
+    let repr = jw_test_repr(6);
+
+    let mut expected = SumRepr::new();
+    JordanWigner::new(&repr).add_to(&mut expected).unwrap();
+    let mut result = SumRepr::new();
+    JordanWigner::new(&repr).par_add_to(&mut result).unwrap();
+
+    // Coefficients are dyadic rationals, so summation order does not matter.
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn jw_par_add_to_empty() {
+    let repr = SumRepr::<f64, Fermions>::new();
+
+    let mut result = SumRepr::new();
+    JordanWigner::new(&repr).par_add_to(&mut result).unwrap();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn jw_par_add_to_index_error() {
+    let repr = SumRepr::from([(1.0, Fermions::try_from((0, 64)).unwrap())]);
+
+    let mut result = SumRepr::new();
+    assert!(JordanWigner::new(&repr).par_add_to(&mut result).is_err());
+}
+
+// Reference implementation by expansion into products of Pauli strings.
+
+/// A complex number that can only be either real or imaginary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReIm<T> {
+    Zero,
+    Re(T),
+    Im(T),
+}
+
+impl<T> Mul for ReIm<T>
+where
+    T: Mul<Output = T> + Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn mul(
+        self,
+        rhs: Self,
+    ) -> Self::Output {
+        match self {
+            Self::Zero => Self::Zero,
+            Self::Re(x) => match rhs {
+                Self::Zero => Self::Zero,
+                Self::Re(y) => Self::Re(x * y),
+                Self::Im(y) => Self::Im(x * y),
+            },
+            Self::Im(x) => match rhs {
+                Self::Zero => Self::Zero,
+                Self::Re(y) => Self::Im(x * y),
+                Self::Im(y) => Self::Re(-x * y),
+            },
+        }
+    }
+}
+
+impl<T> From<Root4> for ReIm<T>
+where
+    T: Neg<Output = T> + One,
+{
+    fn from(value: Root4) -> Self {
+        use Root4::{
+            R0,
+            R1,
+            R2,
+            R3,
+        };
+        match value {
+            R0 => ReIm::Re(T::one()),
+            R1 => ReIm::Re(-T::one()),
+            R2 => ReIm::Im(T::one()),
+            R3 => ReIm::Im(-T::one()),
+        }
+    }
+}
+
+enum Map {
+    An(Orbital),
+    Cr(Orbital),
+}
+
+macro_rules! impl_tryfrom_map {
+    ($($Typ:tt)* ) => {
+        $(
+            impl TryFrom<$Typ> for Map {
+                type Error = Error;
+
+                fn try_from(value: $Typ) -> Result<Self, Self::Error> {
+                    (value.0.index() < 64)
+                        .then_some(Self::$Typ(value.0))
+                        .ok_or_else(|| Error::QubitIndex {
+                            msg: "orbital index must be within 0..=63".to_string(),
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_tryfrom_map!(An Cr);
+
+fn pauli_codes_from_index(index: u16) -> (Paulis, Paulis) {
+    let code = Paulis::parity_op(index);
+
+    let x = {
+        let mut code = code;
+        code.set(index, Sigma::X);
+        code
+    };
+    let y = {
+        let mut code = code;
+        code.set(index, Sigma::Y);
+        code
+    };
+
+    (x, y)
+}
+
+impl Map {
+    fn index(&self) -> u16 {
+        u16::try_from(match self {
+            Self::An(an) => an.index(),
+            Self::Cr(cr) => cr.index(),
+        })
+        .expect("index within 0..=63")
+    }
+
+    fn mul_iter<'a, T, I>(
+        &'a self,
+        rhs: I,
+    ) -> impl Iterator<Item = (ReIm<T>, Paulis)> + 'a
+    where
+        T: Float + 'a,
+        I: IntoIterator<Item = (ReIm<T>, Paulis)> + 'a,
+    {
+        let one_half =
+            T::from(0.5_f64).expect("floating point conversion from 0.5");
+        let (x, y) = pauli_codes_from_index(self.index());
+        let term_x = ReIm::Re(one_half);
+        let term_y = match self {
+            Self::An(_) => ReIm::Im(one_half),
+            Self::Cr(_) => ReIm::Im(-one_half),
+        };
+
+        rhs.into_iter().flat_map(move |(rhs_coeff, rhs_pauli)| {
+            [(term_x, x), (term_y, y)].into_iter().map(
+                move |(lhs_coeff, lhs_pauli)| {
+                    let (root, prod) = lhs_pauli * rhs_pauli;
+
+                    (lhs_coeff * rhs_coeff * ReIm::from(root), prod)
+                },
+            )
+        })
+    }
+}
+
+fn iter_hermitian<'a, T, I>(iter: I) -> impl Iterator<Item = (T, Paulis)> + 'a
+where
+    T: Float + 'a,
+    I: IntoIterator<Item = (ReIm<T>, Paulis)> + 'a,
+{
+    let two = T::from(2.0_f64).expect("floating point conversion from 2.0");
+    iter.into_iter().filter_map(move |(x, p)| {
+        if let ReIm::Re(xre) = x {
+            Some((xre * two, p))
+        } else {
+            None
+        }
+    })
+}
+
+fn jw_map_two<'a, T: Float + 'a>(
+    op1: &'a Map,
+    op2: &'a Map,
+    coeff: T,
+) -> impl Iterator<Item = (T, Paulis)> + 'a {
+    iter_hermitian(
+        op1.mul_iter(op2.mul_iter([(ReIm::Re(coeff), Paulis::identity())])),
+    )
+}
+
+fn jw_map_four<'a, T: Float + 'a>(
+    op1: &'a Map,
+    op2: &'a Map,
+    op3: &'a Map,
+    op4: &'a Map,
+    coeff: T,
+) -> impl Iterator<Item = (T, Paulis)> + 'a {
+    iter_hermitian(op1.mul_iter(op2.mul_iter(
+        op3.mul_iter(op4.mul_iter([(ReIm::Re(coeff), Paulis::identity())])),
+    )))
+}
+
+fn add_term_to_reference<T>(
+    coeff: T,
+    code: Fermions,
+    repr: &mut impl Extend<(T, Paulis)>,
+) -> Result<(), Error>
+where
+    T: Float,
+{
+    match code {
+        Fermions::Offset => {
+            repr.extend(Some((coeff, Paulis::identity())));
+        }
+        Fermions::One {
+            cr,
+            an,
+        } => {
+            let jw_cr = Map::try_from(cr)?;
+            let jw_an = Map::try_from(an)?;
+            repr.extend(jw_map_two(&jw_cr, &jw_an, coeff));
+        }
+        Fermions::Two {
+            cr,
+            an,
+        } => {
+            let jw_cr = (Map::try_from(cr.0)?, Map::try_from(cr.1)?);
+            let jw_an = (Map::try_from(an.0)?, Map::try_from(an.1)?);
+            repr.extend(jw_map_four(
+                &jw_cr.0, &jw_cr.1, &jw_an.0, &jw_an.1, coeff,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Closed-form image of a single term.
+fn closed_form<T>(
+    coeff: T,
+    code: Fermions,
+    repr: &mut impl Extend<(T, Paulis)>,
+) -> Result<(), Error>
+where
+    T: Float + Send + Sync,
+{
+    JordanWigner::new(&SumRepr::from([(coeff, code)])).add_to(repr)
+}
+
+fn jw_canonical_codes(num_orbitals: u32) -> Vec<Fermions> {
+    let mut codes = vec![Fermions::Offset];
+    for p in 0..num_orbitals {
+        for q in p..num_orbitals {
+            codes.push(Fermions::try_from((p, q)).unwrap());
+        }
+    }
+    for p in 0..num_orbitals {
+        for q in p + 1..num_orbitals {
+            for r in p + 1..num_orbitals {
+                for s in p..r {
+                    codes.push(Fermions::try_from((p, q, r, s)).unwrap());
+                }
+            }
+        }
+    }
+    codes
+}
+
+#[test]
+fn closed_form_matches_reference() {
+    for code in jw_canonical_codes(7) {
+        let mut result = SumRepr::new();
+        closed_form(0.75, code, &mut result).unwrap();
+        let mut expected = SumRepr::new();
+        add_term_to_reference(0.75, code, &mut expected).unwrap();
+
+        assert_eq!(result, expected, "{code}");
+    }
+}
+
+#[test]
+fn closed_form_matches_reference_high_index() {
+    let codes = [
+        Fermions::try_from((0, 63)).unwrap(),
+        Fermions::try_from((62, 63)).unwrap(),
+        Fermions::try_from((0, 63, 62, 1)).unwrap(),
+        Fermions::try_from((31, 32, 63, 31)).unwrap(),
+        Fermions::try_from((2, 40, 40, 2)).unwrap(),
+    ];
+    for code in codes {
+        let mut result = SumRepr::new();
+        closed_form(-1.5, code, &mut result).unwrap();
+        let mut expected = SumRepr::new();
+        add_term_to_reference(-1.5, code, &mut expected).unwrap();
+
+        assert_eq!(result, expected, "{code}");
+    }
+}
+
+#[test]
+fn closed_form_index_error() {
+    let code = Fermions::try_from((0, 64)).unwrap();
+    let mut result = SumRepr::new();
+
+    assert!(closed_form(1.0, code, &mut result).is_err());
+    assert!(add_term_to_reference(1.0, code, &mut result).is_err());
+}
+
+#[test]
+fn mul_iter_01() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> =
+        jw_an.mul_iter([(Re(2.0), Paulis::identity())]).collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([X])),
+            (Im(1.0), Paulis::with_ops([Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_02() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> =
+        jw_cr.mul_iter([(Re(2.0), Paulis::identity())]).collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([X])),
+            (Im(-1.0), Paulis::with_ops([Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_03() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(3))).unwrap();
+
+    let result: Vec<_> =
+        jw_an.mul_iter([(Re(2.0), Paulis::identity())]).collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([Z, Z, Z, X])),
+            (Im(1.0), Paulis::with_ops([Z, Z, Z, Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_04() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(3))).unwrap();
+
+    let result: Vec<_> =
+        jw_cr.mul_iter([(Re(2.0), Paulis::identity())]).collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([Z, Z, Z, X])),
+            (Im(-1.0), Paulis::with_ops([Z, Z, Z, Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_05() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an_1 = Map::try_from(An(Orbital::with_index(0))).unwrap();
+    let jw_an_2 = Map::try_from(An(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> = jw_an_1
+        .mul_iter(jw_an_2.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([I])),
+            (Re(1.0), Paulis::with_ops([Z])),
+            (Re(-1.0), Paulis::with_ops([Z])),
+            (Re(-1.0), Paulis::with_ops([I])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_06() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_cr_1 = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
+    let jw_cr_2 = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> = jw_cr_1
+        .mul_iter(jw_cr_2.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([I])),
+            (Re(-1.0), Paulis::with_ops([Z])),
+            (Re(1.0), Paulis::with_ops([Z])),
+            (Re(-1.0), Paulis::with_ops([I])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_07() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> = jw_cr
+        .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([I])),
+            (Re(-1.0), Paulis::with_ops([Z])),
+            (Re(-1.0), Paulis::with_ops([Z])),
+            (Re(1.0), Paulis::with_ops([I])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_08() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(2))).unwrap();
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(2))).unwrap();
+
+    let result: Vec<_> = jw_cr
+        .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Re(1.0), Paulis::with_ops([I])),
+            (Re(-1.0), Paulis::with_ops([I, I, Z])),
+            (Re(-1.0), Paulis::with_ops([I, I, Z])),
+            (Re(1.0), Paulis::with_ops([I])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_09() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(1))).unwrap();
+
+    let result: Vec<_> = jw_cr
+        .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Im(1.0), Paulis::with_ops([Y, X])),
+            (Re(1.0), Paulis::with_ops([Y, Y])),
+            (Re(1.0), Paulis::with_ops([X, X])),
+            (Im(-1.0), Paulis::with_ops([X, Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_10() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(1))).unwrap();
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(0))).unwrap();
+
+    let result: Vec<_> = jw_cr
+        .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Im(-1.0), Paulis::with_ops([Y, X])),
+            (Re(1.0), Paulis::with_ops([X, X])),
+            (Re(1.0), Paulis::with_ops([Y, Y])),
+            (Im(1.0), Paulis::with_ops([X, Y])),
+        ]
+    );
+}
+
+#[test]
+fn mul_iter_11() {
+    use ReIm::*;
+    use Sigma::*;
+
+    let jw_an = Map::try_from(An(Orbital::with_index(0))).unwrap();
+    let jw_cr = Map::try_from(Cr(Orbital::with_index(2))).unwrap();
+
+    let result: Vec<_> = jw_cr
+        .mul_iter(jw_an.mul_iter([(Re(4.0), Paulis::identity())]))
+        .collect();
+
+    assert_eq!(
+        result,
+        &[
+            (Im(1.0), Paulis::with_ops([Y, Z, X])),
+            (Re(1.0), Paulis::with_ops([Y, Z, Y])),
+            (Re(1.0), Paulis::with_ops([X, Z, X])),
+            (Im(-1.0), Paulis::with_ops([X, Z, Y])),
+        ]
+    );
+}
+
+#[test]
+fn reim_mul() {
+    assert_eq!(ReIm::Re(1.0) * ReIm::Re(2.0), ReIm::Re(2.0));
+    assert_eq!(ReIm::Re(1.0) * ReIm::Im(2.0), ReIm::Im(2.0));
+    assert_eq!(ReIm::Im(1.0) * ReIm::Re(2.0), ReIm::Im(2.0));
+    assert_eq!(ReIm::Im(1.0) * ReIm::Im(2.0), ReIm::Re(-2.0));
+}
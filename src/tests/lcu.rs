@@ -0,0 +1,108 @@
+use crate::{
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    lcu::{
+        Prepare,
+        MAGIC,
+    },
+    terms::SumRepr,
+    Error,
+};
+
+#[test]
+fn prepare_invalid() {
+    let repr = SumRepr::from([(1.0, Paulis::identity())]);
+    assert!(matches!(Prepare::new(&repr, 8), Err(Error::Lcu { .. })));
+
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([Sigma::X]))]);
+    assert!(matches!(Prepare::new(&repr, 0), Err(Error::Lcu { .. })));
+    assert!(matches!(Prepare::new(&repr, 33), Err(Error::Lcu { .. })));
+
+    let repr = SumRepr::from([(f64::NAN, Paulis::with_ops([Sigma::X]))]);
+    assert!(matches!(Prepare::new(&repr, 8), Err(Error::Lcu { .. })));
+}
+
+#[test]
+fn prepare_single_term() {
+    let repr = SumRepr::from([(-2.0, Paulis::with_ops([Sigma::Z]))]);
+    let prep = Prepare::new(&repr, 3).unwrap();
+
+    assert_eq!(prep.num_terms, 1);
+    assert_eq!(prep.lambda, 2.0);
+    assert_eq!(prep.signs, &[-1]);
+    assert_eq!(prep.keep, &[0]);
+    assert_eq!(prep.alt, &[0]);
+    assert_eq!(prep.weights(), &[8]);
+}
+
+#[test]
+fn prepare_quantization_error() {
+    let coeffs = [0.731, -0.113, 0.052, 0.0071, -0.29, 1e-4, 0.0333];
+    let repr: SumRepr<f64, Paulis> = coeffs
+        .iter()
+        .enumerate()
+        .map(|(i, &coeff)| (coeff, Paulis::new(((i + 1) as u64, 0))))
+        .collect();
+    let lambda: f64 = coeffs.iter().map(|x| x.abs()).sum();
+
+    for num_bits in [1, 4, 10, 20] {
+        let prep = Prepare::new(&repr, num_bits).unwrap();
+        assert!((prep.lambda - lambda).abs() < 1e-12);
+        assert!(prep.coeffs.windows(2).all(|w| w[0] >= w[1]));
+
+        let capacity = 1_u64 << num_bits;
+        assert!(prep.keep.iter().all(|&k| k < capacity));
+        assert!(prep.alt.iter().all(|&a| a < prep.num_terms));
+
+        let total = prep.num_terms * capacity;
+        let weights = prep.weights();
+        assert_eq!(weights.iter().sum::<u64>(), total);
+        for (w, coeff) in weights.iter().zip(&prep.coeffs) {
+            let err = (*w as f64 / total as f64 - coeff / lambda).abs();
+            assert!(err <= 1.0 / total as f64 + 1e-12, "{num_bits}: {err}");
+        }
+    }
+}
+
+#[test]
+fn prepare_binary() {
+    let repr = SumRepr::from([
+        (0.5, Paulis::identity()),
+        (1.0, Paulis::with_ops([Sigma::X])),
+        (-3.0, Paulis::with_ops([Sigma::I, Sigma::Y])),
+    ]);
+    let prep = Prepare::new(&repr, 2).unwrap();
+    let mut buf = Vec::new();
+    prep.write_binary(&mut buf).unwrap();
+
+    let num_terms = 2;
+    assert_eq!(buf.len(), 40 + num_terms * (16 + 8 + 1 + 8 + 8));
+    assert_eq!(&buf[..8], MAGIC);
+    assert_eq!(buf[8..12], 1_u32.to_le_bytes());
+    assert_eq!(buf[12..16], 2_u32.to_le_bytes());
+    assert_eq!(buf[16..24], 2_u64.to_le_bytes());
+    assert_eq!(buf[24..32], 4.0_f64.to_le_bytes());
+    assert_eq!(buf[32..40], 0.5_f64.to_le_bytes());
+    assert_eq!(
+        buf[40..56],
+        Paulis::with_ops([Sigma::I, Sigma::Y]).index().to_le_bytes()
+    );
+    // signs
+    assert_eq!(buf[88..90], [1, 0]);
+}
+
+#[test]
+fn prepare_serde() {
+    let repr = SumRepr::from([
+        (1.0, Paulis::with_ops([Sigma::X])),
+        (-3.0, Paulis::with_ops([Sigma::I, Sigma::Y])),
+    ]);
+    let prep = Prepare::new(&repr, 2).unwrap();
+    let json = serde_json::to_string(&prep).unwrap();
+    assert!(json.starts_with(r#"{"type":"lcu_prepare""#));
+
+    let prep2: Prepare = serde_json::from_str(&json).unwrap();
+    assert_eq!(prep, prep2);
+}
@@ -1,6 +1,5 @@
 use crate::math::{
     Group,
-    ReIm,
     Root4,
 };
 
@@ -57,11 +56,3 @@ fn root4_conj() {
     assert_eq!(Root4::R2.conj(), Root4::R3);
     assert_eq!(Root4::R3.conj(), Root4::R2);
 }
-
-#[test]
-fn reim_mul() {
-    assert_eq!(ReIm::Re(1.0) * ReIm::Re(2.0), ReIm::Re(2.0));
-    assert_eq!(ReIm::Re(1.0) * ReIm::Im(2.0), ReIm::Im(2.0));
-    assert_eq!(ReIm::Im(1.0) * ReIm::Re(2.0), ReIm::Im(2.0));
-    assert_eq!(ReIm::Im(1.0) * ReIm::Im(2.0), ReIm::Re(-2.0));
-}
@@ -0,0 +1,156 @@
+use num::Complex;
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    map::JordanWigner,
+    matrix::{
+        Coo,
+        Csr,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        SumRepr,
+        Terms,
+    },
+};
+
+fn random_fermi_sum(
+    rng: &mut impl Rng,
+    num_orbitals: u32,
+    num_terms: usize,
+) -> FermiSum {
+    let mut repr = SumRepr::new();
+    repr.add_term(Fermions::Offset, rng.gen_range(-1.0..1.0));
+    for _ in 0..num_terms {
+        let code = if rng.gen_bool(0.5) {
+            let p = rng.gen_range(0..num_orbitals);
+            let q = rng.gen_range(p..num_orbitals);
+            Fermions::try_from((p, q))
+        } else {
+            Fermions::try_from((
+                rng.gen_range(0..num_orbitals),
+                rng.gen_range(0..num_orbitals),
+                rng.gen_range(0..num_orbitals),
+                rng.gen_range(0..num_orbitals),
+            ))
+        };
+        if let Ok(code) = code {
+            repr.add_term(code, rng.gen_range(-1.0..1.0));
+        }
+    }
+    repr
+}
+
+fn assert_matrix_eq(
+    a: &Csr<Complex<f64>>,
+    b: &Csr<Complex<f64>>,
+) {
+    assert_eq!(a.shape(), b.shape());
+    let (n, m) = a.shape();
+    for i in 0..n {
+        for j in 0..m {
+            let diff = a.get(i, j) - b.get(i, j);
+            assert!(diff.norm() < 1e-12, "entry ({i}, {j}) differs: {diff}");
+        }
+    }
+}
+
+#[test]
+fn coo_to_csr_01() {
+    let mut mtx = Coo::new(3, 3);
+    mtx.push(2, 0, 1.0);
+    mtx.push(0, 2, 2.0);
+    mtx.push(2, 0, 3.0);
+    mtx.push(0, 1, 4.0);
+    mtx.push(1, 1, 1.0);
+    mtx.push(1, 1, -1.0);
+
+    let mtx = Csr::from(mtx);
+
+    assert_eq!(mtx.nnz(), 3);
+    assert_eq!(mtx.row_ptr(), &[0, 2, 2, 3]);
+    assert_eq!(mtx.col_idx(), &[1, 2, 0]);
+    assert_eq!(mtx.values(), &[4.0, 2.0, 4.0]);
+}
+
+#[test]
+fn paulis_matrix_01() {
+    use Sigma::*;
+
+    // X0 Z1 on |b1 b0>
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([X, Z]))]);
+    let mtx = Csr::from(Coo::from_paulis(&repr, 2).unwrap());
+
+    assert_eq!(mtx.nnz(), 4);
+    assert_eq!(mtx.get(1, 0), Complex::new(1.0, 0.0));
+    assert_eq!(mtx.get(0, 1), Complex::new(1.0, 0.0));
+    assert_eq!(mtx.get(3, 2), Complex::new(-1.0, 0.0));
+    assert_eq!(mtx.get(2, 3), Complex::new(-1.0, 0.0));
+}
+
+#[test]
+fn paulis_matrix_02() {
+    use Sigma::*;
+
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([I, I, X]))]);
+
+    assert!(Coo::from_paulis(&repr, 2).is_err());
+    assert!(Coo::from_paulis(&repr, 3).is_ok());
+}
+
+#[test]
+fn fermions_matrix_01() {
+    let repr: FermiSum =
+        SumRepr::from([(0.5, Fermions::try_from((0, 1)).unwrap())]);
+    let mtx = Csr::from(Coo::from_fermions(&repr, 2).unwrap());
+
+    // 0.5 * (a+_0 a_1 + a+_1 a_0)
+    assert_eq!(mtx.nnz(), 2);
+    assert_eq!(mtx.get(0b01, 0b10), Complex::new(0.5, 0.0));
+    assert_eq!(mtx.get(0b10, 0b01), Complex::new(0.5, 0.0));
+
+    assert!(Coo::from_fermions(&repr, 1).is_err());
+}
+
+#[test]
+fn fermions_matrix_02() {
+    let repr: FermiSum =
+        SumRepr::from([(1.0, Fermions::try_from((0, 2, 2, 1)).unwrap())]);
+    let mtx = Csr::from(Coo::from_fermions(&repr, 3).unwrap());
+
+    // Orbitals 1 and 2 occupied: a+_0 a+_2 a_2 a_1 |110> = a+_0 |100>
+    assert_eq!(mtx.get(0b101, 0b110), Complex::new(1.0, 0.0));
+    assert_eq!(mtx.get(0b110, 0b101), Complex::new(1.0, 0.0));
+    assert_eq!(mtx.nnz(), 2);
+}
+
+#[test]
+fn jordan_wigner_matrix_01() {
+    let mut rng = StdRng::seed_from_u64(7);
+
+    for num_orbitals in 1..=5 {
+        let fermi_repr = random_fermi_sum(&mut rng, num_orbitals, 12);
+        let mut pauli_repr = PauliSum::new();
+        JordanWigner::new(&fermi_repr)
+            .add_to(&mut pauli_repr)
+            .unwrap();
+
+        let n = u16::try_from(num_orbitals).unwrap();
+        let mtx_f = Csr::from(Coo::from_fermions(&fermi_repr, n).unwrap());
+        let mtx_p = Csr::from(Coo::from_paulis(&pauli_repr, n).unwrap());
+
+        assert_matrix_eq(&mtx_f, &mtx_p);
+    }
+}
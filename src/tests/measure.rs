@@ -0,0 +1,227 @@
+use crate::{
+    circuit::{
+        Gate,
+        QasmVersion,
+    },
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    measure::{
+        eigenvalue,
+        estimate,
+        parse_outcome,
+        qubit_wise_commute,
+        BitOrder,
+        Counts,
+        Grouping,
+    },
+    terms::SumRepr,
+    Error,
+};
+
+#[test]
+fn qubit_wise_commute_01() {
+    let x = Paulis::with_ops([Sigma::X]);
+    let y = Paulis::with_ops([Sigma::Y]);
+    let iy = Paulis::with_ops([Sigma::I, Sigma::Y]);
+
+    assert!(qubit_wise_commute(x, x));
+    assert!(qubit_wise_commute(x, iy));
+    assert!(qubit_wise_commute(Paulis::identity(), y));
+    assert!(!qubit_wise_commute(x, y));
+}
+
+#[test]
+fn grouping_covers_all_terms() {
+    let codes = [
+        [Sigma::X, Sigma::X, Sigma::I],
+        [Sigma::Y, Sigma::Y, Sigma::I],
+        [Sigma::Z, Sigma::Z, Sigma::I],
+        [Sigma::Z, Sigma::I, Sigma::I],
+        [Sigma::I, Sigma::Z, Sigma::Z],
+        [Sigma::I, Sigma::X, Sigma::Y],
+        [Sigma::X, Sigma::I, Sigma::Y],
+    ];
+    let mut repr = SumRepr::from([(-1.5, Paulis::identity())]);
+    for (i, ops) in codes.iter().enumerate() {
+        repr.add_term(Paulis::with_ops(*ops), 0.1 * (i + 1) as f64);
+    }
+    let grouping = Grouping::qubit_wise(&repr);
+
+    assert_eq!(grouping.identity(), -1.5);
+    let mut num_terms = 0;
+    for group in grouping.groups() {
+        for (i, &(coeff, code)) in group.terms().iter().enumerate() {
+            assert!(group.is_compatible(code));
+            assert_eq!(repr.coeff(code), Some(&coeff));
+            for &(_, other) in &group.terms()[i + 1..] {
+                assert!(qubit_wise_commute(code, other));
+            }
+        }
+        num_terms += group.terms().len();
+    }
+    assert_eq!(num_terms, codes.len());
+}
+
+#[test]
+fn group_circuit_01() {
+    let repr = SumRepr::from([
+        (0.5, Paulis::with_ops([Sigma::X, Sigma::I, Sigma::Y])),
+        (0.25, Paulis::with_ops([Sigma::X, Sigma::Z])),
+    ]);
+    let grouping = Grouping::qubit_wise(&repr);
+    assert_eq!(grouping.groups().len(), 1);
+
+    let circs = grouping.circuits(4).unwrap();
+    assert_eq!(
+        circs[0].gates(),
+        &[
+            Gate::H(0),
+            Gate::Sdg(2),
+            Gate::H(2),
+            Gate::Measure(0),
+            Gate::Measure(1),
+            Gate::Measure(2),
+            Gate::Measure(3),
+        ]
+    );
+
+    assert!(matches!(grouping.circuits(2), Err(Error::Circuit { .. })));
+}
+
+#[test]
+fn group_circuit_qasm() {
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([Sigma::I, Sigma::X]))]);
+    let circ = &Grouping::qubit_wise(&repr).circuits(2).unwrap()[0];
+
+    let mut buf = Vec::new();
+    circ.write_qasm(&mut buf, QasmVersion::V2).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "OPENQASM 2.0;
+include \"qelib1.inc\";
+qreg q[2];
+creg c[2];
+h q[1];
+measure q[0] -> c[0];
+measure q[1] -> c[1];
+"
+    );
+
+    let mut buf = Vec::new();
+    circ.write_qasm(&mut buf, QasmVersion::V3).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "OPENQASM 3.0;
+include \"stdgates.inc\";
+qubit[2] q;
+bit[2] c;
+h q[1];
+c[0] = measure q[0];
+c[1] = measure q[1];
+"
+    );
+}
+
+#[test]
+fn eigenvalue_01() {
+    let code = Paulis::with_ops([Sigma::X, Sigma::Y, Sigma::I, Sigma::Z]);
+    for outcome in 0..16_u64 {
+        let parity = (outcome & 0b1011).count_ones() % 2;
+        let expected = if parity == 0 { 1.0 } else { -1.0 };
+        assert_eq!(eigenvalue(code, outcome), expected);
+    }
+}
+
+#[test]
+fn parse_outcome_01() {
+    assert_eq!(parse_outcome("1", BitOrder::BigEndian), Ok(1));
+    assert_eq!(parse_outcome("110", BitOrder::BigEndian), Ok(0b110));
+    assert_eq!(parse_outcome("110", BitOrder::LittleEndian), Ok(0b011));
+
+    let long = "1".repeat(64);
+    assert_eq!(parse_outcome(&long, BitOrder::BigEndian), Ok(u64::MAX));
+
+    for bits in ["", "012", "1 0", &"0".repeat(65)] {
+        assert!(matches!(
+            parse_outcome(bits, BitOrder::BigEndian),
+            Err(Error::Measure { .. })
+        ));
+    }
+}
+
+#[test]
+fn estimate_big_endian() {
+    // Qubit 0 measured 1 is printed as the last character
+    let repr = SumRepr::from([(1.0, Paulis::with_ops([Sigma::Z]))]);
+    let mut counts = Counts::new(Paulis::with_ops([Sigma::Z, Sigma::Z]));
+    counts.add(parse_outcome("01", BitOrder::BigEndian).unwrap(), 10);
+
+    let est = estimate(&repr, &[counts]).unwrap();
+    assert_eq!(est.energy, -1.0);
+}
+
+#[test]
+fn estimate_eigenstate() {
+    // State |0>|+> is an eigenstate of Z_0 + 0.5 X_1 - 0.25 Z_0 X_1
+    let repr = SumRepr::from([
+        (2.0, Paulis::identity()),
+        (1.0, Paulis::with_ops([Sigma::Z])),
+        (0.5, Paulis::with_ops([Sigma::I, Sigma::X])),
+        (-0.25, Paulis::with_ops([Sigma::Z, Sigma::X])),
+    ]);
+    let mut counts = Counts::new(Paulis::with_ops([Sigma::Z, Sigma::X]));
+    counts.add(0b00, 1000);
+
+    let est = estimate(&repr, &[counts]).unwrap();
+    assert_eq!(est.energy, 3.25);
+    assert_eq!(est.variance, 0.0);
+    assert_eq!(est.terms.len(), 3);
+    assert!(est.terms.iter().all(|t| t.variance == 0.0 && t.counts == 0));
+}
+
+#[test]
+fn estimate_variance() {
+    let x = Paulis::with_ops([Sigma::X]);
+    let z = Paulis::with_ops([Sigma::Z]);
+    let repr = SumRepr::from([(0.5, x), (2.0, z)]);
+
+    let mut counts_z = Counts::new(z);
+    counts_z.add(0, 3);
+    counts_z.add(1, 1);
+    let mut counts_x = Counts::new(x);
+    counts_x.add(0, 1);
+    counts_x.add(1, 1);
+    // Counts without shots are skipped
+    let counts_empty = Counts::new(x);
+
+    let est = estimate(&repr, &[counts_empty, counts_z, counts_x]).unwrap();
+
+    // <Z> = 0.5, <X> = 0
+    assert_eq!(est.energy, 1.0);
+    // Sample variances: 2^2 * 4/3 * (1 - 0.25) / 4 and 0.5^2 * 2 * 1 / 2
+    assert!((est.variance - (1.0 + 0.25)).abs() < 1e-12);
+
+    assert_eq!(est.terms[0].code, x);
+    assert_eq!(est.terms[0].counts, 2);
+    assert_eq!(est.terms[0].variance, 0.5);
+    assert_eq!(est.terms[1].code, z);
+    assert_eq!(est.terms[1].counts, 1);
+    assert_eq!(est.terms[1].expectation, 0.5);
+}
+
+#[test]
+fn estimate_not_covered() {
+    let repr = SumRepr::from([
+        (1.0, Paulis::with_ops([Sigma::Z])),
+        (1.0, Paulis::with_ops([Sigma::I, Sigma::Y])),
+    ]);
+    let mut counts = Counts::new(Paulis::with_ops([Sigma::Z, Sigma::X]));
+    counts.add(0, 1);
+
+    assert!(matches!(
+        estimate(&repr, &[counts]),
+        Err(Error::Measure { .. })
+    ));
+}
@@ -0,0 +1,118 @@
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        SumRepr,
+    },
+};
+
+#[test]
+fn pauli_metrics_mixed() {
+    let repr = SumRepr::from([
+        (-2.0, Paulis::identity()),
+        (0.5, Paulis::with_ops([Sigma::X])),
+        (-1.5, Paulis::with_ops([Sigma::I, Sigma::Y, Sigma::Z])),
+        (0.25, Paulis::with_ops([Sigma::Z, Sigma::Z])),
+        (
+            -0.125,
+            Paulis::with_ops([Sigma::I, Sigma::I, Sigma::I, Sigma::X]),
+        ),
+    ]);
+
+    assert_eq!(repr.one_norm(), 2.375);
+    assert_eq!(repr.max_coeff(), Some(1.5));
+    assert_eq!(repr.weight_histogram(), &[1, 2, 2]);
+    assert_eq!(repr.num_qubits(), 4);
+    assert_eq!(repr.identity_shift(), -2.0);
+}
+
+#[test]
+fn pauli_metrics_identity_only() {
+    let repr = SumRepr::from([(-2.0, Paulis::identity())]);
+
+    assert_eq!(repr.one_norm(), 0.0);
+    assert_eq!(repr.max_coeff(), None);
+    assert_eq!(repr.weight_histogram(), &[1]);
+    assert_eq!(repr.num_qubits(), 0);
+    assert_eq!(repr.identity_shift(), -2.0);
+}
+
+#[test]
+fn pauli_metrics_empty() {
+    let repr = PauliSum::new();
+
+    assert_eq!(repr.one_norm(), 0.0);
+    assert_eq!(repr.max_coeff(), None);
+    assert!(repr.weight_histogram().is_empty());
+    assert_eq!(repr.num_qubits(), 0);
+    assert_eq!(repr.identity_shift(), 0.0);
+}
+
+#[test]
+fn fermi_metrics_mixed() {
+    let repr = SumRepr::from([
+        (1.0, Fermions::Offset),
+        (-0.5, Fermions::try_from((0, 0)).unwrap()),
+        (0.75, Fermions::try_from((1, 3)).unwrap()),
+        (0.25, Fermions::try_from((0, 1, 1, 0)).unwrap()),
+        (-2.0, Fermions::try_from((1, 7, 4, 2)).unwrap()),
+    ]);
+
+    // Sum of stored coefficients, not the 1-norm of T + T^†
+    assert_eq!(repr.one_norm(), 3.5);
+    assert_eq!(repr.max_coeff(), Some(2.0));
+    assert_eq!(repr.order_histogram(), [1, 2, 2]);
+    assert_eq!(repr.num_orbitals(), 8);
+    assert_eq!(repr.offset(), 1.0);
+}
+
+#[test]
+fn fermi_metrics_offset_only() {
+    let repr = SumRepr::from([(1.0, Fermions::Offset)]);
+
+    assert_eq!(repr.one_norm(), 0.0);
+    assert_eq!(repr.max_coeff(), None);
+    assert_eq!(repr.order_histogram(), [1, 0, 0]);
+    assert_eq!(repr.num_orbitals(), 0);
+    assert_eq!(repr.offset(), 1.0);
+}
+
+#[test]
+fn fermi_metrics_empty() {
+    let repr = FermiSum::new();
+
+    assert_eq!(repr.one_norm(), 0.0);
+    assert_eq!(repr.max_coeff(), None);
+    assert_eq!(repr.order_histogram(), [0, 0, 0]);
+    assert_eq!(repr.num_orbitals(), 0);
+    assert_eq!(repr.offset(), 0.0);
+}
+
+#[test]
+fn fermions_max_index() {
+    assert_eq!(Fermions::Offset.max_index(), None);
+    assert_eq!(Fermions::try_from((2, 2)).unwrap().max_index(), Some(2));
+    assert_eq!(Fermions::try_from((0, 9)).unwrap().max_index(), Some(9));
+
+    // The largest index can be that of a creation or annihilation operator
+    for (code, expected) in [
+        ((0, 7, 1, 0), 7),
+        ((0, 5, 2, 1), 5),
+        ((0, 1, 8, 3), 8),
+        ((0, 1, 3, 0), 3),
+        ((1, 2, 4, 3), 4),
+    ] {
+        assert_eq!(
+            Fermions::try_from(code).unwrap().max_index(),
+            Some(expected),
+            "{code:?}"
+        );
+    }
+}
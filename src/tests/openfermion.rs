@@ -0,0 +1,277 @@
+use rand::Rng;
+
+use crate::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    openfermion::{
+        parse_fermion_operator,
+        parse_qubit_operator,
+        write_fermion_operator,
+        write_qubit_operator,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        SumRepr,
+    },
+    Error,
+};
+
+fn write_to_string(repr: &FermiSum) -> String {
+    let mut buf = Vec::new();
+    write_fermion_operator(repr, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn fermion_operator_parse_01() {
+    let repr = parse_fermion_operator(
+        "(0.5+0j) [] +
+        -0.25 [0^ 2] +
+        -0.25 [2^ 0] - [1^ 1]",
+    )
+    .unwrap();
+
+    let expected = SumRepr::from([
+        (0.5, Fermions::Offset),
+        (-0.25, Fermions::try_from((0, 2)).unwrap()),
+        (-0.5, Fermions::try_from((1, 1)).unwrap()),
+    ]);
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn fermion_operator_parse_zero() {
+    assert!(parse_fermion_operator("0").unwrap().is_empty());
+    assert!(parse_fermion_operator("  \n").unwrap().is_empty());
+    assert!(parse_fermion_operator("1.0 [0^ 0^ 1 1]")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn fermion_operator_parse_coeffs() {
+    let n_0 = Fermions::try_from((0, 0)).unwrap();
+    for (input, expected) in [
+        ("[0^ 0]", 0.5),
+        ("+[0^ 0]", 0.5),
+        ("-[0^ 0]", -0.5),
+        ("2 [0^ 0]", 1.0),
+        ("1e-1 [0^ 0]", 0.05),
+        ("-1.0E+01 [0^ 0]", -5.0),
+        ("(-1+0j) [0^ 0]", -0.5),
+        ("(1e-05-0j) [0^ 0]", 0.5e-5),
+        ("(1.0+1e-12j) [0^ 0]", 0.5),
+        ("0j [0^ 0]", 0.0),
+    ] {
+        let repr = parse_fermion_operator(input).unwrap();
+        assert_eq!(repr.coeff(n_0), Some(&expected), "{input}");
+    }
+}
+
+#[test]
+fn fermion_operator_normal_order() {
+    // a_0 a+_0 = 1 - n_0
+    let repr = parse_fermion_operator("[0 0^]").unwrap();
+    let expected = SumRepr::from([
+        (1.0, Fermions::Offset),
+        (-0.5, Fermions::try_from((0, 0)).unwrap()),
+    ]);
+    assert_eq!(repr, expected);
+
+    // a+_1 a+_0 a_0 a_1 = a+_0 a+_1 a_1 a_0
+    let repr = parse_fermion_operator("0.5 [1^ 0^ 0 1]").unwrap();
+    let expected =
+        SumRepr::from([(0.25, Fermions::try_from((0, 1, 1, 0)).unwrap())]);
+    assert_eq!(repr, expected);
+
+    // a+_1 a+_2 a_3 a_4 = -a+_1 a+_2 a_4 a_3
+    let repr =
+        parse_fermion_operator("0.5 [1^ 2^ 3 4] + 0.5 [4^ 3^ 2 1]").unwrap();
+    let expected =
+        SumRepr::from([(-0.5, Fermions::try_from((1, 2, 4, 3)).unwrap())]);
+    assert_eq!(repr, expected);
+
+    // a_1 a+_2 a_3 a+_1 = a+_2 a_3 a_1 a+_1 = a+_2 a_3 - a+_1 a+_2 a_3 a_1
+    let repr = parse_fermion_operator(
+        "[1 2^ 3 1^] +
+        [1 3^ 2 1^]",
+    )
+    .unwrap();
+    let expected = SumRepr::from([
+        (1.0, Fermions::try_from((2, 3)).unwrap()),
+        (-1.0, Fermions::try_from((1, 2, 3, 1)).unwrap()),
+    ]);
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn fermion_operator_adjoint_codes() {
+    // Both codes are valid, and stand for the same operator.
+    let repr = SumRepr::from([
+        (0.5, Fermions::try_from((0, 3, 1, 0)).unwrap()),
+        (0.25, Fermions::try_from((0, 1, 3, 0)).unwrap()),
+    ]);
+
+    let output = write_to_string(&repr);
+    assert_eq!(
+        output,
+        "0.25 [0^ 1^ 3 0] +
+0.25 [0^ 3^ 1 0] +
+0.5 [0^ 3^ 1 0] +
+0.5 [0^ 1^ 3 0]
+"
+    );
+    assert_eq!(
+        parse_fermion_operator(&output).unwrap(),
+        SumRepr::from([(0.75, Fermions::try_from((0, 1, 3, 0)).unwrap())])
+    );
+}
+
+#[test]
+fn fermion_operator_parse_errors() {
+    for input in [
+        "0.5 [0^ 1]",
+        "0.5 [0^ 1] + 0.25 [1^ 0]",
+        "(0.5+0.5j) [0^ 0]",
+        "0.5 [0^]",
+        "0.5 [0^ 1^ 2^ 2 1 0]",
+        "0.5 [0^ 0",
+        "0.5 0^ 0]",
+        "0.5 [0^ 0] 0.5 [1^ 1]",
+        "0.5 [0^ 0] +",
+        "x [0^ 0]",
+        "0.5 [a^ 0]",
+        "0.5 [-1^ 0]",
+    ] {
+        let err = parse_fermion_operator(input).unwrap_err();
+        assert!(matches!(err, Error::OpenFermion { .. }), "{input}: {err}");
+    }
+}
+
+#[test]
+fn fermion_operator_write_empty() {
+    let repr = FermiSum::new();
+    assert_eq!(write_to_string(&repr), "0\n");
+    assert_eq!(parse_fermion_operator("0\n").unwrap(), repr);
+}
+
+#[test]
+fn fermion_operator_write_01() {
+    let repr = SumRepr::from([
+        (-0.5, Fermions::try_from((1, 2, 4, 3)).unwrap()),
+        (0.25, Fermions::try_from((0, 1, 1, 0)).unwrap()),
+        (1e-20, Fermions::try_from((0, 2)).unwrap()),
+    ]);
+
+    assert_eq!(
+        write_to_string(&repr),
+        "1e-20 [0^ 2] +
+1e-20 [2^ 0] +
+0.5 [0^ 1^ 1 0] +
+-0.5 [1^ 2^ 4 3] +
+-0.5 [3^ 4^ 2 1]
+"
+    );
+}
+
+#[test]
+fn fermion_operator_write_parse() {
+    let mut rng = rand::thread_rng();
+    let mut repr = FermiSum::new();
+    repr.add_term(Fermions::Offset, rng.gen_range(-1.0..1.0));
+    for p in 0..4 {
+        for q in 0..4 {
+            if let Ok(code) = Fermions::try_from((p, q)) {
+                repr.add_term(code, rng.gen_range(-1.0..1.0));
+            }
+            for r in 0..4 {
+                for s in 0..4 {
+                    // Skip adjoints of codes already included
+                    if p == s && q > r {
+                        continue;
+                    }
+                    if let Ok(code) = Fermions::try_from((p, q, r, s)) {
+                        repr.add_term(code, rng.gen_range(-1.0..1.0));
+                    }
+                }
+            }
+        }
+    }
+
+    let output = write_to_string(&repr);
+    assert_eq!(parse_fermion_operator(&output).unwrap(), repr);
+}
+
+#[test]
+fn qubit_operator_parse_01() {
+    let repr = parse_qubit_operator(
+        "(-0.5+0j) [] +
+        0.25 [X0 Y1 Z3] +
+        0.125 [Z63] - [Y1 X0 Z3]",
+    )
+    .unwrap();
+
+    let mut z63 = Paulis::identity();
+    z63.set(63, Sigma::Z);
+    let expected = SumRepr::from([
+        (-0.5, Paulis::identity()),
+        (
+            -0.75,
+            Paulis::with_ops([Sigma::X, Sigma::Y, Sigma::I, Sigma::Z]),
+        ),
+        (0.125, z63),
+    ]);
+    assert_eq!(repr, expected);
+    assert!(parse_qubit_operator("0").unwrap().is_empty());
+}
+
+#[test]
+fn qubit_operator_parse_errors() {
+    for input in [
+        "0.5 [X0 Y0]",
+        "0.5 [X64]",
+        "0.5 [I0]",
+        "0.5 [x0]",
+        "0.5 [X]",
+        "0.5 [0^ 1]",
+        "(0.5+0.5j) [X0]",
+        "0.5 [X0] 0.5 [Z0]",
+    ] {
+        let err = parse_qubit_operator(input).unwrap_err();
+        assert!(matches!(err, Error::OpenFermion { .. }), "{input}: {err}");
+    }
+}
+
+#[test]
+fn qubit_operator_write_empty() {
+    let repr = PauliSum::new();
+    let mut buf = Vec::new();
+    write_qubit_operator(&repr, &mut buf).unwrap();
+
+    assert_eq!(String::from_utf8(buf).unwrap(), "0\n");
+}
+
+#[test]
+fn qubit_operator_write_parse() {
+    let mut rng = rand::thread_rng();
+    let mut repr = PauliSum::new();
+    for _ in 0..100 {
+        let code = Paulis::new((rng.gen(), rng.gen()));
+        repr.add_term(code, rng.gen_range(-1.0..1.0));
+    }
+    repr.add_term(Paulis::identity(), 1e-300);
+
+    let mut buf = Vec::new();
+    write_qubit_operator(&repr, &mut buf).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+
+    assert_eq!(output.lines().count(), repr.len());
+    assert!(output.starts_with("1e-300 [] +\n"));
+    assert_eq!(parse_qubit_operator(&output).unwrap(), repr);
+}
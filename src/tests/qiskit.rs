@@ -0,0 +1,157 @@
+use rand::Rng;
+
+use crate::{
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    qiskit::SparsePauliOp,
+    terms::{
+        PauliSum,
+        SumRepr,
+        Terms,
+    },
+    Error,
+};
+
+fn to_paulisum(op: &SparsePauliOp) -> Result<PauliSum, Error> {
+    let mut repr = SumRepr::new();
+    op.add_to(&mut repr)?;
+    Ok(repr)
+}
+
+fn sparse_pauli_op(
+    labels: &[&str],
+    coeffs: &[f64],
+) -> SparsePauliOp {
+    SparsePauliOp {
+        labels: labels.iter().map(ToString::to_string).collect(),
+        coeffs: coeffs.to_vec(),
+    }
+}
+
+#[test]
+fn sparse_pauli_op_endianness() {
+    let op = sparse_pauli_op(&["IIIX", "XIII", "ZYXI"], &[1.0, 2.0, 3.0]);
+    let repr = to_paulisum(&op).unwrap();
+
+    let expected = SumRepr::from([
+        (1.0, Paulis::with_ops([Sigma::X])),
+        (
+            2.0,
+            Paulis::with_ops([Sigma::I, Sigma::I, Sigma::I, Sigma::X]),
+        ),
+        (
+            3.0,
+            Paulis::with_ops([Sigma::I, Sigma::X, Sigma::Y, Sigma::Z]),
+        ),
+    ]);
+    assert_eq!(repr, expected);
+    assert_eq!(SparsePauliOp::from_terms(&repr, None).unwrap(), op);
+}
+
+#[test]
+fn sparse_pauli_op_from_terms_num_qubits() {
+    let repr = SumRepr::from([
+        (0.5, Paulis::identity()),
+        (0.25, Paulis::with_ops([Sigma::I, Sigma::Z])),
+    ]);
+
+    assert_eq!(
+        SparsePauliOp::from_terms(&repr, None).unwrap(),
+        sparse_pauli_op(&["II", "ZI"], &[0.5, 0.25])
+    );
+    assert_eq!(
+        SparsePauliOp::from_terms(&repr, Some(4)).unwrap(),
+        sparse_pauli_op(&["IIII", "IIZI"], &[0.5, 0.25])
+    );
+
+    let err = SparsePauliOp::from_terms(&repr, Some(1)).unwrap_err();
+    assert!(matches!(err, Error::Qiskit { .. }), "{err}");
+    let err = SparsePauliOp::from_terms(&repr, Some(65)).unwrap_err();
+    assert!(matches!(err, Error::Qiskit { .. }), "{err}");
+
+    let mut code = Paulis::identity();
+    code.set(63, Sigma::Y);
+    let op =
+        SparsePauliOp::from_terms(&SumRepr::from([(1.0, code)]), None).unwrap();
+    assert_eq!(op.num_qubits(), Some(64));
+    assert!(op.labels[0].starts_with("YI"));
+    assert_eq!(to_paulisum(&op).unwrap(), SumRepr::from([(1.0, code)]));
+}
+
+#[test]
+fn sparse_pauli_op_empty() {
+    let repr = PauliSum::new();
+    let op = SparsePauliOp::from_terms(&repr, None).unwrap();
+    assert_eq!(op, sparse_pauli_op(&["I"], &[0.0]));
+
+    let op =
+        SparsePauliOp::from_terms(&SumRepr::<f32, _>::new(), Some(3)).unwrap();
+    assert_eq!(op, sparse_pauli_op(&["III"], &[0.0]));
+
+    assert!(to_paulisum(&SparsePauliOp::default()).unwrap().is_empty());
+}
+
+#[test]
+fn sparse_pauli_op_duplicate_labels() {
+    let op = sparse_pauli_op(&["XZ", "XZ", "II"], &[0.5, 0.25, 1.0]);
+    let repr = to_paulisum(&op).unwrap();
+
+    assert_eq!(repr.len(), 2);
+    assert_eq!(
+        repr.coeff(Paulis::with_ops([Sigma::Z, Sigma::X])),
+        Some(&0.75)
+    );
+}
+
+#[test]
+fn sparse_pauli_op_errors() {
+    for op in [
+        sparse_pauli_op(&["XZ", "Z"], &[0.5, 0.25]),
+        sparse_pauli_op(&["XZ"], &[0.5, 0.25]),
+        sparse_pauli_op(&["XA"], &[0.5]),
+        sparse_pauli_op(&["xz"], &[0.5]),
+        sparse_pauli_op(&["-XZ"], &[0.5]),
+        sparse_pauli_op(&[&"X".repeat(65)], &[0.5]),
+    ] {
+        let err = to_paulisum(&op).unwrap_err();
+        assert!(matches!(err, Error::Qiskit { .. }), "{op:?}: {err}");
+    }
+}
+
+#[test]
+fn sparse_pauli_op_errors_keep_sum() {
+    let mut repr = SumRepr::from([(1.0, Paulis::identity())]);
+    for op in [
+        sparse_pauli_op(&["XZ", "YY", "Z"], &[0.5, 0.25, 1.0]),
+        sparse_pauli_op(&["XZ", "YY", "XA"], &[0.5, 0.25, 1.0]),
+    ] {
+        assert!(op.add_to(&mut repr).is_err());
+        assert_eq!(repr, SumRepr::from([(1.0, Paulis::identity())]));
+    }
+}
+
+#[test]
+fn sparse_pauli_op_from_terms_to_terms() {
+    let mut rng = rand::thread_rng();
+    let mut repr = PauliSum::new();
+    for _ in 0..100 {
+        let code = Paulis::new((rng.gen(), rng.gen::<u64>() >> 4));
+        repr.add_term(code, rng.gen_range(-1.0..1.0));
+    }
+
+    let op = SparsePauliOp::from_terms(&repr, Some(64)).unwrap();
+    assert_eq!(op.labels.len(), repr.len());
+    assert!(op.labels.windows(2).all(|w| w[0] != w[1]));
+    assert_eq!(to_paulisum(&op).unwrap(), repr);
+}
+
+#[test]
+fn sparse_pauli_op_serde() {
+    let op = sparse_pauli_op(&["IZ", "XY"], &[0.5, -0.25]);
+    let json = serde_json::to_string(&op).unwrap();
+
+    assert_eq!(json, r#"{"labels":["IZ","XY"],"coeffs":[0.5,-0.25]}"#);
+    assert_eq!(serde_json::from_str::<SparsePauliOp>(&json).unwrap(), op);
+}
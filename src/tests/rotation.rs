@@ -0,0 +1,263 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use crate::{
+    code::fermions::{
+        Fermions,
+        Orbital,
+        Spin,
+    },
+    eigen::Lanczos,
+    integrals::{
+        Convention,
+        Integrals,
+    },
+    matrix::{
+        Coo,
+        Csr,
+        Sector,
+    },
+    rotation::OrbitalRotation,
+    terms::{
+        FermiSum,
+        SumRepr,
+    },
+    Error,
+};
+
+/// Random orthogonal matrix, as a product of Givens rotations.
+fn random_orthogonal(n: usize) -> Vec<f64> {
+    let mut rng = rand::thread_rng();
+    let mut u = vec![0.0; n * n];
+    for i in 0..n {
+        u[i * n + i] = 1.0;
+    }
+    for _ in 0..n * n {
+        let i = rng.gen_range(0..n);
+        let j = rng.gen_range(0..n);
+        if i == j {
+            continue;
+        }
+        let theta: f64 = rng.gen_range(-PI..PI);
+        let (sin, cos) = theta.sin_cos();
+        for row in u.chunks_mut(n) {
+            let (a, b) = (row[i], row[j]);
+            row[i] = cos * a - sin * b;
+            row[j] = sin * a + cos * b;
+        }
+    }
+    u
+}
+
+fn transpose(
+    u: &[f64],
+    n: usize,
+) -> Vec<f64> {
+    (0..n * n).map(|k| u[(k % n) * n + k / n]).collect()
+}
+
+/// Random Hermitian sum of one- and two-electron terms.
+fn random_sum(n: u32) -> FermiSum {
+    let mut rng = rand::thread_rng();
+    let mut repr = SumRepr::new();
+    repr.add_term(Fermions::Offset, rng.gen_range(-1.0..1.0));
+    for p in 0..n {
+        for q in p..n {
+            repr.add_term(
+                Fermions::try_from((p, q)).unwrap(),
+                rng.gen_range(-1.0..1.0),
+            );
+        }
+    }
+    for p in 0..n {
+        for q in p + 1..n {
+            for s in p..n {
+                for r in s + 1..n {
+                    // Skip redundant adjoint codes
+                    if p == s && q > r {
+                        continue;
+                    }
+                    repr.add_term(
+                        Fermions::try_from((p, q, r, s)).unwrap(),
+                        rng.gen_range(-1.0..1.0),
+                    );
+                }
+            }
+        }
+    }
+    repr
+}
+
+#[test]
+fn rotation_identity() {
+    for n in 1..6 {
+        let mut u = vec![0.0; n * n];
+        for i in 0..n {
+            u[i * n + i] = 1.0;
+        }
+        let rotation = OrbitalRotation::new(n as u32, &u).unwrap();
+        let repr = random_sum(n as u32);
+
+        let mut rotated = rotation.rotate(&repr).unwrap();
+        rotated.prune(1e-14);
+
+        assert_eq!(rotated.len(), repr.len());
+        assert!(rotated.approx_eq(&repr, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn rotation_inverse() {
+    for n in 1..6 {
+        let u = random_orthogonal(n);
+        let rotation = OrbitalRotation::new(n as u32, &u).unwrap();
+        let inverse =
+            OrbitalRotation::new(n as u32, &transpose(&u, n)).unwrap();
+        let repr = random_sum(n as u32);
+
+        let mut restored =
+            inverse.rotate(&rotation.rotate(&repr).unwrap()).unwrap();
+        restored.prune(1e-10);
+
+        assert!(restored.approx_eq(&repr, 1e-10, 0.0));
+    }
+}
+
+#[test]
+fn rotation_spatial_integrals() {
+    let mut rng = rand::thread_rng();
+    for n in 1..4 {
+        let h1: Vec<f64> =
+            (0..n * n).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let h2: Vec<f64> = (0..n * n * n * n)
+            .map(|_| rng.gen_range(-1.0..1.0))
+            .collect();
+        let u = random_orthogonal(n);
+
+        // h'_ij = sum_pq U_pi U_qj h_pq, and likewise for (pq|rs)
+        let mut h1_rot = vec![0.0; n * n];
+        for (k, value) in h1_rot.iter_mut().enumerate() {
+            let (i, j) = (k / n, k % n);
+            for p in 0..n {
+                for q in 0..n {
+                    *value += u[p * n + i] * u[q * n + j] * h1[p * n + q];
+                }
+            }
+        }
+        let mut h2_rot = vec![0.0; n * n * n * n];
+        for (k, value) in h2_rot.iter_mut().enumerate() {
+            let (i, j, l, m) =
+                (k / (n * n * n), k / (n * n) % n, k / n % n, k % n);
+            for (t, h) in h2.iter().enumerate() {
+                let (p, q, r, s) =
+                    (t / (n * n * n), t / (n * n) % n, t / n % n, t % n);
+                *value += u[p * n + i]
+                    * u[q * n + j]
+                    * u[r * n + l]
+                    * u[s * n + m]
+                    * h;
+            }
+        }
+
+        let repr = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .constant(0.5)
+                .one_electron(&h1)
+                .two_electron(&h2),
+        )
+        .unwrap();
+        let expected = FermiSum::try_from(
+            Integrals::new(n as u32, Convention::Chemist)
+                .constant(0.5)
+                .one_electron(&h1_rot)
+                .two_electron(&h2_rot),
+        )
+        .unwrap();
+
+        let rotation = OrbitalRotation::spatial(n as u32, &u).unwrap();
+        assert_eq!(rotation.num_orbitals(), 2 * n as u32);
+        let rotated = rotation.rotate(&repr).unwrap();
+
+        assert!(rotated.approx_eq(&expected, 1e-10, 1e-10));
+        assert!(expected.approx_eq(&rotated, 1e-10, 1e-10));
+    }
+}
+
+#[test]
+fn rotation_spatial_blocks() {
+    for n in 1..4 {
+        let u = random_orthogonal(n);
+        let dim = 2 * n;
+        let mut spin_matrix = vec![0.0; dim * dim];
+        for p in 0..n {
+            for q in 0..n {
+                for sigma in Spin::both() {
+                    let i = Orbital::new(p as u32, sigma).index() as usize;
+                    let j = Orbital::new(q as u32, sigma).index() as usize;
+                    spin_matrix[i * dim + j] = u[p * n + q];
+                }
+            }
+        }
+        // Terms that do not conserve spin, too
+        let repr = random_sum(dim as u32);
+
+        let rotated = OrbitalRotation::spatial(n as u32, &u)
+            .unwrap()
+            .rotate(&repr)
+            .unwrap();
+        let expected = OrbitalRotation::new(dim as u32, &spin_matrix)
+            .unwrap()
+            .rotate(&repr)
+            .unwrap();
+
+        assert!(rotated.approx_eq(&expected, 1e-12, 0.0));
+        assert!(expected.approx_eq(&rotated, 1e-12, 0.0));
+    }
+}
+
+#[test]
+fn rotation_spectrum() {
+    let n = 6;
+    let repr = random_sum(n);
+    let rotation =
+        OrbitalRotation::new(n, &random_orthogonal(n as usize)).unwrap();
+    let rotated = rotation.rotate(&repr).unwrap();
+
+    let sector = Sector::new(n as u16, 3, None).unwrap();
+    let lowest = |repr: &FermiSum| {
+        let mtx =
+            Csr::from(Coo::from_fermions_in_sector(repr, &sector).unwrap());
+        Lanczos::new(1).solve(&mtx).unwrap().values[0]
+    };
+
+    assert!((lowest(&rotated) - lowest(&repr)).abs() < 1e-8);
+}
+
+#[test]
+fn rotation_errors() {
+    assert!(matches!(
+        OrbitalRotation::new(2, &[1.0, 0.0, 0.0]),
+        Err(Error::Rotation { .. })
+    ));
+    assert!(matches!(
+        OrbitalRotation::new(2, &[1.0, 1.0, 0.0, 1.0]),
+        Err(Error::Rotation { .. })
+    ));
+    assert!(matches!(
+        OrbitalRotation::spatial(2, &[1.0, 0.0, 0.0, 1.0, 0.0]),
+        Err(Error::Rotation { .. })
+    ));
+    assert!(matches!(
+        OrbitalRotation::spatial(1, &[2.0]),
+        Err(Error::Rotation { .. })
+    ));
+
+    let rotation = OrbitalRotation::new(2, &[1.0, 0.0, 0.0, 1.0]).unwrap();
+    let repr = SumRepr::from([(1.0, Fermions::try_from((0, 2)).unwrap())]);
+    assert!(matches!(
+        rotation.rotate(&repr),
+        Err(Error::Rotation { .. })
+    ));
+    assert!(rotation.rotate(&SumRepr::new()).unwrap().is_empty());
+}
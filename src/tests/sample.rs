@@ -0,0 +1,74 @@
+use rand::{
+    distributions::Distribution,
+    rngs::StdRng,
+    SeedableRng,
+};
+
+use crate::{
+    code::qubits::{
+        Paulis,
+        Sigma,
+    },
+    sample::{
+        AliasTable,
+        QDrift,
+    },
+    terms::SumRepr,
+};
+
+#[test]
+fn alias_table_invalid() {
+    assert!(AliasTable::new(&[]).is_none());
+    assert!(AliasTable::new(&[0.0, 0.0]).is_none());
+    assert!(AliasTable::new(&[1.0, -1.0]).is_none());
+    assert!(AliasTable::new(&[1.0, f64::NAN]).is_none());
+}
+
+#[test]
+fn alias_table_frequencies() {
+    let weights = [0.1, 0.0, 0.5, 0.2, 0.2];
+    let table = AliasTable::new(&weights).unwrap();
+    let mut rng = StdRng::seed_from_u64(17);
+
+    let num_samples = 200_000;
+    let mut counts = [0; 5];
+    for i in table.sample_iter(&mut rng).take(num_samples) {
+        counts[i] += 1;
+    }
+
+    assert_eq!(counts[1], 0);
+    for (count, weight) in counts.iter().zip(weights) {
+        let freq = f64::from(*count) / f64::from(num_samples as u32);
+        assert!((freq - weight).abs() < 0.01, "{freq} {weight}");
+    }
+}
+
+#[test]
+fn qdrift_reproducible() {
+    use Sigma::*;
+
+    let repr = SumRepr::from([
+        (0.3, Paulis::with_ops([X, X])),
+        (-0.2, Paulis::with_ops([Y, Y])),
+        (0.1, Paulis::with_ops([Z])),
+        (0.4, Paulis::with_ops([I, Z])),
+    ]);
+    let qdrift = QDrift::new(&repr).unwrap();
+
+    let seq1 = qdrift.sequence(50, 2.0, &mut StdRng::seed_from_u64(3));
+    let seq2 = qdrift.sequence(50, 2.0, &mut StdRng::seed_from_u64(3));
+    assert_eq!(seq1, seq2);
+
+    let tau: f64 = qdrift.lambda() * 2.0 / 50.0;
+    for (angle, code) in seq1 {
+        let coeff = repr.coeff(code).unwrap();
+        assert_eq!(angle, tau.copysign(*coeff));
+    }
+}
+
+#[test]
+fn qdrift_identity_only() {
+    let repr = SumRepr::from([(1.0, Paulis::identity())]);
+
+    assert!(QDrift::new(&repr).is_none());
+}
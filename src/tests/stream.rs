@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use crate::{
+    code::qubits::Paulis,
+    stream::SpillSum,
+    terms::{
+        PauliSum,
+        SumRepr,
+    },
+};
+
+fn spill_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("f2q-test-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn num_files(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+fn test_terms() -> Vec<(f64, Paulis)> {
+    (0..5000_u32)
+        .map(|i| {
+            let code =
+                Paulis::new((u64::from(i * 7919 % 1543), u64::from(i % 3)));
+            (f64::from(i % 17) - 8.0, code)
+        })
+        .collect()
+}
+
+#[test]
+fn spillsum_merge_01() {
+    let dir = spill_dir("merge_01");
+    let terms = test_terms();
+
+    let mut spill = SpillSum::new(&dir, 100);
+    for chunk in terms.chunks(333) {
+        spill.try_extend(chunk.iter().copied()).unwrap();
+    }
+    assert!(spill.num_runs() > 10);
+    assert_eq!(num_files(&dir), spill.num_runs());
+
+    let merge = spill.finish().unwrap();
+    let result: Vec<_> = merge.map(Result::unwrap).collect();
+    assert_eq!(num_files(&dir), 0);
+
+    let codes: Vec<_> = result.iter().map(|&(_, code)| code).collect();
+    assert!(codes.windows(2).all(|w| w[0] < w[1]));
+
+    let expected: PauliSum = terms.into_iter().collect();
+    assert_eq!(result.into_iter().collect::<SumRepr<_, _>>(), expected);
+
+    std::fs::remove_dir(dir).unwrap();
+}
+
+#[test]
+fn spillsum_in_memory() {
+    let dir = spill_dir("in_memory");
+    let terms = test_terms();
+
+    let mut spill = SpillSum::new(&dir, usize::MAX);
+    spill.try_extend(terms.iter().copied()).unwrap();
+    assert_eq!(spill.num_runs(), 0);
+
+    let result: PauliSum =
+        spill.finish().unwrap().map(Result::unwrap).collect();
+    assert_eq!(result, terms.into_iter().collect());
+
+    std::fs::remove_dir(dir).unwrap();
+}
+
+#[test]
+fn spillsum_drop_removes_runs() {
+    let dir = spill_dir("drop");
+
+    let mut spill = SpillSum::new(&dir, 10);
+    spill.try_extend(test_terms()).unwrap();
+    assert!(num_files(&dir) > 0);
+
+    drop(spill);
+    assert_eq!(num_files(&dir), 0);
+
+    let mut spill = SpillSum::new(&dir, 10);
+    spill.try_extend(test_terms()).unwrap();
+    let mut merge = spill.finish().unwrap();
+    let _ = merge.next();
+
+    drop(merge);
+    assert_eq!(num_files(&dir), 0);
+
+    std::fs::remove_dir(dir).unwrap();
+}
+
+#[test]
+fn spillsum_empty() {
+    let dir = spill_dir("empty");
+
+    let spill = SpillSum::new(&dir, 10);
+    assert_eq!(spill.finish().unwrap().count(), 0);
+
+    std::fs::remove_dir(dir).unwrap();
+}
@@ -1,7 +1,11 @@
 use crate::{
-    code::qubits::Paulis,
+    code::{
+        fermions::Fermions,
+        qubits::Paulis,
+    },
     terms::{
         HeapRepr,
+        PackedSum,
         StackRepr,
         SumRepr,
         Terms,
@@ -43,7 +47,7 @@ fn sumrepr_terms() {
 
     let mut elems = vec![];
     repr.add_to(&mut elems).unwrap();
-    elems.sort_by(|a, b| a.1.cmp(&b.1));
+    elems.sort_by_key(|&(_, code)| code);
 
     assert_eq!(elems, &[(1.0, 1), (2.0, 2)]);
 }
@@ -55,7 +59,7 @@ fn stackrepr_terms() {
 
     let mut elems = vec![];
     repr.add_to(&mut elems).unwrap();
-    elems.sort_by(|a, b| a.1.cmp(&b.1));
+    elems.sort_by_key(|&(_, code)| code);
 }
 
 #[test]
@@ -65,5 +69,362 @@ fn heaprepr_terms() {
 
     let mut elems = vec![];
     repr.add_to(&mut elems).unwrap();
-    elems.sort_by(|a, b| a.1.cmp(&b.1));
+    elems.sort_by_key(|&(_, code)| code);
+}
+
+#[test]
+fn sumrepr_prune_01() {
+    let mut repr = SumRepr::from([(1.0, 1), (-1e-14, 2), (1e-12, 3), (0.0, 4)]);
+    let dropped = repr.prune(1e-12);
+
+    assert_eq!(repr.len(), 2);
+    assert_eq!(repr.coeff(3), Some(&1e-12));
+    assert_eq!(dropped.len(), 2);
+    assert_eq!(dropped.coeff(2), Some(&-1e-14));
+    assert_eq!(dropped.coeff(4), Some(&0.0));
+}
+
+#[test]
+fn sumrepr_prune_by_norm_01() {
+    let mut repr = SumRepr::from([
+        (0.5, 1),
+        (-0.1, 2),
+        (0.1, 3),
+        (0.05, 4),
+        (f64::NAN, 5),
+    ]);
+
+    // Terms with equal magnitudes are dropped together or not at all
+    let dropped = repr.prune_by_norm(0.2);
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(dropped.coeff(4), Some(&0.05));
+    assert_eq!(repr.len(), 4);
+
+    let dropped = repr.prune_by_norm(0.2);
+    assert_eq!(dropped.len(), 2);
+    assert!(dropped.iter().all(|(coeff, _)| coeff.abs() == 0.1));
+
+    let dropped = repr.prune_by_norm(f64::INFINITY);
+    assert_eq!(dropped.coeff(1), Some(&0.5));
+    assert_eq!(repr.len(), 1);
+    assert!(repr.coeff(5).unwrap().is_nan());
+}
+
+#[test]
+fn sumrepr_prune_by_norm_budget() {
+    let mut repr: SumRepr<f64, u64> =
+        (1..=100).map(|i| (f64::from(i as u32) * 1e-3, i)).collect();
+    let budget = 0.5;
+    let dropped = repr.prune_by_norm(budget);
+
+    let norm: f64 = dropped.iter().map(|(coeff, _)| coeff.abs()).sum();
+    assert!(norm <= budget);
+    // 1 + 2 + ... + 31 = 496
+    assert_eq!(dropped.len(), 31);
+    assert_eq!(repr.len() + dropped.len(), 100);
+}
+
+#[test]
+fn sumrepr_add_sub_01() {
+    let a = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    let b = SumRepr::from([(0.5, 2), (3.0, 3)]);
+
+    assert_eq!(
+        a.clone() + &b,
+        SumRepr::from([(1.0, 1), (2.5, 2), (3.0, 3)])
+    );
+    assert_eq!(a.clone() + b.clone(), b.clone() + a.clone());
+    assert_eq!(
+        a.clone() - b.clone(),
+        SumRepr::from([(1.0, 1), (1.5, 2), (-3.0, 3)])
+    );
+    assert_eq!(a.clone() - &b, a.clone() + (-b));
+}
+
+#[test]
+fn sumrepr_sub_cancel() {
+    let a = SumRepr::from([(1.0, 1), (2.0, 2)]);
+
+    let diff = a.clone() - &a;
+    assert_eq!(diff.len(), 2);
+    assert!(diff.iter().all(|(&coeff, _)| coeff == 0.0));
+}
+
+#[test]
+fn sumrepr_add_assign() {
+    let mut repr = SumRepr::from([(1.0, 1)]);
+    repr += SumRepr::from([(1.0, 1), (1.0, 2)]);
+    repr += &SumRepr::from([(1.0, 2)]);
+    repr -= SumRepr::from([(0.5, 3)]);
+    repr -= &SumRepr::from([(0.5, 3)]);
+
+    assert_eq!(repr, SumRepr::from([(2.0, 1), (2.0, 2), (-1.0, 3)]));
+}
+
+#[test]
+fn sumrepr_scalar_mul_div() {
+    let repr = SumRepr::from([(1.0, 1), (-2.0, 2)]);
+
+    assert_eq!(repr.clone() * 2.0, SumRepr::from([(2.0, 1), (-4.0, 2)]));
+    assert_eq!(2.0 * repr.clone(), repr.clone() * 2.0);
+    assert_eq!(repr.clone() / 2.0, SumRepr::from([(0.5, 1), (-1.0, 2)]));
+
+    let mut scaled = repr.clone();
+    scaled *= 3.0;
+    scaled /= 3.0;
+    assert_eq!(scaled, repr);
+}
+
+#[test]
+fn sumrepr_linear_combination() {
+    let a = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    let b = SumRepr::from([(1.0, 2), (1.0, 3)]);
+
+    let repr = SumRepr::linear_combination([(0.5, &a), (2.0, &b), (1.0, &a)]);
+    assert_eq!(repr, a.clone() * 1.5 + b.clone() * 2.0);
+
+    let empty = SumRepr::<f64, u64>::linear_combination([]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn sumrepr_compact_01() {
+    let mut repr = SumRepr::from([(0.0, 1), (-0.0, 2), (1e-3, 3), (1.0, 4)]);
+
+    assert_eq!(repr.compact(0.0), 2);
+    assert_eq!(repr, SumRepr::from([(1e-3, 3), (1.0, 4)]));
+    assert_eq!(repr.compact(1e-3), 1);
+    assert_eq!(repr, SumRepr::from([(1.0, 4)]));
+}
+
+#[test]
+fn sumrepr_compact_nan() {
+    let mut repr = SumRepr::from([(f64::NAN, 1), (0.0, 2)]);
+
+    assert_eq!(repr.compact(f64::INFINITY), 1);
+    assert!(repr.coeff(1).unwrap().is_nan());
+}
+
+#[test]
+fn sumrepr_compacting_01() {
+    let terms = [(1.0, 1), (0.5, 2), (-1.0, 1), (0.25, 3), (-0.25, 3)];
+
+    let mut repr = SumRepr::new();
+    repr.compacting(0.0).extend(terms);
+    assert_eq!(repr, SumRepr::from([(0.5, 2)]));
+
+    let mut expected = SumRepr::from(terms);
+    expected.compact(0.0);
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn sumrepr_compacting_reinsert() {
+    let mut repr = SumRepr::new();
+    repr.compacting(1e-12)
+        .extend([(1.0, 1), (-1.0, 1), (2.0, 1), (1e-13, 2)]);
+
+    assert_eq!(repr, SumRepr::from([(2.0, 1)]));
+}
+
+#[test]
+fn packedsum_extend_01() {
+    let mut repr = PackedSum::new();
+    repr.extend([(1.0, Paulis::new((3, 0))), (2.0, Paulis::new((1, 0)))]);
+    repr.extend([
+        (1.0, Paulis::new((2, 0))),
+        (1.0, Paulis::new((3, 0))),
+        (1.0, Paulis::new((0, 1))),
+    ]);
+    repr.extend([]);
+
+    let terms: Vec<_> =
+        repr.iter().map(|(&coeff, code)| (coeff, code)).collect();
+    assert_eq!(
+        terms,
+        &[
+            (2.0, Paulis::new((1, 0))),
+            (1.0, Paulis::new((2, 0))),
+            (2.0, Paulis::new((3, 0))),
+            (1.0, Paulis::new((0, 1))),
+        ]
+    );
+}
+
+#[test]
+fn packedsum_matches_sumrepr() {
+    let mut packed = PackedSum::new();
+    let mut expected = SumRepr::new();
+    for i in 0..20_u64 {
+        let batch: Vec<_> = (0..50_u64)
+            .map(|j| {
+                let code = Paulis::new(((i * 31 + j * 17) % 97, j % 3));
+                (f64::from(u32::try_from(j).unwrap()), code)
+            })
+            .collect();
+        expected.extend(batch.iter().copied());
+        packed.extend(batch);
+    }
+
+    assert_eq!(packed.len(), expected.len());
+    for (coeff, code) in expected.iter() {
+        assert_eq!(packed.coeff(*code), Some(coeff));
+    }
+    let codes: Vec<_> = packed.iter().map(|(_, code)| code).collect();
+    assert!(codes.windows(2).all(|w| w[0] < w[1]));
+
+    assert_eq!(SumRepr::from(packed.clone()), expected);
+    assert_eq!(PackedSum::from(expected), packed);
+}
+
+#[test]
+fn packedsum_terms() {
+    let packed = PackedSum::from_iter([
+        (1.0, Paulis::new((1, 0))),
+        (2.0, Paulis::new((2, 0))),
+    ]);
+
+    let mut repr = SumRepr::new();
+    (&packed).add_to(&mut repr).unwrap();
+    packed.add_to(&mut repr).unwrap();
+
+    assert_eq!(
+        repr,
+        SumRepr::from([(2.0, Paulis::new((1, 0))), (4.0, Paulis::new((2, 0)))])
+    );
+}
+
+#[derive(Debug, PartialEq)]
+struct CustomError;
+
+struct Failing;
+
+impl Terms<(f64, u64)> for Failing {
+    type Error = CustomError;
+
+    fn add_to(
+        self,
+        repr: &mut impl Extend<(f64, u64)>,
+    ) -> Result<(), Self::Error> {
+        repr.extend([(1.0, 1)]);
+        Err(CustomError)
+    }
+}
+
+#[test]
+fn terms_adapters_error() {
+    let mut repr = SumRepr::new();
+    let err = Failing
+        .scale(2.0)
+        .map_code(|code| code + 1)
+        .filter(|_| true)
+        .add_to(&mut repr)
+        .unwrap_err();
+
+    assert_eq!(err, CustomError);
+    assert_eq!(repr, SumRepr::from([(2.0, 2)]));
+}
+
+#[test]
+fn terms_adapters_pipeline() {
+    let repr = SumRepr::from([(1.0, 1), (0.01, 2), (-4.0, 3)]);
+
+    let mut result = SumRepr::new();
+    (&repr)
+        .map_coeff(|coeff: f64| coeff.abs())
+        .scale(0.5)
+        .filter(|&(coeff, _)| coeff > 0.1)
+        .map_code(|code| code * 10)
+        .chain(&repr)
+        .add_to(&mut result)
+        .unwrap();
+
+    assert_eq!(
+        result,
+        SumRepr::from([(0.5, 10), (2.0, 30), (1.0, 1), (0.01, 2), (-4.0, 3)])
+    );
+}
+
+#[test]
+fn terms_take_while_across_batches() {
+    let mut iter = (1..10_u32).map(|i| (f64::from(i), u64::from(i)));
+    let repr = StackRepr::new(|| iter.next());
+
+    let mut result = SumRepr::new();
+    repr.take_while(|&(_, code)| code != 4)
+        .add_to(&mut result)
+        .unwrap();
+
+    assert_eq!(result, SumRepr::from([(1.0, 1), (2.0, 2), (3.0, 3)]));
+}
+
+#[test]
+fn sumrepr_approx_eq_01() {
+    let a = SumRepr::from([(1.0, 1), (2.0, 2)]);
+    let b = SumRepr::from([(1.0 + 1e-10, 1), (2.0, 2), (1e-13, 3)]);
+
+    assert!(a.approx_eq(&a, 0.0, 0.0));
+    assert!(a.approx_eq(&b, 1e-9, 0.0));
+    assert!(b.approx_eq(&a, 1e-9, 0.0));
+    assert!(a.approx_eq(&b, 1e-12, 1e-9));
+    assert!(!a.approx_eq(&b, 1e-12, 0.0));
+    assert!(!b.approx_eq(&a, 1e-12, 0.0));
+}
+
+#[test]
+fn sumrepr_approx_eq_nan() {
+    let a = SumRepr::from([(f64::NAN, 1)]);
+
+    assert!(!a.approx_eq(&a, 1.0, 1.0));
+    assert!(!a.approx_eq(&SumRepr::new(), 1.0, 1.0));
+    assert!(!SumRepr::new().approx_eq(&a, 1.0, 1.0));
+}
+
+#[test]
+fn sumrepr_diff_paulis() {
+    let a = SumRepr::from([
+        (1.0, Paulis::new((0, 0))),
+        (0.5, Paulis::new((1, 0))),
+        (0.25, Paulis::new((2, 0))),
+        (1e-14, Paulis::new((3, 0))),
+    ]);
+    let b = SumRepr::from([
+        (1.0, Paulis::new((0, 0))),
+        (-0.5, Paulis::new((1, 0))),
+        (0.125, Paulis::new((5, 0))),
+        (0.125, Paulis::new((4, 0))),
+    ]);
+
+    let diff = a.diff(&b, 1e-12, 0.0);
+    assert_eq!(diff.len(), 4);
+    assert_eq!(diff.left_only, &[(0.25, Paulis::new((2, 0)))]);
+    assert_eq!(
+        diff.right_only,
+        &[(0.125, Paulis::new((4, 0))), (0.125, Paulis::new((5, 0)))]
+    );
+    assert_eq!(diff.changed, &[(0.5, -0.5, Paulis::new((1, 0)))]);
+
+    let diff = b.diff(&a, 1e-12, 0.0);
+    assert_eq!(diff.left_only.len(), 2);
+    assert_eq!(diff.right_only.len(), 1);
+    assert_eq!(diff.changed, &[(-0.5, 0.5, Paulis::new((1, 0)))]);
+
+    assert!(a.diff(&a, 0.0, 0.0).is_empty());
+}
+
+#[test]
+fn sumrepr_diff_fermions() {
+    let one = Fermions::try_from((0, 1)).unwrap();
+    let two = Fermions::try_from((0, 1, 1, 0)).unwrap();
+    let a = SumRepr::from([(1.0, Fermions::Offset), (1.0, one), (2.0, two)]);
+    let b = SumRepr::from([(1.0, Fermions::Offset), (1.0 + 1e-6, one)]);
+
+    let diff = a.diff(&b, 0.0, 1e-3);
+    assert_eq!(diff.left_only, &[(2.0, two)]);
+    assert!(diff.right_only.is_empty());
+    assert!(diff.changed.is_empty());
+
+    let diff = a.diff(&b, 0.0, 1e-9);
+    assert_eq!(diff.changed, &[(1.0, 1.0 + 1e-6, one)]);
+    assert!(!a.approx_eq(&b, 0.0, 1e-9));
 }
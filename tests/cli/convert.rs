@@ -0,0 +1,135 @@
+use f2q::terms::PauliSum;
+
+use super::{
+    run_f2q,
+    run_f2q_err,
+};
+
+#[test]
+fn cli_convert_stream_text_output() {
+    let input = r#"{"type":"sumrepr","encoding":"fermions","terms":[]}"#;
+    for format in ["fcidump", "openfermion", "qiskit"] {
+        let stderr = run_f2q_err(
+            &[
+                "convert",
+                "--mapping",
+                "jordan-wigner",
+                "--stream",
+                "--output-format",
+                format,
+            ],
+            input,
+        );
+
+        assert!(
+            stderr.contains(
+                "streaming conversion supports only json, yaml and toml output"
+            ),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn cli_convert_missing_mapping() {
+    let input = r#"{"type":"sumrepr","encoding":"fermions","terms":[]}"#;
+    let stderr = run_f2q_err(&["convert"], input);
+
+    assert!(
+        stderr
+            .contains("mapping must be specified to convert between encodings"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn cli_convert_stream_json_input_only() {
+    let stderr = run_f2q_err(
+        &[
+            "convert",
+            "--mapping",
+            "jordan-wigner",
+            "--stream",
+            "--input-format",
+            "yaml",
+        ],
+        "type: sumrepr\nencoding: fermions\nterms: []\n",
+    );
+
+    assert!(
+        stderr.contains("streaming conversion supports only json input"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn cli_convert_stream_matches_in_memory() {
+    let input = run_f2q(
+        &[
+            "generate",
+            "--random",
+            "--encoding",
+            "fermions",
+            "--max-orbital-index",
+            "11",
+            "500",
+        ],
+        "",
+    );
+    let temp_dir = std::env::temp_dir()
+        .join(format!("f2q-cli-test-{}-stream", std::process::id()));
+    std::fs::create_dir_all(&temp_dir).unwrap();
+
+    let expected: PauliSum = serde_json::from_str(&run_f2q(
+        &["convert", "--mapping", "jordan-wigner"],
+        &input,
+    ))
+    .unwrap();
+    let result: PauliSum = serde_json::from_str(&run_f2q(
+        &[
+            "convert",
+            "--mapping",
+            "jordan-wigner",
+            "--stream",
+            "--chunk-size",
+            "7",
+            "--max-terms",
+            "50",
+            "--temp-dir",
+            temp_dir.to_str().unwrap(),
+        ],
+        &input,
+    ))
+    .unwrap();
+
+    assert!(expected.len() > 50, "input should spill to disk");
+    // Equal up to the round-off of summation in a different order
+    assert!(result.approx_eq(&expected, 1e-12, 1e-12));
+    assert!(expected.approx_eq(&result, 1e-12, 1e-12));
+    assert_eq!(std::fs::read_dir(&temp_dir).unwrap().count(), 0);
+    std::fs::remove_dir(&temp_dir).unwrap();
+}
+
+#[test]
+fn cli_convert_text_output_sort() {
+    let input = r#"{"type":"sumrepr","encoding":"qubits","terms":[]}"#;
+    for format in ["openfermion", "qiskit"] {
+        let stderr = run_f2q_err(
+            &[
+                "convert",
+                "--input-encoding",
+                "qubits",
+                "--output-format",
+                format,
+                "--sort",
+                "magnitude",
+            ],
+            input,
+        );
+
+        assert!(
+            stderr.contains("output supports only the order of codes"),
+            "{stderr}"
+        );
+    }
+}
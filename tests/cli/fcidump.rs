@@ -0,0 +1,121 @@
+use f2q::{
+    fcidump::Fcidump,
+    terms::{
+        FermiSum,
+        Terms,
+    },
+};
+
+use super::run_f2q;
+
+const H2_STO3G: &str = " &FCI NORB=   2,NELEC= 2,MS2=0,
+  ORBSYM=1,5,
+  ISYM=1,
+ &END
+  0.6744887663568382     1    1    1    1
+  0.1812104620151        2    1    2    1
+  0.6634114006126878     2    2    1    1
+  0.6973979494693556     2    2    2    2
+ -1.2524635735648981     1    1    0    0
+ -0.4759487172683919     2    2    0    0
+  0.7137539936876182     0    0    0    0
+";
+
+fn to_fermisum(fcidump: &Fcidump) -> FermiSum {
+    let mut repr = FermiSum::new();
+    fcidump.add_to(&mut repr).unwrap();
+    repr
+}
+
+const PRUNE_FCIDUMP: [&str; 8] = [
+    "prune",
+    "--input-encoding",
+    "fermions",
+    "--input-format",
+    "fcidump",
+    "--output-format",
+    "fcidump",
+    "--threshold=0",
+];
+
+#[test]
+fn cli_prune_fcidump_keeps_header() {
+    let input: Fcidump = H2_STO3G.parse().unwrap();
+
+    let output: Fcidump = run_f2q(&PRUNE_FCIDUMP, H2_STO3G).parse().unwrap();
+
+    assert_eq!(output.header, input.header);
+    assert!(to_fermisum(&output).approx_eq(&to_fermisum(&input), 1e-12, 0.0));
+}
+
+const CONVERT_FCIDUMP: [&str; 7] = [
+    "convert",
+    "--input-encoding",
+    "fermions",
+    "--input-format",
+    "fcidump",
+    "--output-encoding",
+    "fermions",
+];
+
+#[test]
+fn cli_convert_fcidump_keeps_header() {
+    let input: Fcidump = H2_STO3G.parse().unwrap();
+    let mut args = CONVERT_FCIDUMP.to_vec();
+    args.extend(["--output-format", "fcidump"]);
+
+    let output: Fcidump = run_f2q(&args, H2_STO3G).parse().unwrap();
+
+    assert_eq!(output.header, input.header);
+    assert!(to_fermisum(&output).approx_eq(&to_fermisum(&input), 1e-12, 0.0));
+}
+
+#[test]
+fn cli_convert_fcidump_nelec_ms2() {
+    let mut args = CONVERT_FCIDUMP.to_vec();
+    args.extend(["--output-format", "fcidump", "--nelec", "1", "--ms2", "-1"]);
+
+    let output: Fcidump = run_f2q(&args, H2_STO3G).parse().unwrap();
+
+    assert_eq!(output.header.nelec, 1);
+    assert_eq!(output.header.ms2, -1);
+    assert_eq!(output.header.orbsym, &[1, 5]);
+}
+
+#[test]
+fn cli_convert_fcidump_to_json() {
+    let input: Fcidump = H2_STO3G.parse().unwrap();
+    let mut args = CONVERT_FCIDUMP.to_vec();
+    args.extend(["--output-format", "json"]);
+
+    let output: FermiSum =
+        serde_json::from_str(&run_f2q(&args, H2_STO3G)).unwrap();
+
+    assert!(output.approx_eq(&to_fermisum(&input), 1e-12, 0.0));
+}
+
+#[test]
+fn cli_convert_json_to_fcidump() {
+    let input: Fcidump = H2_STO3G.parse().unwrap();
+    let json = serde_json::to_string(&to_fermisum(&input)).unwrap();
+
+    let output: Fcidump = run_f2q(
+        &[
+            "convert",
+            "--output-encoding",
+            "fermions",
+            "--output-format",
+            "fcidump",
+            "--nelec",
+            "2",
+        ],
+        &json,
+    )
+    .parse()
+    .unwrap();
+
+    assert_eq!(output.header.norb, 2);
+    assert_eq!(output.header.nelec, 2);
+    assert_eq!(output.header.ms2, 0);
+    assert!(to_fermisum(&output).approx_eq(&to_fermisum(&input), 1e-12, 0.0));
+}
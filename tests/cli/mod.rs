@@ -0,0 +1,52 @@
+use std::{
+    io::Write,
+    process::{
+        Command,
+        Output,
+        Stdio,
+    },
+};
+
+mod convert;
+mod fcidump;
+
+/// Run `f2q` with `input` passed to STDIN.
+fn spawn_f2q(
+    args: &[&str],
+    input: &str,
+) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_f2q"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The command may fail before reading its input
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+/// Run `f2q` successfully, and return STDOUT.
+pub fn run_f2q(
+    args: &[&str],
+    input: &str,
+) -> String {
+    let output = spawn_f2q(args, input);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Run `f2q` expecting failure, and return STDERR.
+pub fn run_f2q_err(
+    args: &[&str],
+    input: &str,
+) -> String {
+    let output = spawn_f2q(args, input);
+    assert!(!output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
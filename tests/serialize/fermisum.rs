@@ -7,6 +7,7 @@ use f2q::{
     },
     terms::{
         FermiSum,
+        SortOrder,
         SumRepr,
     },
 };
@@ -142,6 +143,29 @@ fn fermisum_serialize_04() {
     assert_eq!(arr.len(), 3);
 }
 
+#[test]
+fn fermisum_serialize_sorted() {
+    let repr = SumRepr::from([
+        (0.3, Fermions::try_from((0, 1, 1, 0)).unwrap()),
+        (-0.2, Fermions::try_from((1, 2)).unwrap()),
+        (0.1, Fermions::Offset),
+        (0.4, Fermions::try_from((0, 2)).unwrap()),
+    ]);
+
+    let json = serde_json::to_string(&repr).unwrap();
+    assert_eq!(
+        json,
+        r#"{"type":"sumrepr","encoding":"fermions","terms":[{"code":[],"value":0.1},{"code":[0,2],"value":0.4},{"code":[1,2],"value":-0.2},{"code":[0,1,1,0],"value":0.3}]}"#
+    );
+
+    let json =
+        serde_json::to_string(&repr.sorted(SortOrder::Magnitude)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"type":"sumrepr","encoding":"fermions","terms":[{"code":[0,2],"value":0.4},{"code":[0,1,1,0],"value":0.3},{"code":[1,2],"value":-0.2},{"code":[],"value":0.1}]}"#
+    );
+}
+
 #[test]
 #[allow(clippy::float_cmp)]
 fn fermisum_deserialize_01() {
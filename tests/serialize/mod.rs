@@ -3,3 +3,4 @@ mod fermisum;
 mod pauli;
 mod pauliop;
 mod paulisum;
+mod stream;
@@ -5,6 +5,7 @@ use f2q::{
     },
     terms::{
         PauliSum,
+        SortOrder,
         SumRepr,
     },
 };
@@ -118,6 +119,47 @@ fn paulisum_serialize_04() {
     assert_eq!(arr.len(), 3);
 }
 
+#[test]
+fn paulisum_serialize_sorted() {
+    let codes = ["ZZ", "X", "IIY", "I", "XY", "IZ"];
+    let repr: PauliSum = codes
+        .iter()
+        .enumerate()
+        .map(|(i, code)| {
+            (
+                f64::from(i as u8) - 2.5,
+                serde_json::from_str(&format!("\"{code}\"")).unwrap(),
+            )
+        })
+        .collect();
+
+    // Deterministic output, in the order of codes
+    let json = serde_json::to_value(&repr).unwrap();
+    let Value::Array(arr) = &json["terms"] else {
+        panic!()
+    };
+    let sorted: Vec<_> = arr
+        .iter()
+        .map(|term| term["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(sorted, &["I", "X", "XY", "IZ", "ZZ", "IIY"]);
+    let rebuilt: PauliSum = repr.iter().map(|(&t, &k)| (t, k)).collect();
+    assert_eq!(
+        serde_json::to_string(&repr).unwrap(),
+        serde_json::to_string(&rebuilt).unwrap()
+    );
+
+    let json = serde_json::to_value(repr.sorted(SortOrder::Magnitude)).unwrap();
+    let Value::Array(arr) = &json["terms"] else {
+        panic!()
+    };
+    let sorted: Vec<_> = arr
+        .iter()
+        .map(|term| term["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(sorted, &["IZ", "ZZ", "X", "XY", "I", "IIY"]);
+}
+
 #[test]
 #[allow(clippy::float_cmp)]
 fn paulisum_deserialize_01() {
@@ -0,0 +1,158 @@
+use f2q::{
+    code::{
+        fermions::Fermions,
+        qubits::{
+            Paulis,
+            Sigma,
+        },
+    },
+    stream::{
+        FermiTerms,
+        PauliTerms,
+    },
+    terms::{
+        FermiSum,
+        PauliSum,
+        SumRepr,
+    },
+};
+use serde::de::DeserializeSeed;
+
+const FERMISUM_JSON: &str = r#"
+{
+    "encoding": "fermions",
+    "terms": [
+        { "code": [], "value": 0.5 },
+        { "code": [0, 1], "value": 1.0 },
+        { "code": [0, 1, 3, 2], "value": -0.25 },
+        { "code": [0, 1], "value": 1.0 }
+    ],
+    "comment": "ignored",
+    "type": "sumrepr"
+}
+"#;
+
+fn read_terms(input: &str) -> Result<Vec<(f64, Fermions)>, serde_json::Error> {
+    let mut terms = vec![];
+    FermiTerms::new(|coeff, code| {
+        terms.push((coeff, code));
+        Ok::<_, String>(())
+    })
+    .deserialize(&mut serde_json::Deserializer::from_str(input))?;
+    Ok(terms)
+}
+
+#[test]
+fn fermiterms_deserialize_01() {
+    let terms = read_terms(FERMISUM_JSON).unwrap();
+    assert_eq!(terms.len(), 4);
+
+    let repr: FermiSum = terms.into_iter().collect();
+    let expected: FermiSum = serde_json::from_str(FERMISUM_JSON).unwrap();
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn fermiterms_deserialize_yaml() {
+    let expected: FermiSum = serde_json::from_str(FERMISUM_JSON).unwrap();
+    let yaml = serde_yaml::to_string(&expected).unwrap();
+
+    let mut repr = SumRepr::new();
+    FermiTerms::new(|coeff: f64, code| {
+        repr.add_term(code, coeff);
+        Ok::<_, String>(())
+    })
+    .deserialize(serde_yaml::Deserializer::from_str(&yaml))
+    .unwrap();
+
+    assert_eq!(repr, expected);
+}
+
+#[test]
+fn fermiterms_deserialize_errors() {
+    let wrong_encoding = r#"{"type":"sumrepr","encoding":"qubits","terms":[]}"#;
+    let err = read_terms(wrong_encoding).unwrap_err();
+    assert!(err.to_string().contains("encoding should be: 'fermions'"));
+
+    let missing_terms = r#"{"type":"sumrepr","encoding":"fermions"}"#;
+    let err = read_terms(missing_terms).unwrap_err();
+    assert!(err.to_string().contains("missing field `terms`"));
+
+    let wrong_code = r#"{"type":"sumrepr","encoding":"fermions",
+        "terms":[{"code":[1,0],"value":1.0}]}"#;
+    assert!(read_terms(wrong_code).is_err());
+}
+
+#[test]
+fn fermiterms_callback_error() {
+    let mut count = 0;
+    let err = FermiTerms::new(|_: f64, _| {
+        count += 1;
+        if count == 2 {
+            Err("stop")
+        } else {
+            Ok(())
+        }
+    })
+    .deserialize(&mut serde_json::Deserializer::from_str(FERMISUM_JSON))
+    .unwrap_err();
+
+    assert_eq!(count, 2);
+    assert!(err.to_string().contains("stop"));
+}
+
+#[test]
+fn pauliterms_serialize_01() {
+    let repr = PauliSum::from([
+        (0.5, Paulis::identity()),
+        (-1.0, Paulis::with_ops([Sigma::X, Sigma::Y])),
+        (0.25, Paulis::with_ops([Sigma::I, Sigma::Z])),
+    ]);
+    let mut terms: Vec<_> = repr.iter().map(|(&c, &p)| (c, p)).collect();
+    terms.sort_by_key(|&(_, code)| code);
+
+    let stream = PauliTerms::new(terms.into_iter().map(Ok::<_, String>));
+    assert_eq!(
+        serde_json::to_string(&stream).unwrap(),
+        serde_json::to_string(&repr).unwrap()
+    );
+
+    // The iterator has been consumed.
+    assert!(serde_json::to_string(&stream).is_err());
+}
+
+#[test]
+fn pauliterms_serialize_error() {
+    let stream = PauliTerms::new(
+        [Ok((0.5, Paulis::identity())), Err("broken run")].into_iter(),
+    );
+    let err = serde_json::to_string(&stream).unwrap_err();
+
+    assert!(err.to_string().contains("broken run"));
+}
+
+#[test]
+fn pauliterms_serialize_formats() {
+    let repr = PauliSum::from([
+        (0.5, Paulis::identity()),
+        (0.25, Paulis::with_ops([Sigma::I, Sigma::Z])),
+    ]);
+    let terms = || {
+        PauliTerms::new(
+            [
+                Ok::<_, String>((0.5, Paulis::identity())),
+                Ok((0.25, Paulis::with_ops([Sigma::I, Sigma::Z]))),
+            ]
+            .into_iter(),
+        )
+    };
+
+    assert_eq!(
+        serde_yaml::to_string(&terms()).unwrap(),
+        serde_yaml::to_string(&repr).unwrap()
+    );
+    assert_eq!(
+        toml::to_string(&terms()).unwrap(),
+        toml::to_string(&repr).unwrap()
+    );
+}